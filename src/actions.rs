@@ -0,0 +1,58 @@
+pub const WORKFLOW_PATH: &str = ".github/workflows/github-grid.yml";
+
+// GitHub Actions workflow that installs the tool and runs it against the
+// checked-out grid repo on a cron schedule. Pushes use the GRID_TOKEN repo
+// secret when present, falling back to the workflow's own GITHUB_TOKEN.
+pub fn workflow_yaml(cron: &str, name: &str, email: &str, args: &[String]) -> String {
+    let command = args.iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        r#"name: github-grid
+
+on:
+  schedule:
+    - cron: "{cron}"
+  workflow_dispatch:
+
+permissions:
+  contents: write
+
+jobs:
+  catch-up:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+          token: ${{{{ secrets.GRID_TOKEN || github.token }}}}
+
+      - name: Install github-grid
+        run: cargo install --locked --git https://github.com/nijaru/github-grid
+
+      - name: Configure commit identity
+        run: |
+          git config --global user.name {name}
+          git config --global user.email {email}
+
+      - name: Generate commits
+        env:
+          GH_TOKEN: ${{{{ secrets.GRID_TOKEN || github.token }}}}
+        run: github-grid {command}
+"#,
+        cron = cron,
+        name = shell_quote(name),
+        email = shell_quote(email),
+        command = command,
+    )
+}
+
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./@:=,+".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
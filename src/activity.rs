@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDate;
+use rand::{rng, Rng};
+use serde::Deserialize;
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{create_commit_at_time, separate_timestamps, CommitInfo};
+
+// RescueTime category counted as coding time
+const CODING_CATEGORY: &str = "software development";
+// Hours that commits added to a day are spread over
+const WORKING_HOURS: (u32, u32) = (9, 22);
+
+// Seconds of tracked coding time per day, from a WakaTime or RescueTime export
+#[derive(Debug, Clone, Default)]
+pub struct CodingTime {
+    seconds: BTreeMap<NaiveDate, f64>,
+}
+
+#[derive(Deserialize)]
+struct WakaTimeExport {
+    #[serde(alias = "data")]
+    days: Vec<WakaTimeDay>,
+}
+
+// The data export dates each day directly, the API under `range`
+#[derive(Deserialize)]
+struct WakaTimeDay {
+    date: Option<String>,
+    range: Option<WakaTimeRange>,
+    grand_total: Option<WakaTimeTotal>,
+}
+
+#[derive(Deserialize)]
+struct WakaTimeRange {
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WakaTimeTotal {
+    #[serde(default)]
+    total_seconds: f64,
+}
+
+impl CodingTime {
+    // WakaTime's data export (`{"days": [...]}`) or a summaries API response
+    // (`{"data": [...]}`), using each day's grand_total
+    pub fn from_wakatime_json(content: &str) -> Result<Self> {
+        let invalid = |what: &str| GitHubGridError::Parse(format!("Invalid WakaTime export: {}", what));
+        let export: WakaTimeExport = serde_json::from_str(content).map_err(|e| invalid(&e.to_string()))?;
+
+        let mut seconds = BTreeMap::new();
+        for day in export.days {
+            let date = day.date.or_else(|| day.range.and_then(|range| range.date))
+                .ok_or_else(|| invalid("day without a date"))?;
+            let date = NaiveDate::parse_from_str(date.get(..10).unwrap_or(&date), "%Y-%m-%d")
+                .map_err(|_| invalid(&format!("bad date '{}'", date)))?;
+            let total = day.grand_total.map_or(0.0, |total| total.total_seconds);
+            *seconds.entry(date).or_insert(0.0) += total;
+        }
+        Ok(Self { seconds })
+    }
+
+    // RescueTime's analytic data CSV (Date, Time Spent (seconds), ...,
+    // Category). Only Software Development rows count when categories are present.
+    pub fn from_rescuetime_csv(content: &str) -> Result<Self> {
+        let invalid = |what: &str| GitHubGridError::Parse(format!("Invalid RescueTime export: {}", what));
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let header = reader.headers().map_err(|e| invalid(&e.to_string()))?.clone();
+        if header.iter().all(str::is_empty) {
+            return Err(invalid("empty file"));
+        }
+        let column = |name: &str| header.iter().position(|h| h.eq_ignore_ascii_case(name));
+        let date_col = column("Date").ok_or_else(|| invalid("no Date column"))?;
+        let time_col = column("Time Spent (seconds)").ok_or_else(|| invalid("no \"Time Spent (seconds)\" column"))?;
+        let category_col = column("Category");
+
+        let mut seconds = BTreeMap::new();
+        for record in reader.records() {
+            let fields = record.map_err(|e| invalid(&e.to_string()))?;
+            let field = |i: usize| fields.get(i).unwrap_or("");
+            if category_col.is_some_and(|i| !field(i).to_lowercase().contains(CODING_CATEGORY)) {
+                continue;
+            }
+            let date = field(date_col);
+            let date = NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d")
+                .map_err(|_| invalid(&format!("bad date '{}'", date)))?;
+            let spent: f64 = field(time_col).parse().map_err(|_| invalid(&format!("bad time '{}'", field(time_col))))?;
+            *seconds.entry(date).or_insert(0.0) += spent;
+        }
+        if seconds.is_empty() {
+            return Err(invalid("no Software Development time found"));
+        }
+        Ok(Self { seconds })
+    }
+
+    // By extension: .json for WakaTime, .csv for RescueTime
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_wakatime_json(&content),
+            Some("csv") => Self::from_rescuetime_csv(&content),
+            _ => Err(GitHubGridError::Config(format!(
+                "Don't know the format of {}: expected a WakaTime .json or RescueTime .csv export", path.display()
+            ))),
+        }
+    }
+
+    // First and last day the export covers
+    pub fn coverage(&self) -> Option<(NaiveDate, NaiveDate)> {
+        Some((*self.seconds.keys().next()?, *self.seconds.keys().next_back()?))
+    }
+
+    // Moves commits between days so each covered day gets a share of the
+    // commits proportional to its coding time. The total stays the same, and
+    // days outside the export's coverage keep their commits.
+    pub fn shape(&self, commits: Vec<CommitInfo>, days: &[NaiveDate]) -> Vec<CommitInfo> {
+        let Some((first, last)) = self.coverage() else {
+            return commits;
+        };
+        let covered: Vec<NaiveDate> = days.iter().copied().filter(|day| (first..=last).contains(day)).collect();
+        let (inside, outside): (Vec<CommitInfo>, Vec<CommitInfo>) = commits.into_iter()
+            .partition(|c| (first..=last).contains(&c.date.date_naive()));
+        let total_time: f64 = covered.iter().map(|day| self.seconds.get(day).copied().unwrap_or(0.0)).sum();
+        if total_time <= 0.0 {
+            return outside.into_iter().chain(inside).collect();
+        }
+
+        // Largest-remainder split of the covered commits by coding time
+        let budget = inside.len();
+        let exact: Vec<f64> = covered.iter()
+            .map(|day| self.seconds.get(day).copied().unwrap_or(0.0) / total_time * budget as f64)
+            .collect();
+        let mut wanted: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+        let mut order: Vec<usize> = (0..covered.len()).collect();
+        order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+        let short = budget - wanted.iter().sum::<usize>();
+        for &i in order.iter().take(short) {
+            wanted[i] += 1;
+        }
+
+        let mut rng = rng();
+        let mut by_day: BTreeMap<NaiveDate, Vec<CommitInfo>> = BTreeMap::new();
+        for commit in inside {
+            by_day.entry(commit.date.date_naive()).or_default().push(commit);
+        }
+        let mut shaped = outside;
+        for (day, count) in covered.iter().zip(wanted) {
+            let mut day_commits = by_day.remove(day).unwrap_or_default();
+            while day_commits.len() > count {
+                day_commits.remove(rng.random_range(0..day_commits.len()));
+            }
+            while day_commits.len() < count {
+                let hour = rng.random_range(WORKING_HOURS.0..=WORKING_HOURS.1);
+                day_commits.push(create_commit_at_time(*day, hour, rng.random_range(0..60)));
+            }
+            day_commits.sort_by_key(|c| c.date);
+            separate_timestamps(&mut day_commits);
+            shaped.extend(day_commits);
+        }
+        shaped.sort_by_key(|c| c.date);
+        shaped
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::ValueEnum;
+use crate::error::{GitHubGridError, Result};
+
+// Columns in GitHub's contribution graph: a year always touches 53 weeks
+pub const WEEKS: usize = 53;
+// Darkest shade; GitHub draws four above empty
+pub const MAX_LEVEL: u8 = 4;
+
+// Glyphs are rows of `#` (lit) and `.` (blank), top to bottom. Widths vary,
+// so narrow characters take less of the canvas.
+const FONT_5X7: &[(char, [&str; 7])] = &[
+    ('A', [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('B', ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."]),
+    ('C', [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."]),
+    ('D', ["####.", "#...#", "#...#", "#...#", "#...#", "#...#", "####."]),
+    ('E', ["#####", "#....", "#....", "####.", "#....", "#....", "#####"]),
+    ('F', ["#####", "#....", "#....", "####.", "#....", "#....", "#...."]),
+    ('G', [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".####"]),
+    ('H', ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "#....", "#####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#"]),
+    ('N', ["#...#", "#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#"]),
+    ('O', [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('P', ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."]),
+    ('Q', [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"]),
+    ('R', ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"]),
+    ('S', [".####", "#....", "#....", ".###.", "....#", "....#", "####."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('V', ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "#.#.#", ".#.#."]),
+    ('X', ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."]),
+    ('Z', ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"]),
+    ('0', [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###."]),
+    ('1', ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('2', [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"]),
+    ('3', ["####.", "....#", "....#", ".###.", "....#", "....#", "####."]),
+    ('4', ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#."]),
+    ('5', ["#####", "#....", "####.", "....#", "....#", "#...#", ".###."]),
+    ('6', [".###.", "#....", "#....", "####.", "#...#", "#...#", ".###."]),
+    ('7', ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."]),
+    ('8', [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."]),
+    ('9', [".###.", "#...#", "#...#", ".####", "....#", "....#", ".###."]),
+    (' ', ["...", "...", "...", "...", "...", "...", "..."]),
+    ('.', [".", ".", ".", ".", ".", ".", "#"]),
+    ('!', ["#", "#", "#", "#", "#", ".", "#"]),
+    ('?', [".###.", "#...#", "....#", "...#.", "..#..", ".....", "..#.."]),
+    ('-', ["...", "...", "...", "###", "...", "...", "..."]),
+    (':', [".", ".", "#", ".", "#", ".", "."]),
+    ('\'', ["#", "#", ".", ".", ".", ".", "."]),
+];
+
+const FONT_3X5: &[(char, [&str; 5])] = &[
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#.#", "###", "###", "#.#", "#.#"]),
+    ('N', ["##.", "#.#", "#.#", "#.#", "#.#"]),
+    ('O', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#.#", "#.#", "###", "###", "#.#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('0', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "#..", "###"]),
+    ('3', ["##.", "..#", ".#.", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "###", "#.#", "###"]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', ["###", "#.#", "###", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#", "##."]),
+    (' ', ["..", "..", "..", "..", ".."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["##.", "..#", ".#.", "...", ".#."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    (':', [".", "#", ".", "#", "."]),
+    ('\'', ["#", "#", ".", ".", "."]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Font {
+    /// 3 pixels wide, 5 tall (Monday to Friday), fits about 13 letters
+    #[value(name = "3x5")]
+    Small,
+    /// 5 pixels wide, using all seven days, fits about 8 letters
+    #[value(name = "5x7")]
+    Large,
+}
+
+impl Font {
+    // The glyph's rows and the first weekday row (Sunday is 0) it's drawn at
+    fn glyph(self, c: char) -> Option<(Vec<&'static str>, usize)> {
+        let c = c.to_ascii_uppercase();
+        match self {
+            Font::Small => FONT_3X5.iter().find(|(g, _)| *g == c).map(|(_, rows)| (rows.to_vec(), 1)),
+            Font::Large => FONT_5X7.iter().find(|(g, _)| *g == c).map(|(_, rows)| (rows.to_vec(), 0)),
+        }
+    }
+}
+
+const HEART: [&str; 7] = [
+    ".......",
+    ".##.##.",
+    "#######",
+    "#######",
+    ".#####.",
+    "..###..",
+    "...#...",
+];
+
+const SMILEY: [&str; 7] = [
+    "..#####..",
+    ".#.....#.",
+    "#..#.#..#",
+    "#.......#",
+    "#.#...#.#",
+    ".#.###.#.",
+    "..#####..",
+];
+
+// Ready-made drawings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shape {
+    Heart,
+    Smiley,
+    /// Alternating days across the whole year
+    Checkerboard,
+    /// Diagonal stripes across the whole year
+    Stripes,
+    /// A sine wave running across the year
+    Wave,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+// How `render_text` and `render_shape` lay a drawing out on the canvas
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    pub font: Font,
+    // Blank columns between characters
+    pub spacing: usize,
+    pub align: Align,
+    // Week the text starts at, overriding `align`
+    pub offset: Option<usize>,
+    pub level: u8,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self { font: Font::Large, spacing: 1, align: Align::Center, offset: None, level: MAX_LEVEL }
+    }
+}
+
+// The contribution graph as a grid of shades (0-4): one column per week,
+// rows from Sunday to Saturday like GitHub draws them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    columns: Vec<[u8; 7]>,
+}
+
+impl Canvas {
+    pub fn new(weeks: usize) -> Self {
+        Self { columns: vec![[0; 7]; weeks] }
+    }
+
+    // Every day lit, for painting around existing activity
+    pub fn filled(weeks: usize, level: u8) -> Self {
+        Self { columns: vec![[level.min(MAX_LEVEL); 7]; weeks] }
+    }
+
+    pub fn weeks(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn get(&self, week: usize, weekday: usize) -> u8 {
+        self.columns.get(week).map_or(0, |column| column[weekday])
+    }
+
+    pub fn set(&mut self, week: usize, weekday: usize, level: u8) {
+        if let Some(column) = self.columns.get_mut(week) {
+            column[weekday] = level.min(MAX_LEVEL);
+        }
+    }
+
+    // Lights the `#` pixels of a bitmap whose top-left corner is at `week`, `top`
+    fn blit(&mut self, rows: &[&str], week: usize, top: usize, level: u8) {
+        for (y, row) in rows.iter().enumerate() {
+            for (x, pixel) in row.chars().enumerate() {
+                if pixel == '#' {
+                    self.set(week + x, top + y, level);
+                }
+            }
+        }
+    }
+
+    // Shade per day, for a canvas whose first column is the week starting
+    // on `first_sunday`; blank cells are left out
+    pub fn days(&self, first_sunday: NaiveDate) -> BTreeMap<NaiveDate, u8> {
+        let mut days = BTreeMap::new();
+        for (week, column) in self.columns.iter().enumerate() {
+            for (weekday, level) in column.iter().enumerate() {
+                if *level > 0 {
+                    days.insert(first_sunday + Duration::days((week * 7 + weekday) as i64), *level);
+                }
+            }
+        }
+        days
+    }
+
+    // Seven lines of block characters, for previews
+    pub fn to_text(&self) -> String {
+        (0..7)
+            .map(|weekday| self.columns.iter().map(|column| match column[weekday] {
+                0 => '·',
+                1 => '░',
+                2 => '▒',
+                3 => '▓',
+                _ => '█',
+            }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// The Sunday that starts the graph column holding `day`
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_sunday() as i64)
+}
+
+// Draws `text` in pixel letters on a `weeks`-wide canvas
+pub fn render_text(text: &str, options: &TextOptions, weeks: usize) -> Result<Canvas> {
+    let glyphs = text.chars()
+        .map(|c| options.font.glyph(c).ok_or_else(|| GitHubGridError::Config(format!(
+            "No glyph for '{}' (letters, digits, spaces and . ! ? - : ' are supported)", c
+        ))))
+        .collect::<Result<Vec<_>>>()?;
+    if glyphs.is_empty() {
+        return Err(GitHubGridError::Config("Nothing to draw".to_string()));
+    }
+    let width = glyphs.iter().map(|(rows, _)| rows[0].len()).sum::<usize>() + options.spacing * (glyphs.len() - 1);
+    let start = place(&format!("'{}'", text), width, options, weeks)
+        .map_err(|e| GitHubGridError::Config(format!("{}; try a smaller font, less spacing or shorter text", e)))?;
+
+    let mut canvas = Canvas::new(weeks);
+    let mut column = start;
+    for (rows, top) in glyphs {
+        canvas.blit(&rows, column, top, options.level);
+        column += rows[0].len() + options.spacing;
+    }
+    Ok(canvas)
+}
+
+// Draws a preset shape; heart and smiley are placed like text, the others
+// cover the whole canvas
+pub fn render_shape(shape: Shape, options: &TextOptions, weeks: usize) -> Result<Canvas> {
+    let mut canvas = Canvas::new(weeks);
+    let level = options.level;
+    match shape {
+        Shape::Heart | Shape::Smiley => {
+            let rows = if shape == Shape::Heart { &HEART[..] } else { &SMILEY[..] };
+            let start = place(&format!("{:?}", shape).to_lowercase(), rows[0].len(), options, weeks)?;
+            canvas.blit(rows, start, 0, level);
+        }
+        Shape::Checkerboard => fill(&mut canvas, level, |week, weekday| (week + weekday) % 2 == 0),
+        Shape::Stripes => fill(&mut canvas, level, |week, weekday| (week + weekday) % 4 == 0),
+        Shape::Wave => fill(&mut canvas, level, |week, weekday| {
+            // One period every 13 weeks, a quarter of the year
+            let phase = week as f64 * std::f64::consts::TAU / 13.0;
+            (3.0 - 3.0 * phase.sin()).round() as usize == weekday
+        }),
+    }
+    Ok(canvas)
+}
+
+// First week of a drawing `width` weeks wide
+fn place(what: &str, width: usize, options: &TextOptions, weeks: usize) -> Result<usize> {
+    let start = match (options.offset, options.align) {
+        (Some(offset), _) => offset,
+        (None, Align::Left) => 0,
+        (None, Align::Center) => weeks.saturating_sub(width) / 2,
+        (None, Align::Right) => weeks.saturating_sub(width),
+    };
+    if start + width > weeks {
+        return Err(GitHubGridError::Config(format!(
+            "{} is {} weeks wide{} but the graph has {}",
+            what, width, if start > 0 { format!(" starting at week {},", start) } else { String::new() }, weeks
+        )));
+    }
+    Ok(start)
+}
+
+fn fill(canvas: &mut Canvas, level: u8, lit: impl Fn(usize, usize) -> bool) {
+    for week in 0..canvas.weeks() {
+        for weekday in 0..7 {
+            if lit(week, weekday) {
+                canvas.set(week, weekday, level);
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+use chrono::{Duration, NaiveDate};
+use crate::error::{GitHubGridError, Result};
+
+// Summary words that mark an all-day event as time off by default
+pub const DEFAULT_KEYWORDS: &[&str] = &["vacation", "holiday", "pto", "travel", "out of office", "leave"];
+
+// An all-day event from an .ics export, with an inclusive end date
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+// All-day VEVENTs from an iCalendar file. Timed events are skipped, as are
+// recurrence rules (only the first occurrence is kept).
+pub fn parse_ics(content: &str) -> Result<Vec<CalendarEvent>> {
+    // Long lines are folded onto continuation lines starting with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<(Option<String>, Option<NaiveDate>, Option<NaiveDate>)> = None;
+    // Nesting inside the current event, e.g. a VALARM with its own SUMMARY
+    let mut depth = 0;
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = key.split_once(';').unwrap_or((key, ""));
+        let name = name.to_ascii_uppercase();
+        if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+            current = Some((None, None, None));
+            depth = 0;
+            continue;
+        }
+        if current.is_some() && (name == "BEGIN" || (name == "END" && depth > 0)) {
+            depth += if name == "BEGIN" { 1 } else { -1 };
+            continue;
+        }
+        if name == "END" && value.eq_ignore_ascii_case("VEVENT") {
+            if let Some((summary, Some(start), end)) = current.take() {
+                // DTEND is exclusive for all-day events; a missing one means a single day
+                let end = end.map_or(start, |end| (end - Duration::days(1)).max(start));
+                events.push(CalendarEvent { summary: summary.unwrap_or_default(), start, end });
+            }
+            continue;
+        }
+        let Some(event) = current.as_mut().filter(|_| depth == 0) else {
+            continue;
+        };
+        match name.as_str() {
+            "SUMMARY" => event.0 = Some(unescape(value)),
+            "DTSTART" => event.1 = all_day_date(params, value)?,
+            "DTEND" => event.2 = all_day_date(params, value)?,
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+pub fn load(path: &Path) -> Result<Vec<CalendarEvent>> {
+    parse_ics(&fs::read_to_string(path)?)
+}
+
+// Date of an all-day DTSTART/DTEND (`VALUE=DATE`, or a bare YYYYMMDD), or
+// None for a timed one
+fn all_day_date(params: &str, value: &str) -> Result<Option<NaiveDate>> {
+    let value = value.trim();
+    let is_date = params.split(';').any(|p| p.eq_ignore_ascii_case("VALUE=DATE"))
+        || (value.len() == 8 && value.chars().all(|c| c.is_ascii_digit()));
+    if !is_date {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .map(Some)
+        .map_err(|_| GitHubGridError::Parse(format!("Invalid calendar date '{}'", value)))
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+// Date ranges of the events whose summary contains one of the keywords
+// (case-insensitive)
+pub fn blackout_ranges(events: &[CalendarEvent], keywords: &[String]) -> Vec<(NaiveDate, NaiveDate)> {
+    let keywords: Vec<String> = keywords.iter().map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty()).collect();
+    events.iter()
+        .filter(|event| {
+            let summary = event.summary.to_lowercase();
+            keywords.iter().any(|keyword| summary.contains(keyword.as_str()))
+        })
+        .map(|event| (event.start, event.end))
+        .collect()
+}
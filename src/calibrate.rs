@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+use crate::patterns::{CommitInfo, ConfigurablePattern, IntensityLevel, Pattern, PatternConfig};
+
+const MAX_ITERATIONS: u32 = 12;
+
+// Outcome of fitting a config to a commit target
+pub struct Calibration {
+    pub config: PatternConfig,
+    pub commits: Vec<CommitInfo>,
+    pub expected: u32,
+    pub iterations: u32,
+}
+
+impl Calibration {
+    pub fn achieved(&self) -> u32 {
+        self.commits.len() as u32
+    }
+
+    // Signed deviation from the target as a fraction of it
+    pub fn deviation(&self) -> f64 {
+        if self.expected == 0 {
+            return 0.0;
+        }
+        (self.achieved() as f64 - self.expected as f64) / self.expected as f64
+    }
+
+    pub fn within(&self, tolerance: f64) -> bool {
+        self.deviation().abs() <= tolerance
+    }
+}
+
+// Generates over `ranges`, measures the total and rescales the config's
+// volume until the plan is within `tolerance` (a fraction, e.g. 0.02) of
+// `commits_needed`. Returns the best plan seen if it never gets there.
+pub fn calibrate(commits_needed: u32, ranges: &[(NaiveDate, NaiveDate)], tolerance: f64) -> Calibration {
+    let days: i64 = ranges.iter().map(|(start, end)| (*end - *start).num_days() + 1).sum();
+    let mut config = initial_config(commits_needed, days.max(1));
+    let mut best: Option<Calibration> = None;
+    let mut iterations = 0;
+
+    while iterations < MAX_ITERATIONS {
+        iterations += 1;
+        let pattern = ConfigurablePattern::new(config.clone());
+        let commits: Vec<CommitInfo> = ranges.iter()
+            .flat_map(|(start, end)| pattern.generate(*start, *end))
+            .collect();
+        let achieved = commits.len() as u32;
+
+        let candidate = Calibration { config: config.clone(), commits, expected: commits_needed, iterations };
+        let done = candidate.within(tolerance);
+        if best.as_ref().is_none_or(|b| candidate.deviation().abs() < b.deviation().abs()) {
+            best = Some(candidate);
+        }
+        if done {
+            break;
+        }
+
+        // Output scales roughly linearly with volume; damp the step so
+        // per-run randomness doesn't make the search oscillate
+        let ratio = if achieved == 0 { 2.0 } else { commits_needed as f64 / achieved as f64 };
+        let volume = (config.volume() * ratio.powf(0.8)).clamp(0.05, 20.0);
+        config = config.to_builder().volume(volume).build().expect("clamped volume is valid");
+    }
+
+    let mut best = best.expect("at least one calibration iteration ran");
+    best.iterations = iterations;
+    best
+}
+
+// Single-shot starting point: picks an intensity tier from the required daily average
+fn initial_config(commits_needed: u32, days_in_range: i64) -> PatternConfig {
+    let avg_per_day = commits_needed as f64 / days_in_range as f64;
+    
+    // Choose intensity level based on required daily average
+    // Calibrated for aggressive spike system: 0.7x accounts for frequent high spikes  
+    let target_avg = avg_per_day * 0.7;  // Conservative: two-tier spikes significantly boost output
+    
+    let intensity = if target_avg < 5.0 {
+        IntensityLevel::Casual
+    } else if target_avg < 15.0 {
+        IntensityLevel::Active  
+    } else if target_avg < 30.0 {
+        IntensityLevel::Maintainer
+    } else if target_avg < 50.0 {
+        IntensityLevel::Hyperactive
+    } else {
+        IntensityLevel::Extreme
+    };
+    
+    // Create pattern config with enhanced variance for target hitting
+    // More realistic vacation frequencies
+    let vacation_freq = match intensity {
+        IntensityLevel::Casual => 0.05,     // More time off
+        IntensityLevel::Active => 0.035,    // Regular breaks
+        IntensityLevel::Maintainer => 0.025, // Still needs breaks
+        IntensityLevel::Hyperactive => 0.02,  // Less but still important
+        IntensityLevel::Extreme => 0.015,    // Rare but necessary
+    };
+    
+    // More aggressive spike probability for dramatic variance
+    let spike_prob = match intensity {
+        IntensityLevel::Casual => 0.25,
+        IntensityLevel::Active => 0.32,
+        IntensityLevel::Maintainer => 0.38,
+        IntensityLevel::Hyperactive => 0.42,
+        IntensityLevel::Extreme => 0.48,
+    };
+    
+    PatternConfig::builder()
+        .intensity(intensity)
+        .weekly_rhythm(true)
+        .vacation_frequency(vacation_freq)
+        .vacation_duration(2, 8)  // Longer, more realistic breaks
+        .spike_probability(spike_prob)
+        .spike_multiplier(3.5)  // Much more dramatic spikes for release/deadline days
+        .build()
+        .expect("initial calibration config is valid")
+}
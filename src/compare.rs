@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, NaiveDate, Weekday};
+use crate::readme_stats::{grid_rows, streaks, week_rows, LEVELS};
+
+// Diff cells: first user busier, second user busier, both active with the
+// same count, neither active
+const DIFF: [char; 4] = ['▲', '▼', '=', '·'];
+
+// Summary of one user's graph over the compared range
+struct Stats {
+    total: u32,
+    active: usize,
+    longest: u32,
+    busiest: u32,
+    median: u32,
+    weekend_share: f64,
+    weeks_active: usize,
+}
+
+fn stats(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate) -> Stats {
+    let in_range: Vec<(NaiveDate, u32)> = days.range(start..=end).map(|(d, c)| (*d, *c)).collect();
+    let total: u32 = in_range.iter().map(|(_, c)| c).sum();
+    let mut active: Vec<u32> = in_range.iter().map(|(_, c)| *c).filter(|&c| c > 0).collect();
+    active.sort_unstable();
+    let weekend: u32 = in_range.iter()
+        .filter(|(d, _)| matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .map(|(_, c)| c)
+        .sum();
+    let mut weeks: Vec<(i32, u32)> = in_range.iter()
+        .filter(|(_, c)| *c > 0)
+        .map(|(d, _)| (d.iso_week().year(), d.iso_week().week()))
+        .collect();
+    weeks.dedup();
+
+    Stats {
+        total,
+        active: active.len(),
+        longest: streaks(days, start, end).0,
+        busiest: active.last().copied().unwrap_or(0),
+        median: active.get(active.len() / 2).copied().unwrap_or(0),
+        weekend_share: if total == 0 { 0.0 } else { weekend as f64 / total as f64 },
+        weeks_active: weeks.len(),
+    }
+}
+
+// Both users' graphs stacked (each shaded on its own quartiles, as GitHub
+// does), a per-day diff, and a table of stats side by side
+pub fn render(
+    (name_a, days_a): (&str, &BTreeMap<NaiveDate, u32>),
+    (name_b, days_b): (&str, &BTreeMap<NaiveDate, u32>),
+    start: NaiveDate,
+    end: NaiveDate,
+) -> String {
+    let label_width = name_a.len().max(name_b.len()).max(4) + 2;
+    let weekday_labels = ["", "Mon", "", "Wed", "", "Fri", ""];
+    let mut out = String::new();
+
+    let mut push_grid = |title: &str, rows: Vec<String>| {
+        out.push_str(&format!("{}\n", title));
+        for (label, row) in weekday_labels.iter().zip(rows) {
+            out.push_str(&format!("  {:<4}{}\n", label, row.trim_end()));
+        }
+        out.push('\n');
+    };
+    push_grid(name_a, grid_rows(days_a, start, end));
+    push_grid(name_b, grid_rows(days_b, start, end));
+    push_grid(&format!("{} vs {}", name_a, name_b), week_rows(start, end, |day| {
+        let a = days_a.get(&day).copied().unwrap_or(0);
+        let b = days_b.get(&day).copied().unwrap_or(0);
+        match a.cmp(&b) {
+            std::cmp::Ordering::Greater => DIFF[0],
+            std::cmp::Ordering::Less => DIFF[1],
+            _ if a > 0 => DIFF[2],
+            _ => DIFF[3],
+        }
+    }));
+    out.push_str(&format!(
+        "  Less {} More    {} {} busier  {} {} busier  {} same\n\n",
+        LEVELS.iter().collect::<String>(), DIFF[0], name_a, DIFF[1], name_b, DIFF[2]
+    ));
+
+    let (a, b) = (stats(days_a, start, end), stats(days_b, start, end));
+    let days = (end - start).num_days() + 1;
+    let percent = |count: usize| count as f64 * 100.0 / days as f64;
+    let rows: [(&str, String, String); 8] = [
+        ("Contributions", a.total.to_string(), b.total.to_string()),
+        ("Active days", format!("{} ({:.0}%)", a.active, percent(a.active)), format!("{} ({:.0}%)", b.active, percent(b.active))),
+        ("Active weeks", a.weeks_active.to_string(), b.weeks_active.to_string()),
+        ("Longest streak", format!("{} days", a.longest), format!("{} days", b.longest)),
+        ("Busiest day", a.busiest.to_string(), b.busiest.to_string()),
+        ("Median active day", a.median.to_string(), b.median.to_string()),
+        ("Avg per active day", average(a.total, a.active), average(b.total, b.active)),
+        ("Weekend share", format!("{:.0}%", a.weekend_share * 100.0), format!("{:.0}%", b.weekend_share * 100.0)),
+    ];
+    let column = label_width.max(14);
+    out.push_str(&format!("  {:<20}{:>column$}{:>column$}\n", "", name_a, name_b));
+    for (label, left, right) in rows {
+        out.push_str(&format!("  {:<20}{:>column$}{:>column$}\n", label, left, right));
+    }
+    out
+}
+
+fn average(total: u32, active: usize) -> String {
+    if active == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.1}", total as f64 / active as f64)
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+use std::iter::Peekable;
+use std::ops::RangeInclusive;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rand::seq::SliceRandom;
+use rand::{rng, Rng};
+use crate::dates;
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{create_commit_at_time, separate_timestamps, CommitInfo, Pattern};
+
+const MAX_MULTIPLIER: f64 = 10.0;
+const MAX_HACKATHONS: u32 = 12;
+
+// A stretch of days with boosted activity, e.g. before a launch
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrunchWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub multiplier: f64,
+}
+
+impl CrunchWindow {
+    // `START..END×2.5`; `x` or `*` work in place of `×`
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GitHubGridError::Parse(format!("Invalid crunch window '{}', expected e.g. 2024-10-01..2024-11-15x2.5", value));
+        let (range, multiplier) = value.rsplit_once(['×', 'x', '*']).ok_or_else(invalid)?;
+        let (start, end) = dates::parse_range(range.trim())?;
+        let multiplier = check_multiplier(multiplier.trim().parse().map_err(|_| invalid())?)?;
+        Ok(Self { start, end, multiplier })
+    }
+    
+    // The last `weeks` weeks of every calendar quarter in `years`, for
+    // deadline pushes and OKR closeouts
+    pub fn quarter_ends(years: RangeInclusive<i32>, weeks: u32, multiplier: f64) -> Result<Vec<Self>> {
+        let multiplier = check_multiplier(multiplier)?;
+        if !(1..=4).contains(&weeks) {
+            return Err(GitHubGridError::Config(format!("Quarter-end pushes last 1 to 4 weeks, got {}", weeks)));
+        }
+        let mut windows = Vec::new();
+        for year in years {
+            for month in [3, 6, 9, 12] {
+                let next = NaiveDate::from_ymd_opt(year + month / 12, month as u32 % 12 + 1, 1)
+                    .ok_or_else(|| GitHubGridError::Config(format!("Invalid year: {}", year)))?;
+                windows.push(Self { start: next - Duration::weeks(weeks as i64), end: next - Duration::days(1), multiplier });
+            }
+        }
+        Ok(windows)
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&day)
+    }
+}
+
+// `per_year` random Saturdays in each of `years`, each starting a hackathon
+// weekend
+pub fn hackathon_weekends(years: RangeInclusive<i32>, per_year: u32) -> Result<Vec<NaiveDate>> {
+    if per_year > MAX_HACKATHONS {
+        return Err(GitHubGridError::Config(format!("At most {} hackathon weekends a year, got {}", MAX_HACKATHONS, per_year)));
+    }
+    let mut rng = rng();
+    let mut saturdays = Vec::new();
+    for year in years {
+        let first = NaiveDate::from_weekday_of_month_opt(year, 1, Weekday::Sat, 1)
+            .ok_or_else(|| GitHubGridError::Config(format!("Invalid year: {}", year)))?;
+        let mut year_saturdays: Vec<NaiveDate> = first.iter_weeks().take_while(|day| day.year() == year).collect();
+        year_saturdays.shuffle(&mut rng);
+        saturdays.extend(year_saturdays.into_iter().take(per_year as usize));
+    }
+    saturdays.sort();
+    Ok(saturdays)
+}
+
+fn check_multiplier(multiplier: f64) -> Result<f64> {
+    if !multiplier.is_finite() || !(1.0..=MAX_MULTIPLIER).contains(&multiplier) {
+        return Err(GitHubGridError::Config(format!(
+            "Crunch multiplier must be between 1 and {}, got {}", MAX_MULTIPLIER, multiplier
+        )));
+    }
+    Ok(multiplier)
+}
+
+// Layers crunch windows onto another pattern. Inside a window each day's
+// commits are multiplied, quiet days are more likely to see work, and the
+// extra commits run into the evening. Hackathon weekends replace the usual
+// weekend with a burst that runs past midnight.
+pub struct CrunchPattern {
+    inner: Box<dyn Pattern>,
+    windows: Vec<CrunchWindow>,
+    hackathons: BTreeSet<NaiveDate>,
+}
+
+impl CrunchPattern {
+    pub fn new(inner: Box<dyn Pattern>, windows: Vec<CrunchWindow>) -> Self {
+        Self { inner, windows, hackathons: BTreeSet::new() }
+    }
+    
+    // Saturdays that start a hackathon weekend
+    pub fn with_hackathons(mut self, saturdays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.hackathons.extend(saturdays);
+        self
+    }
+
+    fn multiplier(&self, day: NaiveDate) -> Option<f64> {
+        // Overlapping windows take the strongest push
+        self.windows.iter()
+            .filter(|window| window.contains(day))
+            .map(|window| window.multiplier)
+            .reduce(f64::max)
+    }
+}
+
+impl Pattern for CrunchPattern {
+    fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
+        self.stream(start, end).collect()
+    }
+
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        Box::new(CrunchStream {
+            pattern: self,
+            inner: self.inner.stream(start, end).peekable(),
+            current: start,
+            end,
+            day: Vec::new().into_iter(),
+        })
+    }
+}
+
+// Walks the range a day at a time so empty days inside a window can be
+// filled in too
+struct CrunchStream<'a> {
+    pattern: &'a CrunchPattern,
+    inner: Peekable<Box<dyn Iterator<Item = CommitInfo> + 'a>>,
+    current: NaiveDate,
+    end: NaiveDate,
+    day: std::vec::IntoIter<CommitInfo>,
+}
+
+impl CrunchStream<'_> {
+    fn next_day(&mut self) -> Option<Vec<CommitInfo>> {
+        if self.current > self.end {
+            // Anything the inner pattern put past the end passes through
+            let rest: Vec<CommitInfo> = self.inner.by_ref().collect();
+            return (!rest.is_empty()).then_some(rest);
+        }
+        let day = self.current;
+        self.current = day.succ_opt().unwrap();
+        let mut commits = Vec::new();
+        while let Some(commit) = self.inner.next_if(|commit| commit.date.date_naive() <= day) {
+            commits.push(commit);
+        }
+        if self.pattern.hackathons.contains(&day) {
+            hackathon(day, &mut commits, true);
+        } else if self.pattern.hackathons.contains(&(day - Duration::days(1))) {
+            hackathon(day, &mut commits, false);
+        } else if let Some(multiplier) = self.pattern.multiplier(day) {
+            boost(day, &mut commits, multiplier);
+        }
+        Some(commits)
+    }
+}
+
+impl Iterator for CrunchStream<'_> {
+    type Item = CommitInfo;
+
+    fn next(&mut self) -> Option<CommitInfo> {
+        loop {
+            if let Some(commit) = self.day.next() {
+                return Some(commit);
+            }
+            self.day = self.next_day()?.into_iter();
+        }
+    }
+}
+
+fn boost(day: NaiveDate, commits: &mut Vec<CommitInfo>, multiplier: f64) {
+    let mut rng = rng();
+    let base = match commits.len() {
+        // Days off get pulled in at the rate the crunch adds work
+        0 if rng.random_bool(1.0 - 1.0 / multiplier) => rng.random_range(1..=3),
+        count => count,
+    };
+    let target = (base as f64 * multiplier).round() as usize;
+    while commits.len() < target {
+        // Extended hours: half the extra work lands in the evening
+        let hour = if rng.random_bool(0.5) { rng.random_range(19..=23) } else { rng.random_range(9..=18) };
+        commits.push(create_commit_at_time(day, hour, rng.random_range(0..60)));
+    }
+    commits.sort_by_key(|commit| commit.date);
+    separate_timestamps(commits);
+}
+
+// A hackathon day: several times a busy day's commits, from late morning
+// until late at night, with Sunday picking up where Saturday's night left off
+fn hackathon(day: NaiveDate, commits: &mut Vec<CommitInfo>, saturday: bool) {
+    let mut rng = rng();
+    let target = (commits.len() * 3).max(rng.random_range(20..=45));
+    while commits.len() < target {
+        let hour = match (saturday, rng.random_range(0..10)) {
+            (true, 0..=4) => rng.random_range(19..=23),
+            (true, _) => rng.random_range(10..=18),
+            (false, 0..=3) => rng.random_range(0..=3),
+            (false, _) => rng.random_range(10..=20),
+        };
+        commits.push(create_commit_at_time(day, hour, rng.random_range(0..60)));
+    }
+    commits.sort_by_key(|commit| commit.date);
+    separate_timestamps(commits);
+}
@@ -0,0 +1,78 @@
+use std::thread;
+use std::time::Duration as StdDuration;
+use chrono::{Local, NaiveDateTime};
+use github_grid::git_ops::GitOperations;
+use github_grid::patterns::{CommitInfo, Pattern};
+use crate::progress::ProgressReporter;
+use github_grid::error::Result;
+
+// Re-check the clock at least this often so suspend/resume and clock
+// changes don't leave the daemon oversleeping
+const MAX_SLEEP: StdDuration = StdDuration::from_secs(60);
+
+// Stays running and creates each of today's planned commits when its
+// timestamp arrives, pushing immediately, then plans the next day.
+pub fn run_daemon(
+    git_ops: &mut GitOperations,
+    pattern: &dyn Pattern,
+    reporter: &ProgressReporter,
+    push: bool,
+) -> Result<()> {
+    reporter.info("🕒 Daemon started, press Ctrl+C to stop");
+    let mut pushed_total = 0;
+    let mut unpushed = 0;
+
+    loop {
+        let today = Local::now().date_naive();
+        let last_autogen = git_ops.get_latest_autogen_commit()?;
+        let now = Local::now();
+
+        // Skip anything already due or already covered by a previous run today
+        let pending: Vec<CommitInfo> = pattern.generate(today, today)
+            .into_iter()
+            .filter(|c| c.date > now && last_autogen.is_none_or(|last| c.date > last))
+            .collect();
+
+        reporter.info(&format!("📅 {}: {} commits scheduled", today, pending.len()));
+
+        for (index, commit) in pending.iter().enumerate() {
+            sleep_until(commit.date.naive_local());
+
+            let oid = git_ops.create_commit(commit)?;
+            git_ops.sync_worktree()?;
+            reporter.committed(commit, index, Some(pending.len()), &oid.to_string());
+            reporter.info(&format!("✅ Committed at {}", commit.date.format("%H:%M")));
+            unpushed += 1;
+            if !push {
+                continue;
+            }
+
+            // A failed push is retried with the next commit rather than stopping the daemon
+            match git_ops.push_commits() {
+                Ok(()) => {
+                    pushed_total += unpushed;
+                    unpushed = 0;
+                    reporter.pushed(index + 1, pushed_total);
+                }
+                Err(e) => reporter.info(&format!("⚠️  Push failed, will retry with next commit: {}", e)),
+            }
+        }
+
+        let tomorrow = today.succ_opt().unwrap().and_hms_opt(0, 0, 5).unwrap();
+        reporter.info(&format!("💤 Done for {}, sleeping until tomorrow", today));
+        sleep_until(tomorrow);
+    }
+}
+
+fn sleep_until(target: NaiveDateTime) {
+    loop {
+        let remaining = target - Local::now().naive_local();
+        let Ok(remaining) = remaining.to_std() else {
+            return;
+        };
+        if remaining.is_zero() {
+            return;
+        }
+        thread::sleep(remaining.min(MAX_SLEEP));
+    }
+}
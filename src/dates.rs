@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, Duration, Local, LocalResult, Months, NaiveDate, NaiveDateTime, TimeZone};
+use crate::error::{GitHubGridError, Result};
+
+pub fn parse_date(value: &str) -> Result<NaiveDate> {
+    Ok(NaiveDate::parse_from_str(value, "%Y-%m-%d")?)
+}
+
+// Inclusive `START..END` range, or a single `YYYY-MM-DD` day
+pub fn parse_range(value: &str) -> Result<(NaiveDate, NaiveDate)> {
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) => (parse_date(start.trim())?, parse_date(end.trim())?),
+        None => {
+            let day = parse_date(value.trim())?;
+            (day, day)
+        }
+    };
+
+    if end < start {
+        return Err(GitHubGridError::Parse(format!("Range '{}' ends before it starts", value)));
+    }
+
+    Ok((start, end))
+}
+
+// Full calendar year, e.g. `--year 2023`
+pub fn year_range(year: i32) -> Result<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, 1, 1)
+        .ok_or_else(|| GitHubGridError::Parse(format!("Invalid year: {}", year)))?;
+    let end = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+    Ok((start, end))
+}
+
+// Splits ranges at year boundaries, grouped by calendar year
+pub fn split_by_year(ranges: &[(NaiveDate, NaiveDate)]) -> BTreeMap<i32, Vec<(NaiveDate, NaiveDate)>> {
+    let mut years: BTreeMap<i32, Vec<(NaiveDate, NaiveDate)>> = BTreeMap::new();
+    for &(start, end) in ranges {
+        for year in start.year()..=end.year() {
+            let segment_start = start.max(NaiveDate::from_ymd_opt(year, 1, 1).unwrap());
+            let segment_end = end.min(NaiveDate::from_ymd_opt(year, 12, 31).unwrap());
+            years.entry(year).or_default().push((segment_start, segment_end));
+        }
+    }
+    years
+}
+
+// Full calendar month, e.g. `--month 2024-06`
+pub fn month_range(value: &str) -> Result<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+        .map_err(|_| GitHubGridError::Parse(format!("Invalid month '{}', expected YYYY-MM", value)))?;
+    let end = start + Months::new(1) - Duration::days(1);
+    Ok((start, end))
+}
+
+// Trailing window ending on `end`, e.g. `--last 90d`, `12w`, `6m`, `1y`
+pub fn last_range(value: &str, end: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    let invalid = || GitHubGridError::Parse(
+        format!("Invalid duration '{}', expected e.g. 90d, 12w, 6m or 1y", value)
+    );
+
+    let (unit_index, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(unit_index);
+    let amount: u32 = amount.parse().map_err(|_| invalid())?;
+    if amount == 0 {
+        return Err(invalid());
+    }
+
+    let start = match unit {
+        "d" => end.checked_sub_signed(Duration::days(amount as i64 - 1)),
+        "w" => end.checked_sub_signed(Duration::days(amount as i64 * 7 - 1)),
+        "m" => end.checked_sub_months(Months::new(amount)).map(|day| day + Duration::days(1)),
+        "y" => amount.checked_mul(12)
+            .and_then(|months| end.checked_sub_months(Months::new(months)))
+            .map(|day| day + Duration::days(1)),
+        _ => return Err(invalid()),
+    };
+
+    let start = start.ok_or_else(|| GitHubGridError::Parse(format!("Duration '{}' reaches too far back", value)))?;
+    Ok((start, end))
+}
+
+// Wait time such as `90s`, `10m` or `1h`
+pub fn parse_wait(value: &str) -> Result<std::time::Duration> {
+    let invalid = || GitHubGridError::Parse(
+        format!("Invalid wait time '{}', expected e.g. 90s, 10m or 1h", value)
+    );
+
+    let (unit_index, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(unit_index);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+
+    let seconds = match unit {
+        "s" => Some(amount),
+        "m" => amount.checked_mul(60),
+        "h" => amount.checked_mul(3600),
+        _ => return Err(invalid()),
+    };
+    Ok(std::time::Duration::from_secs(seconds.ok_or_else(invalid)?))
+}
+
+// Local wall-clock time that never panics around DST changes: times that
+// occur twice (fall back) resolve to the first occurrence, and times skipped
+// by a spring-forward gap move to the first valid minute after the gap.
+pub fn local_datetime(naive: NaiveDateTime) -> DateTime<Local> {
+    let mut candidate = naive;
+    // Real-world gaps are at most two hours; step past them in 15 minute jumps
+    for _ in 0..=12 {
+        match Local.from_local_datetime(&candidate) {
+            LocalResult::Single(datetime) => return datetime,
+            LocalResult::Ambiguous(earliest, _) => return earliest,
+            LocalResult::None => candidate += Duration::minutes(15),
+        }
+    }
+    Local.from_utc_datetime(&naive)
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, Duration, NaiveDate};
+use rand::{rng, Rng};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use github_grid::patterns::{create_commit_at_time, separate_timestamps, CommitInfo};
+use github_grid::error::Result;
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "   ", "Wed", "   ", "Fri", "   ", "Sun"];
+
+// Per-day plan editor: a GitHub-style grid (weeks as columns, weekdays as
+// rows) where each day's commit count can be nudged before execution.
+struct PlanEditor {
+    days: BTreeMap<NaiveDate, Vec<CommitInfo>>,
+    start: NaiveDate,
+    end: NaiveDate,
+    grid_start: NaiveDate,
+    cursor: NaiveDate,
+}
+
+impl PlanEditor {
+    fn new(commits: Vec<CommitInfo>, start: NaiveDate, end: NaiveDate) -> Self {
+        let mut days: BTreeMap<NaiveDate, Vec<CommitInfo>> = BTreeMap::new();
+        for commit in commits {
+            days.entry(commit.date.date_naive()).or_default().push(commit);
+        }
+
+        let grid_start = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+
+        Self { days, start, end, grid_start, cursor: start }
+    }
+
+    fn count(&self, date: NaiveDate) -> usize {
+        self.days.get(&date).map_or(0, |c| c.len())
+    }
+
+    fn total(&self) -> usize {
+        self.days.values().map(|c| c.len()).sum()
+    }
+
+    fn move_cursor(&mut self, days: i64) {
+        let target = self.cursor + Duration::days(days);
+        if target >= self.start && target <= self.end {
+            self.cursor = target;
+        }
+    }
+
+    fn add_commit(&mut self) {
+        let mut rng = rng();
+        let commit = create_commit_at_time(self.cursor, rng.random_range(6..=23), rng.random_range(0..60));
+        let day = self.days.entry(self.cursor).or_default();
+        day.push(commit);
+        day.sort_by_key(|c| c.date);
+        separate_timestamps(day);
+    }
+
+    fn remove_commit(&mut self) {
+        if let Some(day) = self.days.get_mut(&self.cursor) {
+            day.pop();
+        }
+    }
+
+    fn clear_day(&mut self) {
+        self.days.remove(&self.cursor);
+    }
+
+    fn into_commits(self) -> Vec<CommitInfo> {
+        let mut commits: Vec<CommitInfo> = self.days.into_values().flatten().collect();
+        commits.sort_by_key(|c| c.date);
+        commits
+    }
+
+    fn cell_style(&self, count: usize) -> Style {
+        let color = match count {
+            0 => Color::DarkGray,
+            1..=3 => Color::Rgb(14, 68, 41),
+            4..=10 => Color::Rgb(0, 109, 50),
+            _ => Color::Rgb(57, 211, 83),
+        };
+        Style::default().fg(color)
+    }
+
+    fn render_grid(&self) -> Vec<Line<'static>> {
+        let weeks = (self.end - self.grid_start).num_days() / 7 + 1;
+        let mut lines = Vec::new();
+
+        for weekday in 0..7 {
+            let mut spans = vec![Span::raw(format!("{} ", WEEKDAY_LABELS[weekday as usize]))];
+            for week in 0..weeks {
+                let date = self.grid_start + Duration::days(week * 7 + weekday);
+                if date < self.start || date > self.end {
+                    spans.push(Span::raw("  "));
+                    continue;
+                }
+
+                let mut style = self.cell_style(self.count(date));
+                if date == self.cursor {
+                    style = style.bg(Color::White).add_modifier(Modifier::BOLD);
+                }
+                spans.push(Span::styled("■", style));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        lines
+    }
+
+    fn draw(&self, terminal: &mut DefaultTerminal) -> Result<()> {
+        terminal.draw(|frame| {
+            let [grid_area, status_area, help_area] = Layout::vertical([
+                Constraint::Length(9),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ])
+            .areas(frame.area());
+
+            let grid = Paragraph::new(self.render_grid())
+                .block(Block::bordered().title(" Plan editor "));
+            frame.render_widget(grid, grid_area);
+
+            let status = Paragraph::new(format!(
+                "{}  {} commits   |   Plan total: {} commits",
+                self.cursor.format("%a %Y-%m-%d"),
+                self.count(self.cursor),
+                self.total(),
+            ))
+            .block(Block::bordered());
+            frame.render_widget(status, status_area);
+
+            let help = Paragraph::new(
+                "←/→ week  ↑/↓ day  +/- adjust  d clear day  Enter accept  q/Esc cancel",
+            )
+            .style(Style::default().fg(Color::Gray));
+            frame.render_widget(help, help_area);
+        })?;
+
+        Ok(())
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<bool> {
+        loop {
+            self.draw(terminal)?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-7),
+                KeyCode::Right | KeyCode::Char('l') => self.move_cursor(7),
+                KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+                KeyCode::Char('+') | KeyCode::Char('=') => self.add_commit(),
+                KeyCode::Char('-') => self.remove_commit(),
+                KeyCode::Char('d') | KeyCode::Delete | KeyCode::Backspace => self.clear_day(),
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+}
+
+// Returns the edited plan, or None if the user cancelled
+pub fn edit_plan(commits: Vec<CommitInfo>, start: NaiveDate, end: NaiveDate) -> Result<Option<Vec<CommitInfo>>> {
+    let mut editor = PlanEditor::new(commits, start, end);
+
+    let mut terminal = ratatui::try_init()?;
+    let accepted = editor.run(&mut terminal);
+    ratatui::restore();
+
+    if accepted? {
+        Ok(Some(editor.into_commits()))
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,245 @@
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use chrono::Local;
+use serde::Deserialize;
+use crate::history::config_dir;
+use github_grid::error::{GitHubGridError, Result};
+
+const SMTP_FILE: &str = "smtp.toml";
+// Implicit TLS; any other port upgrades with STARTTLS
+const SMTPS_PORT: u16 = 465;
+
+// Mail server for --email, kept in the config dir rather than on the
+// command line so scheduled runs don't carry credentials in their args
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    // Falls back to git's credential helper (the system keyring) for
+    // smtp://host when left out
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    // Off only for a relay on the same machine
+    #[serde(default = "default_tls")]
+    pub tls: bool,
+}
+
+fn default_port() -> u16 {
+    587
+}
+
+fn default_tls() -> bool {
+    true
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(SMTP_FILE))
+}
+
+impl SmtpConfig {
+    pub fn load() -> Result<Self> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Err(GitHubGridError::Config(format!("--email needs SMTP settings in {}", path.display())));
+        }
+        let config: Self = toml::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid {}: {}", path.display(), e)))?;
+        if config.to.is_empty() {
+            return Err(GitHubGridError::Config(format!("No recipients in {}", path.display())));
+        }
+        Ok(config)
+    }
+
+    fn password(&self, username: &str) -> Result<String> {
+        if let Some(password) = &self.password {
+            return Ok(password.clone());
+        }
+        keyring_password(&self.host, username).ok_or_else(|| GitHubGridError::Config(format!(
+            "No SMTP password for {} in {} or git's credential helper", username, SMTP_FILE
+        )))
+    }
+
+    // Sends a plain-text message through curl's SMTP client, which does
+    // the TLS and AUTH. Credentials go in on stdin, never in the args.
+    pub fn send(&self, subject: &str, body: &str) -> Result<()> {
+        let scheme = if self.port == SMTPS_PORT { "smtps" } else { "smtp" };
+        let mut curl_config = format!("url = \"{}\"\n", curl_quote(&format!("{}://{}:{}", scheme, self.host, self.port))?);
+        if self.tls {
+            curl_config.push_str("ssl-reqd\n");
+        }
+        if let Some(username) = &self.username {
+            let password = self.password(username)?;
+            curl_config.push_str(&format!("user = \"{}\"\n", curl_quote(&format!("{}:{}", username, password))?));
+        }
+
+        let message_dir = private_dir()?;
+        let message_path = message_dir.join("message.eml");
+        let written = write_private(&message_path, message(&self.from, &self.to, subject, body).as_bytes());
+        if let Err(e) = written {
+            let _ = fs::remove_dir_all(&message_dir);
+            return Err(e);
+        }
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error", "--max-time", "60", "--config", "-"])
+            .arg("--mail-from").arg(&self.from)
+            .arg("--upload-file").arg(&message_path);
+        for recipient in &self.to {
+            command.arg("--mail-rcpt").arg(recipient);
+        }
+        let output = command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::piped()).spawn()
+            .and_then(|mut child| {
+                child.stdin.take().expect("stdin is piped").write_all(curl_config.as_bytes())?;
+                child.wait_with_output()
+            });
+        let _ = fs::remove_dir_all(&message_dir);
+
+        let output = output.map_err(|e| GitHubGridError::Config(format!("could not run curl: {}", e)))?;
+        if !output.status.success() {
+            return Err(GitHubGridError::Config(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(())
+    }
+}
+
+// A new directory in the temp dir that only this user can enter, so the
+// message can't be read or swapped through a planted path
+fn private_dir() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("github-grid-mail-{}-{:016x}", std::process::id(), rand::random::<u64>()));
+    let mut builder = DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(content)?;
+    Ok(())
+}
+
+// Stored with `git credential approve` under protocol=smtp, like the
+// github.com token in `--token-source keychain`
+fn keyring_password(host: &str, username: &str) -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let request = format!("protocol=smtp\nhost={}\nusername={}\n\n", host, username);
+    child.stdin.take()?.write_all(request.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .map(str::to_string)
+        .filter(|password| !password.is_empty())
+}
+
+fn message(from: &str, to: &[String], subject: &str, body: &str) -> String {
+    let headers = [
+        format!("From: {}", single_line(from)),
+        format!("To: {}", single_line(&to.join(", "))),
+        format!("Subject: {}", encode_header(subject)),
+        format!("Date: {}", Local::now().to_rfc2822()),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=utf-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+    ];
+    let mut message = headers.join("\r\n");
+    message.push_str("\r\n\r\n");
+    // curl escapes leading dots itself
+    for line in body.lines() {
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+// Line breaks and other control characters collapsed to a space, so text
+// such as a multi-line error can't end a header and start another
+fn single_line(value: &str) -> String {
+    value.split(char::is_control).filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+// RFC 2047 Q-encoding for subjects that aren't plain ASCII
+fn encode_header(value: &str) -> String {
+    let value = single_line(value);
+    if value.is_ascii() {
+        return value;
+    }
+    let mut encoded = String::from("=?UTF-8?Q?");
+    for byte in value.bytes() {
+        match byte {
+            b' ' => encoded.push('_'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b',' | b':' | b'-' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("={:02X}", byte)),
+        }
+    }
+    encoded.push_str("?=");
+    encoded
+}
+
+// A quoted curl config value. A line break would end the option and let
+// the rest be read as more config, so those are refused rather than escaped.
+fn curl_quote(value: &str) -> Result<String> {
+    if value.contains(['\n', '\r', '\0']) {
+        return Err(GitHubGridError::Config("SMTP settings and credentials must be on a single line".to_string()));
+    }
+    Ok(value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_ascii_subjects_are_left_alone() {
+        assert_eq!(encode_header("github-grid run: 12 commits created"), "github-grid run: 12 commits created");
+    }
+
+    #[test]
+    fn non_ascii_subjects_are_q_encoded() {
+        assert_eq!(encode_header("Café run ✓"), "=?UTF-8?Q?Caf=C3=A9_run_=E2=9C=93?=");
+    }
+
+    #[test]
+    fn line_breaks_cannot_start_another_header() {
+        let subject = "github-grid run failed: push rejected\r\nBcc: someone@example.com\n\thint";
+        assert_eq!(encode_header(subject), "github-grid run failed: push rejected Bcc: someone@example.com hint");
+        let encoded = encode_header("échec\r\nBcc: someone@example.com");
+        assert!(encoded.starts_with("=?UTF-8?Q?") && !encoded.contains(['\r', '\n']), "{}", encoded);
+    }
+
+    #[test]
+    fn message_headers_stay_on_one_line() {
+        let to = vec!["me@example.com\r\nBcc: someone@example.com".to_string()];
+        let message = message("grid@example.com", &to, "failed:\nsecond line", "body");
+        let headers = message.split("\r\n\r\n").next().unwrap();
+        assert_eq!(headers.lines().count(), 7);
+        assert!(headers.contains("Subject: failed: second line"));
+    }
+
+    #[test]
+    fn curl_values_are_quoted_and_line_breaks_refused() {
+        assert_eq!(curl_quote(r#"me:pa"ss\word"#).unwrap(), r#"me:pa\"ss\\word"#);
+        assert!(curl_quote("me:pass\nurl = \"smtp://elsewhere\"").is_err());
+        assert!(curl_quote("me:pass\r").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use chrono::{DateTime, Local, Utc};
 
 #[derive(Debug)]
 pub enum GitHubGridError {
@@ -8,6 +9,32 @@ pub enum GitHubGridError {
     Config(String),
     Authentication(String),
     Repository(String),
+    // GitHub API failures, split by kind so callers can branch on them
+    RateLimited { reset: Option<DateTime<Utc>> },
+    NotFound(String),
+    Forbidden { message: String, sso_required: bool },
+    Conflict(String),
+    // Couldn't reach GitHub (DNS, connection, timeout)
+    Network(String),
+    // Some batches reached the remote before the run failed
+    PartialPush { pushed: usize, source: Box<GitHubGridError> },
+    // The run succeeded without creating anything; only surfaced as an
+    // error with --detailed-exit-codes
+    NothingToDo(String),
+    // `check` found the live graph further behind than allowed
+    Drift(String),
+}
+
+// Process exit codes, stable for scripts and cron wrappers. 2 matches
+// clap's own exit code for bad arguments.
+pub mod exit_code {
+    pub const FAILURE: u8 = 1;
+    pub const VALIDATION: u8 = 2;
+    pub const AUTHENTICATION: u8 = 3;
+    pub const NETWORK: u8 = 4;
+    pub const NOTHING_TO_DO: u8 = 5;
+    pub const PARTIAL_PUSH: u8 = 6;
+    pub const DRIFT: u8 = 7;
 }
 
 impl fmt::Display for GitHubGridError {
@@ -19,6 +46,43 @@ impl fmt::Display for GitHubGridError {
             GitHubGridError::Config(msg) => write!(f, "Configuration error: {}", msg),
             GitHubGridError::Authentication(msg) => write!(f, "Authentication error: {}", msg),
             GitHubGridError::Repository(msg) => write!(f, "Repository error: {}", msg),
+            GitHubGridError::RateLimited { reset: Some(reset) } => {
+                write!(f, "GitHub API rate limit exceeded; resets at {}", reset.with_timezone(&Local).format("%H:%M:%S"))
+            }
+            GitHubGridError::RateLimited { reset: None } => write!(f, "GitHub API rate limit exceeded"),
+            GitHubGridError::NotFound(msg) => write!(f, "Not found on GitHub: {}", msg),
+            GitHubGridError::Forbidden { message, sso_required: true } => write!(
+                f, "Forbidden by GitHub: {} (authorize the token for the organization's SSO, e.g. 'gh auth refresh')", message
+            ),
+            GitHubGridError::Forbidden { message, .. } => write!(f, "Forbidden by GitHub: {}", message),
+            GitHubGridError::Conflict(msg) => write!(f, "Conflict on GitHub: {}", msg),
+            GitHubGridError::Network(msg) => write!(f, "Network error: {}", msg),
+            GitHubGridError::PartialPush { pushed, source } => write!(
+                f, "{} ({} commits were already pushed; run `github-grid push` to publish the rest)", source, pushed
+            ),
+            GitHubGridError::NothingToDo(msg) => write!(f, "Nothing to do: {}", msg),
+            GitHubGridError::Drift(msg) => write!(f, "Graph is behind: {}", msg),
+        }
+    }
+}
+
+impl GitHubGridError {
+    // Whether the same request can succeed later without any change
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, GitHubGridError::RateLimited { .. } | GitHubGridError::Network(_))
+    }
+    
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GitHubGridError::Config(_) | GitHubGridError::Parse(_) | GitHubGridError::NotFound(_) => exit_code::VALIDATION,
+            GitHubGridError::Authentication(_) | GitHubGridError::Forbidden { .. } => exit_code::AUTHENTICATION,
+            GitHubGridError::Network(_) | GitHubGridError::RateLimited { .. } => exit_code::NETWORK,
+            GitHubGridError::NothingToDo(_) => exit_code::NOTHING_TO_DO,
+            GitHubGridError::PartialPush { .. } => exit_code::PARTIAL_PUSH,
+            GitHubGridError::Drift(_) => exit_code::DRIFT,
+            GitHubGridError::Git(_) | GitHubGridError::Io(_) | GitHubGridError::Repository(_) | GitHubGridError::Conflict(_) => {
+                exit_code::FAILURE
+            }
         }
     }
 }
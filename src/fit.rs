@@ -0,0 +1,216 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rand::{rng, Rng};
+use std::collections::BTreeMap;
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{CommitInfo, ConfigurablePattern, IntensityLevel, Pattern, PatternConfig};
+
+// Plans generated per candidate; their stats are averaged so one lucky
+// sample doesn't win the search
+const SAMPLES: usize = 3;
+
+// The numbers users care about in a plan
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlanStats {
+    pub total: f64,
+    pub active_share: f64,
+    pub weekend_share: f64,
+    pub longest_streak: f64,
+}
+
+impl PlanStats {
+    pub fn measure(commits: &[CommitInfo], start: NaiveDate, end: NaiveDate) -> Self {
+        let mut per_day: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+        for commit in commits {
+            *per_day.entry(commit.date.date_naive()).or_insert(0) += 1;
+        }
+        let days = ((end - start).num_days() + 1).max(1) as f64;
+        let weekend = commits.iter()
+            .filter(|c| matches!(c.date.weekday(), Weekday::Sat | Weekday::Sun))
+            .count();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in per_day.keys() {
+            run = if previous == Some(*day - Duration::days(1)) { run + 1 } else { 1 };
+            longest = longest.max(run);
+            previous = Some(*day);
+        }
+
+        Self {
+            total: commits.len() as f64,
+            active_share: per_day.len() as f64 / days,
+            weekend_share: if commits.is_empty() { 0.0 } else { weekend as f64 / commits.len() as f64 },
+            longest_streak: longest as f64,
+        }
+    }
+
+    fn mean(samples: &[Self]) -> Self {
+        let n = samples.len().max(1) as f64;
+        Self {
+            total: samples.iter().map(|s| s.total).sum::<f64>() / n,
+            active_share: samples.iter().map(|s| s.active_share).sum::<f64>() / n,
+            weekend_share: samples.iter().map(|s| s.weekend_share).sum::<f64>() / n,
+            longest_streak: samples.iter().map(|s| s.longest_streak).sum::<f64>() / n,
+        }
+    }
+}
+
+// Spread of one stat over many generated plans
+#[derive(Debug, Clone, Copy)]
+pub struct Distribution {
+    pub mean: f64,
+    pub p5: f64,
+    pub median: f64,
+    pub p95: f64,
+}
+
+impl Distribution {
+    pub fn of(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+        };
+        Self {
+            mean: sorted.iter().sum::<f64>() / sorted.len().max(1) as f64,
+            p5: percentile(0.05),
+            median: percentile(0.5),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+// What a fitted pattern should produce over the range. Unset fields are
+// left free.
+#[derive(Debug, Clone, Default)]
+pub struct Constraints {
+    pub total: Option<u32>,
+    pub active_share: Option<f64>,
+    pub weekend_share: Option<f64>,
+    pub max_streak: Option<u32>,
+}
+
+impl Constraints {
+    pub fn validate(&self) -> Result<()> {
+        if self.total.is_none() && self.active_share.is_none() && self.weekend_share.is_none() && self.max_streak.is_none() {
+            return Err(GitHubGridError::Config("Give at least one constraint to fit".to_string()));
+        }
+        if self.total == Some(0) {
+            return Err(GitHubGridError::Config("Total must be at least 1".to_string()));
+        }
+        if self.max_streak == Some(0) {
+            return Err(GitHubGridError::Config("Max streak must be at least 1 day".to_string()));
+        }
+        Ok(())
+    }
+
+    // Sum of relative misses; 0 means every constraint is met. The streak
+    // is a ceiling, so only going over it counts.
+    pub fn error(&self, stats: &PlanStats) -> f64 {
+        let mut error = 0.0;
+        if let Some(total) = self.total {
+            error += (stats.total - total as f64).abs() / total as f64;
+        }
+        if let Some(share) = self.active_share {
+            error += (stats.active_share - share).abs();
+        }
+        if let Some(share) = self.weekend_share {
+            error += (stats.weekend_share - share).abs();
+        }
+        if let Some(max) = self.max_streak {
+            error += (stats.longest_streak - max as f64).max(0.0) / max as f64;
+        }
+        error
+    }
+}
+
+pub struct Fit {
+    pub config: PatternConfig,
+    pub stats: PlanStats,
+    pub error: f64,
+}
+
+// Random search over the pattern parameters, rescaling each candidate's
+// volume toward the total before scoring it, then a round of small
+// perturbations around the best candidate.
+pub fn fit(constraints: &Constraints, start: NaiveDate, end: NaiveDate, candidates: usize) -> Result<Fit> {
+    constraints.validate()?;
+    let mut rng = rng();
+    let mut best: Option<Fit> = None;
+    let explore = (candidates * 2 / 3).max(1);
+
+    for index in 0..candidates.max(1) {
+        let config = match &best {
+            Some(best) if index >= explore => perturb(&best.config, &mut rng)?,
+            _ => random_config(&mut rng)?,
+        };
+        let (config, stats) = evaluate(config, constraints, start, end)?;
+        let error = constraints.error(&stats);
+        if best.as_ref().is_none_or(|b| error < b.error) {
+            best = Some(Fit { config, stats, error });
+        }
+    }
+
+    Ok(best.expect("at least one candidate was scored"))
+}
+
+// Mean stats of a config, after one volume correction toward the total
+fn evaluate(config: PatternConfig, constraints: &Constraints, start: NaiveDate, end: NaiveDate) -> Result<(PatternConfig, PlanStats)> {
+    let measure = |config: &PatternConfig| {
+        let pattern = ConfigurablePattern::new(config.clone());
+        let samples: Vec<PlanStats> = (0..SAMPLES)
+            .map(|_| PlanStats::measure(&pattern.generate(start, end), start, end))
+            .collect();
+        PlanStats::mean(&samples)
+    };
+    let stats = measure(&config);
+    let Some(total) = constraints.total else {
+        return Ok((config, stats));
+    };
+    let ratio = if stats.total == 0.0 { 2.0 } else { total as f64 / stats.total };
+    let config = config.to_builder().volume((config.volume() * ratio).clamp(0.05, 20.0)).build()?;
+    let stats = measure(&config);
+    Ok((config, stats))
+}
+
+const INTENSITIES: [IntensityLevel; 5] = [
+    IntensityLevel::Casual,
+    IntensityLevel::Active,
+    IntensityLevel::Maintainer,
+    IntensityLevel::Hyperactive,
+    IntensityLevel::Extreme,
+];
+
+fn random_config(rng: &mut impl Rng) -> Result<PatternConfig> {
+    let min_vacation = rng.random_range(0..=5);
+    PatternConfig::builder()
+        .intensity(INTENSITIES[rng.random_range(0..INTENSITIES.len())].clone())
+        .weekly_rhythm(rng.random_bool(0.7))
+        .vacation_frequency(rng.random_range(0.0..0.08))
+        .vacation_duration(min_vacation, min_vacation + rng.random_range(0..=10))
+        .spike_probability(rng.random_range(0.0..0.45))
+        .spike_multiplier(rng.random_range(1.0..4.0))
+        .build()
+}
+
+// A close neighbour of `config`: same intensity and rhythm, with the
+// continuous parameters nudged by up to ~20%
+fn perturb(config: &PatternConfig, rng: &mut impl Rng) -> Result<PatternConfig> {
+    let mut nudge = |value: f64, min: f64, max: f64| (value * rng.random_range(0.8..1.2)).clamp(min, max);
+    let (min_vacation, max_vacation) = config.vacation_duration();
+    let vacation_frequency = nudge(config.vacation_frequency().max(0.002), 0.0, 0.2);
+    let spike_probability = nudge(config.spike_probability().max(0.01), 0.0, 0.6);
+    let spike_multiplier = nudge(config.spike_multiplier(), 1.0, 6.0);
+    let volume = nudge(config.volume(), 0.05, 20.0);
+    config.to_builder()
+        .vacation_frequency(vacation_frequency)
+        .vacation_duration(min_vacation, max_vacation)
+        .spike_probability(spike_probability)
+        .spike_multiplier(spike_multiplier)
+        .volume(volume)
+        .build()
+}
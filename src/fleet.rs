@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::NaiveDate;
+use rand::{rng, Rng};
+use serde::Deserialize;
+use github_grid::error::{GitHubGridError, Result};
+use github_grid::patterns::CommitInfo;
+
+// One target repo in a fleet file. Set fields override the command line
+// for this repo; unset ones come from the command line, then the repo's
+// own .github-grid.toml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetRepo {
+    pub path: PathBuf,
+    pub pattern: Option<String>,
+    pub target_total: Option<u32>,
+    pub branch: Option<String>,
+    // Allocation mode: relative share of each day's commits and a daily cap
+    pub weight: Option<f64>,
+    pub max_per_day: Option<u32>,
+}
+
+impl FleetRepo {
+    fn weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FleetFile {
+    #[serde(default)]
+    allocate: bool,
+    #[serde(default)]
+    repo: Vec<FleetRepo>,
+}
+
+// Several grid repos processed by a single `--fleet` invocation. Normally
+// each repo gets its own run; with `allocate = true` one plan is generated
+// from the command-line settings and its commits are shared out.
+#[derive(Debug, Clone)]
+pub struct Fleet {
+    pub allocate: bool,
+    pub repos: Vec<FleetRepo>,
+}
+
+impl Fleet {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file: FleetFile = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid fleet file {}: {}", path.display(), e)))?;
+        if file.repo.is_empty() {
+            return Err(GitHubGridError::Config(format!("Fleet file {} lists no [[repo]] entries", path.display())));
+        }
+
+        for repo in &file.repo {
+            if repo.weight.is_some_and(|weight| !weight.is_finite() || weight < 0.0) {
+                return Err(GitHubGridError::Config(format!("Invalid weight for fleet repo {}", repo.path.display())));
+            }
+            if file.allocate && (repo.pattern.is_some() || repo.target_total.is_some()) {
+                return Err(GitHubGridError::Config(format!(
+                    "Fleet repo {} sets pattern/target_total, which allocation mode takes from the command line",
+                    repo.path.display()
+                )));
+            }
+        }
+        if file.allocate && file.repo.iter().all(|repo| repo.weight() == 0.0) {
+            return Err(GitHubGridError::Config("At least one fleet repo needs a positive weight".to_string()));
+        }
+
+        let repos = file.repo.into_iter()
+            .map(|repo| FleetRepo { path: expand_home(&repo.path), ..repo })
+            .collect();
+        Ok(Self { allocate: file.allocate, repos })
+    }
+
+    // Shares each day's commits among the repos by weight, never giving a
+    // repo more than its `max_per_day`. Returns one plan per repo (in fleet
+    // order) and the number of commits no repo had room for.
+    pub fn allocate(&self, commits: Vec<CommitInfo>) -> (Vec<Vec<CommitInfo>>, usize) {
+        let mut rng = rng();
+        let mut plans = vec![Vec::new(); self.repos.len()];
+        let mut per_day: BTreeMap<(usize, NaiveDate), u32> = BTreeMap::new();
+        let mut dropped = 0;
+
+        for commit in commits {
+            let day = commit.date.date_naive();
+            let open: Vec<usize> = (0..self.repos.len())
+                .filter(|&i| self.repos[i].weight() > 0.0)
+                .filter(|&i| self.repos[i].max_per_day.is_none_or(|cap| per_day.get(&(i, day)).copied().unwrap_or(0) < cap))
+                .collect();
+            if open.is_empty() {
+                dropped += 1;
+                continue;
+            }
+
+            let total: f64 = open.iter().map(|&i| self.repos[i].weight()).sum();
+            let mut roll = rng.random_range(0.0..total);
+            let chosen = open.iter()
+                .copied()
+                .find(|&i| {
+                    roll -= self.repos[i].weight();
+                    roll < 0.0
+                })
+                .unwrap_or(open[open.len() - 1]);
+
+            *per_day.entry((chosen, day)).or_insert(0) += 1;
+            plans[chosen].push(commit);
+        }
+        (plans, dropped)
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var("HOME")) {
+        (Ok(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use clap::ValueEnum;
+use git2::Repository;
+use crate::error::{GitHubGridError, Result};
+use crate::github::GitHubClient;
+
+// Which tool clones from GitHub
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CloneMethod {
+    /// gh first, then the system git over your preferred protocol and the other one
+    #[default]
+    Auto,
+    /// Only `gh repo clone`
+    Gh,
+    /// Only the system `git clone`, like pushes
+    Git,
+}
+
+// How to clone, and how much of a repo to materialize, for adopting big ones
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    // Check out only the top-level files (`git clone --sparse`)
+    pub sparse: bool,
+    // Partial clone filter such as `blob:none`, fetching objects on demand
+    pub filter: Option<String>,
+    pub method: CloneMethod,
+    // Proxy for a system git clone, overriding git's http.proxy
+    pub proxy: Option<String>,
+}
+
+impl CloneOptions {
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.sparse {
+            args.push("--sparse".to_string());
+        }
+        if let Some(filter) = &self.filter {
+            args.push(format!("--filter={}", filter));
+        }
+        args
+    }
+}
+
+// The account's graph over a range, with the emails commits are attributed by
+#[derive(Debug, Clone, Default)]
+pub struct AccountSnapshot {
+    pub calendar: BTreeMap<NaiveDate, u32>,
+    pub emails: Option<Vec<String>>,
+}
+
+// What the CLI needs from a forge: the account, its contribution graph and
+// repo management. GitHubClient talks to GitHub through gh; MockForge
+// stands in for it with --offline.
+pub trait Forge {
+    fn username(&self) -> &str;
+    fn user_emails(&self) -> Result<Vec<String>>;
+    fn contribution_calendar(&self, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>>;
+    fn user_contribution_calendar(&self, login: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>>;
+    // The graph and the account's emails together, for checking attribution.
+    // Emails are None when they can't be read, such as without user:email.
+    fn account_snapshot(&self, from: NaiveDate, to: NaiveDate) -> Result<AccountSnapshot> {
+        Ok(AccountSnapshot {
+            calendar: self.contribution_calendar(from, to)?,
+            emails: self.user_emails().ok(),
+        })
+    }
+    fn public_commit_days(&self, from: NaiveDate, to: NaiveDate, skip: &[String]) -> Result<BTreeMap<NaiveDate, u32>>;
+    fn repo_exists(&self, repo_name: &str) -> Result<bool>;
+    fn create_repo(&self, name: &str) -> Result<String>;
+    fn delete_repo(&self, repo_name: &str) -> Result<()>;
+    fn archive_repo(&self, repo_name: &str, archived_name: &str) -> Result<String>;
+    fn clone_repo(&self, repo_name: &str, local_path: &str, options: &CloneOptions) -> Result<()>;
+    fn mirror_repo(&self, repo_name: &str, dest: &str) -> Result<()>;
+    // Where a repo can be viewed, for messages
+    fn repo_url(&self, repo_name: &str) -> String;
+}
+
+impl Forge for GitHubClient {
+    fn username(&self) -> &str {
+        GitHubClient::username(self)
+    }
+
+    fn user_emails(&self) -> Result<Vec<String>> {
+        GitHubClient::user_emails(self)
+    }
+
+    fn contribution_calendar(&self, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        GitHubClient::contribution_calendar(self, from, to)
+    }
+
+    fn user_contribution_calendar(&self, login: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        GitHubClient::user_contribution_calendar(self, login, from, to)
+    }
+
+    fn account_snapshot(&self, from: NaiveDate, to: NaiveDate) -> Result<AccountSnapshot> {
+        GitHubClient::account_snapshot(self, from, to)
+    }
+
+    fn public_commit_days(&self, from: NaiveDate, to: NaiveDate, skip: &[String]) -> Result<BTreeMap<NaiveDate, u32>> {
+        GitHubClient::public_commit_days(self, from, to, skip)
+    }
+
+    fn repo_exists(&self, repo_name: &str) -> Result<bool> {
+        GitHubClient::repo_exists(self, repo_name)
+    }
+
+    fn create_repo(&self, name: &str) -> Result<String> {
+        GitHubClient::create_repo(self, name)
+    }
+
+    fn delete_repo(&self, repo_name: &str) -> Result<()> {
+        GitHubClient::delete_repo(self, repo_name)
+    }
+
+    fn archive_repo(&self, repo_name: &str, archived_name: &str) -> Result<String> {
+        GitHubClient::archive_repo(self, repo_name, archived_name)
+    }
+
+    fn clone_repo(&self, repo_name: &str, local_path: &str, options: &CloneOptions) -> Result<()> {
+        GitHubClient::clone_repo(self, repo_name, local_path, options)
+    }
+
+    fn mirror_repo(&self, repo_name: &str, dest: &str) -> Result<()> {
+        GitHubClient::mirror_repo(self, repo_name, dest)
+    }
+
+    fn repo_url(&self, repo_name: &str) -> String {
+        format!("https://github.com/{}/{}", GitHubClient::username(self), repo_name)
+    }
+}
+
+// Local stand-in for GitHub: "remote" repos are bare repos under
+// `root/<username>/`, so init, pushes and verification all work without a
+// token or network. The contribution graph counts the commits pushed there;
+// other users' graphs come from `with_calendar` and are otherwise unknown.
+pub struct MockForge {
+    root: PathBuf,
+    username: String,
+    emails: Vec<String>,
+    calendars: BTreeMap<String, BTreeMap<NaiveDate, u32>>,
+}
+
+impl MockForge {
+    pub fn new(root: impl Into<PathBuf>, username: &str) -> Self {
+        Self { root: root.into(), username: username.to_string(), emails: Vec::new(), calendars: BTreeMap::new() }
+    }
+
+    // Emails on the mock account; defaults to git's user.email
+    pub fn with_emails(mut self, emails: Vec<String>) -> Self {
+        self.emails = emails;
+        self
+    }
+
+    // Canned contribution graph for another user
+    pub fn with_calendar(mut self, login: &str, days: BTreeMap<NaiveDate, u32>) -> Self {
+        self.calendars.insert(login.to_lowercase(), days);
+        self
+    }
+
+    fn repo_path(&self, repo_name: &str) -> PathBuf {
+        self.root.join(&self.username).join(format!("{}.git", repo_name))
+    }
+
+    fn existing_repo(&self, repo_name: &str) -> Result<PathBuf> {
+        let path = self.repo_path(repo_name);
+        if !path.exists() {
+            return Err(GitHubGridError::NotFound(format!("{}/{} (offline)", self.username, repo_name)));
+        }
+        Ok(path)
+    }
+
+    fn git(&self, args: &[&str], action: &str) -> Result<()> {
+        let output = Command::new("git").args(args).output()?;
+        if !output.status.success() {
+            return Err(GitHubGridError::Repository(format!(
+                "Failed to {} offline repository: {}", action, String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Forge for MockForge {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn user_emails(&self) -> Result<Vec<String>> {
+        if !self.emails.is_empty() {
+            return Ok(self.emails.clone());
+        }
+        let config = git2::Config::open_default()?;
+        Ok(config.get_string("user.email").ok().into_iter().collect())
+    }
+
+    // Commits on every branch of every mock repo, by their author date
+    fn contribution_calendar(&self, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        let mut calendar = BTreeMap::new();
+        let Ok(entries) = fs::read_dir(self.root.join(&self.username)) else {
+            return Ok(calendar);
+        };
+        for entry in entries {
+            let Ok(repo) = Repository::open_bare(entry?.path()) else {
+                continue;
+            };
+            let mut walk = repo.revwalk()?;
+            walk.push_glob("refs/heads/*")?;
+            for oid in walk {
+                let time = repo.find_commit(oid?)?.author().when();
+                let date = FixedOffset::east_opt(time.offset_minutes() * 60)
+                    .and_then(|offset| DateTime::from_timestamp(time.seconds(), 0).map(|dt| dt.with_timezone(&offset)))
+                    .map(|dt| dt.date_naive());
+                if let Some(date) = date.filter(|date| (from..=to).contains(date)) {
+                    *calendar.entry(date).or_insert(0) += 1;
+                }
+            }
+        }
+        Ok(calendar)
+    }
+
+    fn user_contribution_calendar(&self, login: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        if login.eq_ignore_ascii_case(&self.username) {
+            return self.contribution_calendar(from, to);
+        }
+        let days = self.calendars.get(&login.to_lowercase())
+            .ok_or_else(|| GitHubGridError::NotFound(format!("user {} (offline)", login)))?;
+        Ok(days.range(from..=to).map(|(day, count)| (*day, *count)).collect())
+    }
+
+    // Mock repos are all private, so there's no public activity
+    fn public_commit_days(&self, _from: NaiveDate, _to: NaiveDate, _skip: &[String]) -> Result<BTreeMap<NaiveDate, u32>> {
+        Ok(BTreeMap::new())
+    }
+
+    fn repo_exists(&self, repo_name: &str) -> Result<bool> {
+        Ok(self.repo_path(repo_name).exists())
+    }
+
+    fn create_repo(&self, name: &str) -> Result<String> {
+        let path = self.repo_path(name);
+        if path.exists() {
+            return Err(GitHubGridError::Conflict(format!("{}/{} already exists (offline)", self.username, name)));
+        }
+        fs::create_dir_all(&path)?;
+        Repository::init_bare(&path)?;
+        Ok(path.display().to_string())
+    }
+
+    fn delete_repo(&self, repo_name: &str) -> Result<()> {
+        fs::remove_dir_all(self.existing_repo(repo_name)?)?;
+        Ok(())
+    }
+
+    fn archive_repo(&self, repo_name: &str, archived_name: &str) -> Result<String> {
+        fs::rename(self.existing_repo(repo_name)?, self.repo_path(archived_name))?;
+        Ok(archived_name.to_string())
+    }
+
+    fn clone_repo(&self, repo_name: &str, local_path: &str, options: &CloneOptions) -> Result<()> {
+        let source = self.existing_repo(repo_name)?.display().to_string();
+        // Plain local clones ignore --filter; over file:// git honours it
+        let source = if options.filter.is_some() { format!("file://{}", source) } else { source };
+        let extra = options.git_args();
+        let mut args = vec!["clone", "--quiet"];
+        args.extend(extra.iter().map(String::as_str));
+        args.extend([source.as_str(), local_path]);
+        self.git(&args, "clone")
+    }
+
+    fn mirror_repo(&self, repo_name: &str, dest: &str) -> Result<()> {
+        let source = self.existing_repo(repo_name)?.display().to_string();
+        self.git(&["clone", "--quiet", "--mirror", &source, dest], "back up")
+    }
+
+    fn repo_url(&self, repo_name: &str) -> String {
+        self.repo_path(repo_name).display().to_string()
+    }
+}
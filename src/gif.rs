@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Duration, NaiveDate};
+use crate::readme_stats::quartile_levels;
+
+const CELL: usize = 10;
+const GAP: usize = 3;
+const MARGIN: usize = 8;
+// Hundredths of a second per week, with a pause on the finished graph
+const FRAME_DELAY: u16 = 8;
+const FINAL_DELAY: u16 = 300;
+
+// GitHub's light theme: background, then the empty cell and four shades,
+// padded to a power of two
+const PALETTE: [[u8; 3]; 8] = [
+    [0xff, 0xff, 0xff],
+    [0xeb, 0xed, 0xf0],
+    [0x9b, 0xe9, 0xa8],
+    [0x40, 0xc4, 0x63],
+    [0x30, 0xa1, 0x4e],
+    [0x21, 0x6e, 0x39],
+    [0xff, 0xff, 0xff],
+    [0xff, 0xff, 0xff],
+];
+const BACKGROUND: u8 = 0;
+const EMPTY: u8 = 1;
+
+// Looping animation of the graph for start..=end filling in a week per
+// frame. Shades are cut at the quartiles of the whole range so a day keeps
+// its colour once drawn.
+pub fn grid_animation(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate) -> Vec<u8> {
+    let level = quartile_levels(days, start, end);
+    let first_sunday = start - Duration::days(start.weekday().num_days_from_sunday() as i64);
+    let weeks = ((end - first_sunday).num_days() / 7 + 1) as usize;
+    let width = MARGIN * 2 + weeks * (CELL + GAP) - GAP;
+    let height = MARGIN * 2 + 7 * (CELL + GAP) - GAP;
+
+    let mut pixels = vec![BACKGROUND; width * height];
+    let paint_week = |pixels: &mut [u8], week: usize, shade: &dyn Fn(u32) -> u8| {
+        for weekday in 0..7 {
+            let day = first_sunday + Duration::days((week * 7 + weekday) as i64);
+            if day < start || day > end {
+                continue;
+            }
+            let shade = shade(days.get(&day).copied().unwrap_or(0));
+            let (left, top) = (MARGIN + week * (CELL + GAP), MARGIN + weekday * (CELL + GAP));
+            for y in top..top + CELL {
+                pixels[y * width + left..y * width + left + CELL].fill(shade);
+            }
+        }
+    };
+    // The first frame is the whole range's empty grid
+    for week in 0..weeks {
+        paint_week(&mut pixels, week, &|_| EMPTY);
+    }
+    let mut frames = vec![pixels.clone()];
+    for week in 0..weeks {
+        paint_week(&mut pixels, week, &|count| EMPTY + level(count) as u8);
+        frames.push(pixels.clone());
+    }
+    encode(width as u16, height as u16, &frames)
+}
+
+// GIF89a with the global palette above, one full frame per entry, looping
+// forever
+fn encode(width: u16, height: u16, frames: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    // Global colour table of 8 entries, 8 bits per channel
+    out.extend([0xf2, BACKGROUND, 0]);
+    out.extend(PALETTE.iter().flatten());
+    out.extend([0x21, 0xff, 0x0b]);
+    out.extend(b"NETSCAPE2.0");
+    out.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for (index, frame) in frames.iter().enumerate() {
+        let delay = if index + 1 == frames.len() { FINAL_DELAY } else { FRAME_DELAY };
+        out.extend([0x21, 0xf9, 0x04, 0x04]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.push(0);
+        out.push(MIN_CODE_SIZE);
+        for block in lzw(frame).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    out
+}
+
+const MIN_CODE_SIZE: u8 = 3;
+const MAX_CODE: u16 = 4095;
+
+// Variable-width LZW as GIF expects it, codes packed least significant bit
+// first
+fn lzw(pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+    let mut writer = BitWriter::default();
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut size = MIN_CODE_SIZE + 1;
+    let mut last = end;
+
+    writer.write(clear, size);
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end, size);
+        return writer.finish();
+    };
+    let mut prefix = first as u16;
+    for &pixel in rest {
+        if let Some(&code) = dictionary.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, size);
+        last += 1;
+        dictionary.insert((prefix, pixel), last);
+        if last >= 1 << size {
+            size += 1;
+        }
+        if last == MAX_CODE {
+            // Table full: start over rather than coding on with a stale one
+            writer.write(clear, size);
+            dictionary.clear();
+            size = MIN_CODE_SIZE + 1;
+            last = end;
+        }
+        prefix = pixel as u16;
+    }
+    writer.write(prefix, size);
+    writer.write(end, size);
+    writer.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
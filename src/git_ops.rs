@@ -1,134 +1,1024 @@
-use chrono::{DateTime, Local};
-use git2::{Repository, Signature, Time, Oid};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate};
+use git2::{ErrorCode, Repository, Signature, Sort, Time, Tree, Oid};
+use rand::rng;
+use crate::languages::LanguageMix;
+use crate::messages::MessageBank;
 use crate::patterns::CommitInfo;
-use crate::error::Result;
+use crate::error::{GitHubGridError, Result};
+
+// Commit counts split by whether this tool created them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommitCounts {
+    pub autogen: u32,
+    pub real: u32,
+}
+
+// Outcome of dropping a run's commits from the branch
+#[derive(Debug, Clone, Copy)]
+pub struct RemovedCommits {
+    pub removed: usize,
+    pub replayed: usize,
+    pub old_tip: Oid,
+    pub new_tip: Oid,
+}
+
+// Where the target branch stands against origin after a fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteSync {
+    // No origin, or origin doesn't have the branch yet
+    NoRemoteBranch,
+    UpToDate,
+    // Moved forward by this many commits from origin
+    FastForwarded(usize),
+    // Has this many commits origin doesn't, e.g. from a --no-push run
+    Ahead(usize),
+    Diverged { ahead: usize, behind: usize },
+}
+
+// A commit on the branch as stored, for history-wide checks
+#[derive(Debug, Clone)]
+pub struct HistoryCommit {
+    pub oid: Oid,
+    pub seconds: i64,
+    pub offset_minutes: i32,
+    pub author_email: String,
+    pub message: String,
+}
+
+// One progress line from `git push --progress`, such as
+// "Writing objects:  45% (9/20), 1.20 MiB | 300.00 KiB/s"
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushProgress {
+    pub stage: String,
+    pub current: u64,
+    pub total: u64,
+    pub bytes: Option<u64>,
+}
+
+impl PushProgress {
+    // None for lines without an (a/b) count, like "Enumerating objects: 20"
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim().trim_start_matches("remote:").trim();
+        let (stage, rest) = line.split_once(':')?;
+        let (counts, after) = rest.split_once('(')?.1.split_once(')')?;
+        let (current, total) = counts.split_once('/')?;
+        let bytes = after.trim_start_matches(',').split('|').next().and_then(|size| {
+            let mut parts = size.split_whitespace();
+            let value: f64 = parts.next()?.parse().ok()?;
+            let unit = match parts.next()? {
+                "bytes" => 1.0,
+                "KiB" => 1024.0,
+                "MiB" => 1024.0 * 1024.0,
+                "GiB" => 1024.0 * 1024.0 * 1024.0,
+                _ => return None,
+            };
+            Some((value * unit) as u64)
+        });
+        Some(Self {
+            stage: stage.trim().to_string(),
+            current: current.trim().parse().ok()?,
+            total: total.trim().parse().ok()?,
+            bytes,
+        })
+    }
+}
+
+// Replacement author/committer time, identity and/or message for one commit
+#[derive(Debug, Clone, Default)]
+pub struct CommitEdit {
+    pub seconds: Option<i64>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub message: Option<String>,
+}
+
+impl CommitEdit {
+    fn touches_signature(&self) -> bool {
+        self.seconds.is_some() || self.name.is_some() || self.email.is_some()
+    }
+}
+
+// Outcome of rewriting commits in place on the branch
+#[derive(Debug, Clone, Copy)]
+pub struct RewrittenCommits {
+    pub edited: usize,
+    pub replayed: usize,
+    pub old_tip: Oid,
+    pub new_tip: Oid,
+}
+
+// What each generated commit changes in the tree
+#[derive(Debug, Clone, Default)]
+pub enum CommitContent {
+    // No file changes, like `git commit --allow-empty`
+    #[default]
+    Empty,
+    // A line with the commit's date and sequence number appended to
+    // `log/<YYYY-MM>.jsonl`
+    Log,
+    // A snippet appended to a source file picked from the mix
+    Languages(LanguageMix),
+}
+
+// Supplies the token for fetches and pushes, called only once one of
+// them runs so commands that stay local never need it. None leaves git
+// to the user's own credentials.
+pub type PushToken = Box<dyn Fn() -> Result<Option<String>> + Send + Sync>;
+
+// Message of the --readme-stats commit, which shouldn't count as activity
+// of its own
+pub const STATS_MESSAGE: &str = "Update activity stats";
+
+// Client-side hooks around `git commit`, in the order git runs them
+pub const COMMIT_HOOKS: [&str; 4] = ["pre-commit", "prepare-commit-msg", "commit-msg", "post-commit"];
 
 pub struct GitOperations {
     repo: Repository,
+    branch: String,
+    signoff: bool,
+    run_hooks: bool,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    content: CommitContent,
+    messages: MessageBank,
+    proxy: Option<String>,
+    push_token: Option<PushToken>,
 }
 
 impl GitOperations {
     pub fn new(repo: Repository) -> Self {
-        Self { repo }
+        Self { repo, branch: "main".to_string(), signoff: false, run_hooks: false, author_name: None, author_email: None, content: CommitContent::Empty, messages: MessageBank::builtin(), proxy: None, push_token: None }
+    }
+    
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = branch.to_string();
+        self
+    }
+    
+    // Overrides the name and/or email from git config for new commits
+    pub fn with_identity(mut self, name: Option<String>, email: Option<String>) -> Self {
+        self.author_name = name;
+        self.author_email = email;
+        self
+    }
+    
+    // Name and email new commits are made with
+    pub fn identity(&self) -> Result<(String, String)> {
+        let (name, email) = author_identity()?;
+        Ok((
+            self.author_name.clone().unwrap_or(name),
+            self.author_email.clone().unwrap_or(email),
+        ))
+    }
+    
+    // Append a DCO `Signed-off-by:` trailer to every commit created
+    pub fn with_signoff(mut self, signoff: bool) -> Self {
+        self.signoff = signoff;
+        self
+    }
+    
+    // Proxy for pushes, overriding git's http.proxy. Without it git still
+    // picks up http.proxy and the HTTPS_PROXY/ALL_PROXY variables itself.
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+    
+    // Token for fetches and pushes to github.com, such as a GitHub App
+    // installation's, in place of the user's own credentials. SSH remotes
+    // are sent over HTTPS so the token applies.
+    pub fn with_push_token(mut self, token: Option<PushToken>) -> Self {
+        self.push_token = token;
+        self
+    }
+    
+    // Proxy and token settings for git commands that talk to the remote
+    fn remote_config(&self, command: &mut Command) -> Result<()> {
+        if let Some(proxy) = &self.proxy {
+            command.arg("-c").arg(format!("http.proxy={}", proxy));
+        }
+        if let Some(token) = self.push_token.as_ref().map(|token| token()).transpose()?.flatten() {
+            command.env("GH_TOKEN", token).env("GIT_TERMINAL_PROMPT", "0").args([
+                "-c", "credential.helper=",
+                "-c", crate::github::TOKEN_CREDENTIAL_HELPER,
+                "-c", "url.https://github.com/.insteadOf=git@github.com:",
+                "-c", "url.https://github.com/.insteadOf=ssh://git@github.com/",
+            ]);
+        }
+        Ok(())
+    }
+    
+    // Run the repo's commit hooks around every generated commit, as `git
+    // commit` would. libgit2 never runs hooks on its own.
+    pub fn with_hooks(mut self, run_hooks: bool) -> Self {
+        self.run_hooks = run_hooks;
+        self
+    }
+    
+    pub fn with_content(mut self, content: CommitContent) -> Self {
+        self.content = content;
+        self
+    }
+    
+    // Messages for commits that don't bring their own (the built-in bank
+    // by default)
+    pub fn with_messages(mut self, messages: MessageBank) -> Self {
+        self.messages = messages;
+        self
     }
     
     pub fn repo(&self) -> &Repository {
         &self.repo
     }
     
-    pub fn get_latest_autogen_commit(&mut self) -> Result<Option<DateTime<Local>>> {
+    fn branch_ref(&self) -> String {
+        format!("refs/heads/{}", self.branch)
+    }
+    
+    // Latest commit on the target branch, or None if it doesn't exist yet
+    fn branch_tip(&self) -> Result<Option<git2::Commit<'_>>> {
+        match self.repo.find_reference(&self.branch_ref()) {
+            Ok(reference) => Ok(Some(reference.peel_to_commit()?)),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    // Current tip of the target branch, if it has any commits
+    pub fn branch_head(&self) -> Result<Option<Oid>> {
+        Ok(self.branch_tip()?.map(|commit| commit.id()))
+    }
+    
+    // Walks the target branch (or, before it exists, whatever HEAD points at
+    // since that's where it will be created) newest first, passing each
+    // commit's time and message to `visit` until it returns false.
+    fn walk_history(&self, mut visit: impl FnMut(DateTime<Local>, &str) -> bool) -> Result<()> {
         let mut revwalk = self.repo.revwalk()?;
-        revwalk.push_head()?;
+        if self.branch_tip()?.is_some() {
+            revwalk.push_ref(&self.branch_ref())?;
+        } else if revwalk.push_head().is_err() {
+            return Ok(()); // Unborn: no history yet
+        }
         
         for oid in revwalk {
-            let oid = oid?;
-            let commit = self.repo.find_commit(oid)?;
-            
-            if let Some(message) = commit.message() {
-                if message.starts_with("[AutoGen]") {
-                    let time = commit.time();
-                    let timestamp = time.seconds();
-                    let datetime = DateTime::from_timestamp(timestamp, 0)
-                        .unwrap()
-                        .with_timezone(&Local);
-                    return Ok(Some(datetime));
-                }
+            let commit = self.repo.find_commit(oid?)?;
+            let time = DateTime::from_timestamp(commit.time().seconds(), 0)
+                .unwrap()
+                .with_timezone(&Local);
+            if !visit(time, commit.message().unwrap_or("")) {
+                break;
             }
         }
         
-        Ok(None)
+        Ok(())
     }
     
-    pub fn create_commit(&mut self, commit_info: &CommitInfo) -> Result<Oid> {
-        // Ensure we're on main branch
-        self.ensure_main_branch()?;
-        
-        // Get current tree (we'll create empty commits like --allow-empty)
-        let tree = match self.repo.head() {
-            Ok(head) => {
-                let commit = head.peel_to_commit()?;
-                commit.tree()?
+    pub fn get_latest_autogen_commit(&mut self) -> Result<Option<DateTime<Local>>> {
+        let mut latest = None;
+        self.walk_history(|time, message| {
+            if is_autogen(message) {
+                latest = Some(time);
+                return false;
+            }
+            true
+        })?;
+        Ok(latest)
+    }
+    
+    // Generated and real commits dated within `year`
+    pub fn count_commits_in_year(&self, year: i32) -> Result<CommitCounts> {
+        let mut counts = CommitCounts::default();
+        self.walk_history(|time, message| {
+            if time.year() == year && !is_stats_update(message) {
+                if is_autogen(message) {
+                    counts.autogen += 1;
+                } else {
+                    counts.real += 1;
+                }
             }
-            Err(_) => {
-                // If no HEAD, create empty tree
-                let tree_id = self.repo.treebuilder(None)?.write()?;
-                self.repo.find_tree(tree_id)?
+            true
+        })?;
+        Ok(counts)
+    }
+    
+    // Number of [AutoGen] commits on each day within start..=end
+    pub fn autogen_days(&self, start: NaiveDate, end: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        let mut days = BTreeMap::new();
+        self.walk_history(|time, message| {
+            let day = time.date_naive();
+            if is_autogen(message) && day >= start && day <= end {
+                *days.entry(day).or_insert(0) += 1;
             }
+            true
+        })?;
+        Ok(days)
+    }
+    
+    // How many of `oids` the target branch contains
+    pub fn count_on_branch(&self, oids: &HashSet<Oid>) -> Result<usize> {
+        self.count_reachable(&self.branch_ref(), oids)
+    }
+    
+    // How many of `oids` the remote branch contains as of the last push or
+    // fetch
+    pub fn count_on_remote(&self, oids: &HashSet<Oid>) -> Result<usize> {
+        self.count_reachable(&format!("refs/remotes/origin/{}", self.branch), oids)
+    }
+    
+    fn count_reachable(&self, reference: &str, oids: &HashSet<Oid>) -> Result<usize> {
+        if oids.is_empty() {
+            return Ok(0);
+        }
+        let mut revwalk = self.repo.revwalk()?;
+        match revwalk.push_ref(reference) {
+            Ok(()) => {}
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        }
+        let mut count = 0;
+        for oid in revwalk {
+            if oids.contains(&oid?) {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+    
+    // Every commit on the target branch, oldest first
+    pub fn history(&self) -> Result<Vec<HistoryCommit>> {
+        if self.branch_tip()?.is_none() {
+            return Ok(Vec::new());
+        }
+        
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_ref(&self.branch_ref())?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let when = commit.author().when();
+            commits.push(HistoryCommit {
+                oid: commit.id(),
+                seconds: when.seconds(),
+                offset_minutes: when.offset_minutes(),
+                author_email: commit.author().email().unwrap_or("").to_string(),
+                message: commit.message().unwrap_or("").to_string(),
+            });
+        }
+        Ok(commits)
+    }
+    
+    pub fn create_commit(&mut self, commit_info: &CommitInfo) -> Result<Oid> {
+        // Ensure we're on the target branch
+        self.ensure_branch()?;
+        let message = match commit_info.message.as_str() {
+            "" => self.messages.message_for(commit_info.date.date_naive()),
+            message => message.to_string(),
         };
         
-        // Get parent commit
-        let parent_commit = match self.repo.head() {
-            Ok(head) => {
-                let oid = head.target().unwrap();
-                Some(self.repo.find_commit(oid)?)
+        // Hooks see the commit's own dates, like a backdated `git commit`
+        let date = format!("@{} {}", commit_info.date.timestamp(), commit_info.date.format("%z"));
+        if self.run_hooks {
+            self.run_hook("pre-commit", &[], &date)?;
+        }
+        
+        // Empty commits reuse the parent's tree; the first commit on an
+        // unborn branch gets an empty tree
+        let parent_commit = self.branch_tip()?;
+        let parent_tree = parent_commit.as_ref().map(|parent| parent.tree()).transpose()?;
+        let tree_id = match &self.content {
+            CommitContent::Empty => match &parent_tree {
+                Some(tree) => tree.id(),
+                None => self.repo.treebuilder(None)?.write()?,
+            },
+            CommitContent::Log => {
+                let file = format!("{}.jsonl", commit_info.date.format("%Y-%m"));
+                append_to_path(&self.repo, parent_tree.as_ref(), &["log", &file], |existing| {
+                    let seq = existing.iter().filter(|&&b| b == b'\n').count() + 1;
+                    let line = format!("{{\"date\":\"{}\",\"seq\":{}}}\n", commit_info.date.to_rfc3339(), seq);
+                    [existing, line.as_bytes()].concat()
+                })?
+            }
+            CommitContent::Languages(languages) => {
+                let change = languages.pick(&mut rng());
+                let components: Vec<&str> = change.dir.split('/').chain([change.file.as_str()]).collect();
+                append_to_path(&self.repo, parent_tree.as_ref(), &components, |existing| change.apply(existing))?
             }
-            Err(_) => None,
         };
+        let tree = self.repo.find_tree(tree_id)?;
         
-        // Get user's global git config for name and email
-        let config = git2::Config::open_default()?;
-        let name = config.get_string("user.name").unwrap_or_else(|_| "GitHub Grid".to_string());
-        let email = config.get_string("user.email").unwrap_or_else(|_| "github-grid@example.com".to_string());
+        let (name, email) = self.identity()?;
         
-        // Create signature with commit date
+        // Create signature with commit date, recording the local UTC offset
+        // in effect on that day like a real commit would
+        let offset_seconds = commit_info.utc_offset.unwrap_or_else(|| commit_info.date.offset().local_minus_utc());
+        let offset_minutes = offset_seconds / 60;
         let sig = Signature::new(
             &name, 
             &email,
-            &Time::new(commit_info.date.timestamp(), 0)
+            &Time::new(commit_info.date.timestamp(), offset_minutes)
         )?;
         
-        // Create empty commit (like git commit --allow-empty)
+        let mut message = if self.signoff {
+            with_signoff(&message, &name, &email)
+        } else {
+            message
+        };
+        if self.run_hooks {
+            message = self.run_message_hooks(message, &date)?;
+        }
+        
         let parents: Vec<_> = parent_commit.iter().collect();
         let commit_id = self.repo.commit(
-            Some("HEAD"),
+            Some(&self.branch_ref()),
             &sig,
             &sig,
-            &commit_info.message,
+            &message,
             &tree,
             &parents,
         )?;
         
+        if self.run_hooks {
+            // Like git, a failing post-commit hook doesn't undo the commit
+            let _ = self.run_hook("post-commit", &[], &date);
+        }
         Ok(commit_id)
     }
     
-    pub fn push_commits(&mut self) -> Result<()> {
-        let repo_path = self.repo.workdir().unwrap();
-        
-        println!("🚀 Pushing commits to GitHub...");
-        
-        let output = std::process::Command::new("git")
-            .current_dir(repo_path)
-            .args(&["push", "origin", "main"])
+    // prepare-commit-msg and commit-msg edit the message through a file,
+    // and either can reject the commit
+    fn run_message_hooks(&self, message: String, date: &str) -> Result<String> {
+        let path = self.repo.path().join("COMMIT_EDITMSG");
+        fs::write(&path, &message)?;
+        let path_arg = path.display().to_string();
+        let prepared = self.run_hook("prepare-commit-msg", &[&path_arg, "message"], date)?;
+        let checked = self.run_hook("commit-msg", &[&path_arg], date)?;
+        if !prepared && !checked {
+            return Ok(message);
+        }
+        Ok(fs::read_to_string(&path)?)
+    }
+    
+    // Runs `name` from the hooks directory if it's installed, returning
+    // whether it ran. A non-zero exit rejects the commit.
+    fn run_hook(&self, name: &str, args: &[&str], date: &str) -> Result<bool> {
+        let path = hooks_dir(&self.repo).join(name);
+        if !is_executable(&path) {
+            return Ok(false);
+        }
+        let output = Command::new(&path)
+            .args(args)
+            .current_dir(self.repo.workdir().unwrap_or(self.repo.path()))
+            .env("GIT_DIR", self.repo.path())
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
             .output()
-            .map_err(|e| crate::error::GitHubGridError::Io(e))?;
-            
+            .map_err(GitHubGridError::Io)?;
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::error::GitHubGridError::Repository(
-                format!("Git push failed: {}", stderr)
-            ));
+            return Err(GitHubGridError::Repository(format!(
+                "{} hook rejected a commit dated {}: {}", name, date.trim_start_matches('@'), stderr.trim()
+            )));
         }
+        Ok(true)
+    }
+    
+    // Stage the given worktree paths and commit them on top of HEAD
+    pub fn commit_paths(&mut self, paths: &[&Path], message: &str) -> Result<Oid> {
+        self.ensure_branch()?;
         
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if !stdout.is_empty() {
-            println!("Push output: {}", stdout.trim());
+        let mut index = self.repo.index()?;
+        for path in paths {
+            index.add_path(path)?;
         }
+        index.write()?;
         
+        let tree_id = index.write_tree()?;
+        let tree = self.repo.find_tree(tree_id)?;
+        let parent_commit = self.branch_tip()?;
+        
+        let (name, email) = self.identity()?;
+        let sig = Signature::now(&name, &email)?;
+        let parents: Vec<_> = parent_commit.iter().collect();
+        let commit_id = self.repo.commit(Some(&self.branch_ref()), &sig, &sig, message, &tree, &parents)?;
+        
+        Ok(commit_id)
+    }
+    
+    // Brings the worktree and index up to the branch tip after commits that
+    // changed files, without touching anything the user modified
+    pub fn sync_worktree(&self) -> Result<()> {
+        if matches!(self.content, CommitContent::Empty) || self.branch_tip()?.is_none() {
+            return Ok(());
+        }
+        if self.is_lean() {
+            // One-way merge: updates what's in the sparse cone, fetches
+            // missing blobs and keeps local changes, as `git checkout` does
+            return self.git(&["read-tree", "-m", "-u", "HEAD"]);
+        }
+        self.repo.checkout_head(Some(git2::build::CheckoutBuilder::new().safe()))?;
         Ok(())
     }
     
-    fn ensure_main_branch(&mut self) -> Result<()> {
-        let head = self.repo.head()?;
-        let branch_name = head.shorthand().unwrap_or("");
+    // Sparse checkouts and partial clones, which libgit2 can't check out
+    // without writing every file or failing on objects that were never
+    // fetched. Those go through the system git instead.
+    pub fn is_lean(&self) -> bool {
+        let Ok(config) = self.repo.config() else {
+            return false;
+        };
+        config.get_bool("core.sparseCheckout").unwrap_or(false)
+            || config.get_string("extensions.partialClone").is_ok()
+    }
+    
+    fn git(&self, args: &[&str]) -> Result<()> {
+        self.git_output(args).map(|_| ())
+    }
+    
+    fn git_output(&self, args: &[&str]) -> Result<String> {
+        self.run_git(args, false)
+    }
+    
+    // Runs the system git in the repo, returning its trimmed stdout.
+    // `remote` commands get the proxy and token.
+    fn run_git(&self, args: &[&str], remote: bool) -> Result<String> {
+        let mut command = Command::new("git");
+        command.current_dir(self.repo.workdir().unwrap_or(self.repo.path()));
+        if remote {
+            self.remote_config(&mut command)?;
+        }
+        let output = command.args(args).output().map_err(GitHubGridError::Io)?;
+        if !output.status.success() {
+            return Err(GitHubGridError::Repository(format!(
+                "git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+    
+    // Fetches origin and fast-forwards the target branch to it, so runs on
+    // one machine see what another (or CI) already pushed. Like pushes, this
+    // goes through the system git and the user's credentials. A branch that
+    // has diverged is left alone for the caller to decide.
+    pub fn fetch_and_fast_forward(&mut self) -> Result<RemoteSync> {
+        if self.repo.find_remote("origin").is_err() {
+            return Ok(RemoteSync::NoRemoteBranch);
+        }
+        self.run_git(&["fetch", "--quiet", "origin"], true)?;
+        let remote_ref = format!("refs/remotes/origin/{}", self.branch);
+        let remote_tip = match self.repo.refname_to_id(&remote_ref) {
+            Ok(oid) => oid,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(RemoteSync::NoRemoteBranch),
+            Err(e) => return Err(e.into()),
+        };
+        
+        let Some(local_tip) = self.branch_head()? else {
+            // Never committed here: start from what origin has
+            let behind = self.git_output(&["rev-list", "--count", &remote_ref])?.parse().unwrap_or(0);
+            self.repo.reference(&self.branch_ref(), remote_tip, false, "github-grid: from origin")?;
+            self.ensure_branch()?;
+            return Ok(RemoteSync::FastForwarded(behind));
+        };
+        let counts = self.git_output(&["rev-list", "--left-right", "--count", &format!("{}...{}", local_tip, remote_tip)])?;
+        let mut counts = counts.split_whitespace().map(|count| count.parse::<usize>().unwrap_or(0));
+        let (ahead, behind) = (counts.next().unwrap_or(0), counts.next().unwrap_or(0));
+        
+        Ok(match (ahead, behind) {
+            (0, 0) => RemoteSync::UpToDate,
+            (0, behind) => {
+                let head = self.repo.find_reference("HEAD")?;
+                if head.symbolic_target() == Some(self.branch_ref().as_str()) {
+                    // Checked out: let git move the worktree along, refusing
+                    // if that would clobber local changes
+                    self.git(&["merge", "--ff-only", "--quiet", &remote_ref])?;
+                } else {
+                    self.repo.reference(&self.branch_ref(), remote_tip, true, "github-grid: fast-forward to origin")?;
+                }
+                RemoteSync::FastForwarded(behind)
+            }
+            (ahead, 0) => RemoteSync::Ahead(ahead),
+            (ahead, behind) => RemoteSync::Diverged { ahead, behind },
+        })
+    }
+    
+    pub fn push_commits(&mut self) -> Result<()> {
+        self.git_push(&["origin", &self.branch], None)
+    }
+    
+    // Same as `push_commits`, passing each of git's progress lines to
+    // `on_progress` so a large push doesn't look stuck
+    pub fn push_commits_with_progress(&mut self, mut on_progress: impl FnMut(&PushProgress)) -> Result<()> {
+        self.git_push(&["--progress", "origin", &self.branch], Some(&mut on_progress))
+    }
+    
+    // Force-pushes the rewritten branch, refusing if the remote moved since our last fetch
+    pub fn force_push(&mut self) -> Result<()> {
+        self.git_push(&["--force-with-lease", "origin", &self.branch], None)
+    }
+    
+    pub fn push_branch(&mut self, branch: &str) -> Result<()> {
+        self.git_push(&["origin", branch], None)
+    }
+    
+    // Pushes always go through the system git rather than libgit2's
+    // transports, so the user's credential helpers and SSH setup apply as-is
+    fn push_command(&self, args: &[&str]) -> Result<std::process::Command> {
+        let mut command = std::process::Command::new("git");
+        command.current_dir(self.repo.workdir().unwrap());
+        self.remote_config(&mut command)?;
+        command.arg("push").args(args);
+        Ok(command)
+    }
+    
+    fn git_push(&self, args: &[&str], on_progress: Option<&mut dyn FnMut(&PushProgress)>) -> Result<()> {
+        let Some(on_progress) = on_progress else {
+            let output = self.push_command(args)?
+                .output()
+                .map_err(GitHubGridError::Io)?;
+                
+            if !output.status.success() {
+                return Err(push_failure(&String::from_utf8_lossy(&output.stderr)));
+            }
+            
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if !stdout.is_empty() {
+                println!("Push output: {}", stdout.trim());
+            }
+            return Ok(());
+        };
+        
+        // git rewrites progress lines in place with \r, so split on both
+        // line endings and keep the non-progress lines for error messages
+        let mut child = self.push_command(args)?
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(GitHubGridError::Io)?;
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let mut messages = String::new();
+        let mut line = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = std::io::Read::read(&mut stderr, &mut buffer)?;
+            for &byte in &buffer[..read] {
+                if byte != b'\r' && byte != b'\n' {
+                    line.push(byte);
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&line);
+                match PushProgress::parse(&text) {
+                    Some(progress) => on_progress(&progress),
+                    None if !text.trim().is_empty() => {
+                        messages.push_str(&text);
+                        messages.push('\n');
+                    }
+                    None => {}
+                }
+                line.clear();
+            }
+            if read == 0 {
+                break;
+            }
+        }
+        messages.push_str(&String::from_utf8_lossy(&line));
+        
+        if !child.wait()?.success() {
+            return Err(push_failure(&messages));
+        }
+        
+        Ok(())
+    }
+    
+    // Rewrites the target branch without the commits in `base..last` (one
+    // run's output), replaying anything committed after them on top of
+    // `base`. Only empty [AutoGen] commits can be dropped, so the replayed
+    // trees stay valid. The old tip is kept on `backup_branch`.
+    pub fn remove_commit_range(&mut self, base: Option<Oid>, last: Oid, backup_branch: &str) -> Result<RemovedCommits> {
+        let tip = self.branch_tip()?
+            .ok_or_else(|| GitHubGridError::Repository(format!("Branch {} has no commits", self.branch)))?
+            .id();
+        
+        // The run's commits: reachable from its last commit but not its base
+        let mut run_walk = self.repo.revwalk()?;
+        run_walk.push(last)?;
+        if let Some(base) = base {
+            run_walk.hide(base)?;
+        }
+        let mut removed = 0;
+        for oid in run_walk {
+            let commit = self.repo.find_commit(oid?)?;
+            if !is_autogen(commit.message().unwrap_or("")) {
+                return Err(GitHubGridError::Repository(format!(
+                    "Commit {} in the run isn't an [AutoGen] commit; refusing to rewrite it", commit.id()
+                )));
+            }
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree_id()),
+            };
+            if commit.parent_count() > 1 || parent_tree.is_some_and(|tree| tree != commit.tree_id()) {
+                return Err(GitHubGridError::Repository(format!(
+                    "Commit {} changes files or is a merge; it can't be dropped cleanly", commit.id()
+                )));
+            }
+            removed += 1;
+        }
+        
+        // Everything committed after the run, oldest first
+        let mut later_walk = self.repo.revwalk()?;
+        later_walk.push(tip)?;
+        later_walk.hide(last)?;
+        later_walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        let later = later_walk.collect::<std::result::Result<Vec<Oid>, _>>()?;
+        
+        let mut on_branch = self.repo.revwalk()?;
+        on_branch.push(tip)?;
+        if !on_branch.any(|oid| oid.is_ok_and(|oid| oid == last)) {
+            return Err(GitHubGridError::Repository(format!(
+                "Commit {} is no longer on {}", last, self.branch
+            )));
+        }
+        if base.is_none() && later.is_empty() {
+            return Err(GitHubGridError::Repository(
+                "Rolling back would leave the branch with no commits".to_string()
+            ));
+        }
+        
+        let mut new_tip = base;
+        for oid in &later {
+            let commit = self.repo.find_commit(*oid)?;
+            if commit.parent_count() > 1 {
+                return Err(GitHubGridError::Repository(format!(
+                    "Merge commit {} was made after the run; rewrite it manually", commit.id()
+                )));
+            }
+            let parent = new_tip.map(|oid| self.repo.find_commit(oid)).transpose()?;
+            let parents: Vec<_> = parent.iter().collect();
+            new_tip = Some(self.repo.commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                commit.message().unwrap_or(""),
+                &commit.tree()?,
+                &parents,
+            )?);
+        }
+        let new_tip = new_tip.unwrap();
+        
+        self.repo.reference(&format!("refs/heads/{}", backup_branch), tip, false, "github-grid rollback backup")?;
+        self.repo.find_reference(&self.branch_ref())?
+            .set_target(new_tip, "github-grid rollback")?;
+        
+        Ok(RemovedCommits { removed, replayed: later.len(), old_tip: tip, new_tip })
+    }
+    
+    // Applies `edits` to commits on the branch, recreating every descendant
+    // so the rest of the history stays intact (trees are reused untouched).
+    // The old tip is kept on `backup_branch`.
+    pub fn rewrite_commits(&mut self, edits: &HashMap<Oid, CommitEdit>, backup_branch: &str) -> Result<RewrittenCommits> {
+        let tip = self.branch_tip()?
+            .ok_or_else(|| GitHubGridError::Repository(format!("Branch {} has no commits", self.branch)))?
+            .id();
+        
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(tip)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        
+        let mut rewritten: HashMap<Oid, Oid> = HashMap::new();
+        let (mut edited, mut replayed) = (0, 0);
+        for oid in revwalk {
+            let oid = oid?;
+            let commit = self.repo.find_commit(oid)?;
+            let parent_ids: Vec<Oid> = (0..commit.parent_count())
+                .map(|i| commit.parent_id(i))
+                .collect::<std::result::Result<_, _>>()?;
+            let new_parent_ids: Vec<Oid> = parent_ids.iter()
+                .map(|id| rewritten.get(id).copied().unwrap_or(*id))
+                .collect();
+            
+            let edit = edits.get(&oid);
+            if edit.is_none() && new_parent_ids == parent_ids {
+                continue;
+            }
+            
+            let restamp = |sig: git2::Signature<'_>| -> Result<git2::Signature<'static>> {
+                let Some(edit) = edit.filter(|edit| edit.touches_signature()) else {
+                    return Ok(sig.to_owned());
+                };
+                let when = sig.when();
+                Ok(Signature::new(
+                    edit.name.as_deref().unwrap_or(sig.name().unwrap_or("")),
+                    edit.email.as_deref().unwrap_or(sig.email().unwrap_or("")),
+                    &Time::new(edit.seconds.unwrap_or(when.seconds()), when.offset_minutes()),
+                )?)
+            };
+            let author = restamp(commit.author())?;
+            let committer = restamp(commit.committer())?;
+            let message = edit.and_then(|edit| edit.message.as_deref())
+                .unwrap_or(commit.message().unwrap_or(""));
+            
+            let parents = new_parent_ids.iter()
+                .map(|id| self.repo.find_commit(*id))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let parent_refs: Vec<_> = parents.iter().collect();
+            let new_oid = self.repo.commit(None, &author, &committer, message, &commit.tree()?, &parent_refs)?;
+            rewritten.insert(oid, new_oid);
+            
+            if edit.is_some() {
+                edited += 1;
+            } else {
+                replayed += 1;
+            }
+        }
         
-        if branch_name != "main" {
-            // Try to checkout main branch
-            let obj = self.repo.revparse_single("refs/heads/main")?;
-            self.repo.checkout_tree(&obj, None)?;
-            self.repo.set_head("refs/heads/main")?;
+        let new_tip = rewritten.get(&tip).copied().unwrap_or(tip);
+        if new_tip != tip {
+            self.repo.reference(&format!("refs/heads/{}", backup_branch), tip, false, "github-grid rewrite backup")?;
+            self.repo.find_reference(&self.branch_ref())?
+                .set_target(new_tip, "github-grid rewrite")?;
         }
         
+        Ok(RewrittenCommits { edited, replayed, old_tip: tip, new_tip })
+    }
+    
+    // Points HEAD at the target branch. Handles a detached HEAD (reattach),
+    // a missing branch (created at the current commit) and an unborn HEAD
+    // (HEAD becomes the still-unborn target branch).
+    fn ensure_branch(&mut self) -> Result<()> {
+        let refname = self.branch_ref();
+        let head = self.repo.find_reference("HEAD")?;
+        if head.symbolic_target() == Some(refname.as_str()) {
+            return Ok(()); // Already on it, born or not
+        }
+        
+        if self.branch_tip()?.is_none() {
+            // HEAD is detached or on another branch with commits: branch from there
+            if let Ok(current) = self.repo.head().and_then(|head| head.peel_to_commit()) {
+                self.repo.branch(&self.branch, &current, false)?;
+            }
+        }
+        
+        if self.is_lean() && self.branch_tip()?.is_some() {
+            return self.git(&["checkout", "--quiet", &self.branch]);
+        }
+        if let Some(tip) = self.branch_tip()? {
+            self.repo.checkout_tree(tip.as_object(), None)?;
+        }
+        self.repo.set_head(&refname)?;
+        
         Ok(())
     }
     
-}
\ No newline at end of file
+}
+
+// New root tree with the file at `components` replaced by `update` of its
+// current content (empty if it doesn't exist yet)
+fn append_to_path(
+    repo: &Repository,
+    tree: Option<&Tree<'_>>,
+    components: &[&str],
+    update: impl FnOnce(&[u8]) -> Vec<u8>,
+) -> Result<Oid> {
+    let path: std::path::PathBuf = components.iter().collect();
+    let existing = match tree.and_then(|tree| tree.get_path(&path).ok()) {
+        Some(entry) => repo.find_blob(entry.id())?.content().to_vec(),
+        None => Vec::new(),
+    };
+    let blob = repo.blob(&update(&existing))?;
+    write_path(repo, tree, components, blob)
+}
+
+// `tree` with the blob stored at the path given as components, creating
+// intermediate directories as needed
+fn write_path(repo: &Repository, tree: Option<&Tree<'_>>, components: &[&str], blob: Oid) -> Result<Oid> {
+    let mut builder = repo.treebuilder(tree)?;
+    match components {
+        [file] => {
+            builder.insert(file, blob, 0o100644)?;
+        }
+        [dir, rest @ ..] => {
+            let subtree = match tree.and_then(|tree| tree.get_path(Path::new(dir)).ok()) {
+                Some(entry) => repo.find_tree(entry.id()).ok(),
+                None => None,
+            };
+            let subtree_id = write_path(repo, subtree.as_ref(), rest, blob)?;
+            builder.insert(dir, subtree_id, 0o040000)?;
+        }
+        [] => unreachable!("path has at least a file name"),
+    }
+    Ok(builder.write()?)
+}
+
+// Commit hooks git would run in this repo, from core.hooksPath or
+// .git/hooks. Samples and hooks without the executable bit don't count,
+// since git skips them too.
+pub fn installed_hooks(repo: &Repository) -> Vec<&'static str> {
+    let dir = hooks_dir(repo);
+    COMMIT_HOOKS.into_iter().filter(|name| is_executable(&dir.join(name))).collect()
+}
+
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    match repo.config().and_then(|config| config.get_path("core.hooksPath")) {
+        // Relative paths are taken from the worktree root, where git runs hooks
+        Ok(path) if path.is_relative() => repo.workdir().unwrap_or(repo.path()).join(path),
+        Ok(path) => path,
+        Err(_) => repo.path().join("hooks"),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// User's global git config for name and email
+pub fn author_identity() -> Result<(String, String)> {
+    let config = git2::Config::open_default()?;
+    let name = config.get_string("user.name").unwrap_or_else(|_| "GitHub Grid".to_string());
+    let email = config.get_string("user.email").unwrap_or_else(|_| "github-grid@example.com".to_string());
+    Ok((name, email))
+}
+
+// Author times of every commit on `repo`'s local branches whose author email
+// is one of `emails` (case-insensitive; empty means every author), in the
+// author's own offset. Only times are read, never content.
+pub fn authored_commits(repo: &Repository, emails: &[String]) -> Result<Vec<(Oid, DateTime<FixedOffset>)>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_glob("refs/heads/*")?;
+    
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        let author = commit.author();
+        let email = author.email().unwrap_or("");
+        if !emails.is_empty() && !emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+            continue;
+        }
+        let when = author.when();
+        let offset = FixedOffset::east_opt(when.offset_minutes() * 60).unwrap_or(FixedOffset::east_opt(0).unwrap());
+        if let Some(time) = DateTime::from_timestamp(when.seconds(), 0) {
+            commits.push((oid, time.with_timezone(&offset)));
+        }
+    }
+    Ok(commits)
+}
+
+// Sorts git's push errors by cause so the exit code tells auth problems,
+// an unreachable remote and a rejected push apart
+fn push_failure(stderr: &str) -> GitHubGridError {
+    let message = format!("Git push failed: {}", stderr.trim_end());
+    let lower = stderr.to_lowercase();
+    if ["authentication failed", "permission denied", "could not read username", "invalid username or password"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+    {
+        GitHubGridError::Authentication(message)
+    } else if ["could not resolve host", "failed to connect", "connection timed out", "connection refused", "network is unreachable", "operation timed out"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+    {
+        GitHubGridError::Network(message)
+    } else if lower.contains("[rejected]") {
+        GitHubGridError::Conflict(message)
+    } else {
+        GitHubGridError::Repository(message)
+    }
+}
+
+// Adds the trailer to an existing trailer block (the last paragraph when
+// every line is `Key: value`) or starts a new one, like `git commit -s`
+fn with_signoff(message: &str, name: &str, email: &str) -> String {
+    let message = message.trim_end();
+    let trailer = format!("Signed-off-by: {} <{}>", name, email);
+    if message.lines().any(|line| line == trailer) {
+        return message.to_string();
+    }
+    
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or("");
+    let in_trailer_block = message.contains("\n\n") && last_paragraph.lines().all(|line| {
+        line.split_once(": ").is_some_and(|(key, _)| !key.is_empty() && !key.contains(' '))
+    });
+    
+    let separator = if in_trailer_block { "\n" } else { "\n\n" };
+    format!("{}{}{}", message, separator, trailer)
+}
+
+pub fn is_autogen(message: &str) -> bool {
+    message.starts_with(crate::messages::AUTOGEN_PREFIX)
+}
+
+pub fn is_stats_update(message: &str) -> bool {
+    message.lines().next() == Some(STATS_MESSAGE)
+}
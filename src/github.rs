@@ -1,38 +1,372 @@
-use std::process::Command;
+use std::collections::BTreeMap;
+use std::env;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::Deserialize;
 use crate::error::{GitHubGridError, Result};
+use crate::forge::{AccountSnapshot, CloneMethod, CloneOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TokenSource {
+    /// Credentials stored by `gh auth login`
+    Gh,
+    /// GH_TOKEN or GITHUB_TOKEN environment variable (CI, cron)
+    Env,
+    /// The github.com login in git's credential helper (macOS Keychain, Windows Credential Manager)
+    Keychain,
+    /// A GitHub App installation token, minted from --app-id, --app-key and --app-login
+    App,
+}
+
+// Password git's credential helper holds for github.com, asked through
+// `git credential fill` so whichever store the user's pushes already use
+// is read directly. Never prompts; None if nothing is stored.
+pub fn keychain_token() -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(b"protocol=https\nhost=github.com\n\n").ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+// Saves a github.com login to git's credential helper, where
+// `--token-source keychain` and pushes over HTTPS find it. Fails if no
+// helper is configured, since git would silently drop it.
+pub fn store_keychain_token(username: &str, token: &str) -> Result<()> {
+    // Each line is a separate attribute to git credential
+    if [username, token].iter().any(|value| value.contains(['\n', '\r', '\0'])) {
+        return Err(GitHubGridError::Config("Username and token must be on a single line".to_string()));
+    }
+    let helper = Command::new("git").args(["config", "--get", "credential.helper"]).output()?;
+    if String::from_utf8_lossy(&helper.stdout).trim().is_empty() {
+        return Err(GitHubGridError::Config("No git credential helper is configured to store the token".to_string()));
+    }
+    let mut child = Command::new("git")
+        .args(["credential", "approve"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let request = format!("protocol=https\nhost=github.com\nusername={}\npassword={}\n\n", username, token);
+    child.stdin.take().expect("stdin is piped").write_all(request.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitHubGridError::Config(format!(
+            "git credential approve failed: {}", String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+// Asks the user's askpass program, like git does: GIT_ASKPASS, then
+// SSH_ASKPASS. None if neither is set or the program gives no answer.
+pub fn askpass(prompt: &str) -> Option<String> {
+    let program = env::var("GIT_ASKPASS").or_else(|_| env::var("SSH_ASKPASS")).ok().filter(|p| !p.is_empty())?;
+    let output = Command::new(program).arg(prompt).stderr(Stdio::inherit()).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let answer = String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string();
+    (!answer.is_empty()).then_some(answer)
+}
+
+// A GitHub App's credentials, exchanged for a short-lived installation
+// token on the account being managed. The JWT is signed with the system
+// openssl, so no crypto lives in this crate.
+#[derive(Debug, Clone)]
+pub struct GitHubApp {
+    pub app_id: String,
+    pub private_key: PathBuf,
+    // Account the app is installed on, whose graph and repos are managed
+    pub login: String,
+    // Looked up from `login` when not given
+    pub installation: Option<u64>,
+}
+
+impl GitHubApp {
+    // Installation tokens last an hour, longer than any single run
+    pub fn installation_token(&self) -> Result<String> {
+        let jwt = self.jwt()?;
+        let installation = match self.installation {
+            Some(id) => id.to_string(),
+            None => {
+                let body = app_request(&jwt, "GET", &format!("users/{}/installation", self.login))?;
+                let installation: Installation = serde_json::from_str(&body).map_err(|_| GitHubGridError::NotFound(
+                    format!("No installation of GitHub App {} on {}", self.app_id, self.login)
+                ))?;
+                installation.id.to_string()
+            }
+        };
+        let body = app_request(&jwt, "POST", &format!("app/installations/{}/access_tokens", installation))?;
+        let token: InstallationToken = serde_json::from_str(&body).map_err(|_| GitHubGridError::Authentication(
+            format!("GitHub gave no token for installation {}", installation)
+        ))?;
+        Ok(token.token)
+    }
+
+    // RS256 JWT identifying the app, backdated a minute for clock drift
+    // and valid for the ten minutes GitHub allows at most
+    fn jwt(&self) -> Result<String> {
+        let now = Utc::now().timestamp();
+        let header = base64url(br#"{"alg":"RS256","typ":"JWT"}"#);
+        let claims = base64url(serde_json::json!({ "iat": now - 60, "exp": now + 540, "iss": self.app_id }).to_string().as_bytes());
+        let signing_input = format!("{}.{}", header, claims);
+
+        let mut child = Command::new("openssl")
+            .args(["dgst", "-sha256", "-sign"])
+            .arg(&self.private_key)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| GitHubGridError::Authentication(format!("GitHub App auth needs openssl: {}", e)))?;
+        child.stdin.take().expect("stdin is piped").write_all(signing_input.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(GitHubGridError::Authentication(format!(
+                "Could not sign with {}: {}", self.private_key.display(), String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(format!("{}.{}", signing_input, base64url(&output.stdout)))
+    }
+}
+
+#[derive(Deserialize)]
+struct Installation {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct InstallationToken {
+    token: String,
+}
+
+// Error body of a failed REST call
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+// App-level REST call, authenticated by JWT rather than a token, which gh
+// can't send. Headers go in on stdin so the JWT stays out of `ps`.
+fn app_request(jwt: &str, method: &str, path: &str) -> Result<String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", "30", "-X", method, "--header", "@-", "--write-out", "\n%{http_code}"])
+        .arg(format!("https://api.github.com/{}", path))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitHubGridError::Network(format!("could not run curl: {}", e)))?;
+    let headers = format!(
+        "Authorization: Bearer {}\nAccept: application/vnd.github+json\nX-GitHub-Api-Version: 2022-11-28\n", jwt
+    );
+    child.stdin.take().expect("stdin is piped").write_all(headers.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitHubGridError::Network(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    let response = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = response.rsplit_once('\n').unwrap_or(("", &response));
+    let message = serde_json::from_str::<ApiError>(body).map_or_else(|_| body.trim().to_string(), |error| error.message);
+    match status.trim() {
+        "200" | "201" => Ok(body.to_string()),
+        "401" => Err(GitHubGridError::Authentication(format!("GitHub App rejected: {}", message))),
+        status => Err(classify_failure(&format!("{} (HTTP {})", message, status), "GitHub App request failed")),
+    }
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..chunk.len() + 1 {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+// Whether the gh CLI is on PATH at all, authenticated or not
+pub fn gh_installed() -> bool {
+    Command::new("gh").arg("--version").output().is_ok_and(|output| output.status.success())
+}
+
+// Rate-limited requests are retried when the limit resets within this
+// long; anything further out is returned to the caller
+const MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(90);
+const RATE_LIMIT_RETRIES: u32 = 2;
+
+// How Go's net/http (gh) and curl describe not reaching GitHub at all
+const NETWORK_FAILURES: [&str; 9] = [
+    "no such host",
+    "could not resolve host",
+    "connection refused",
+    "connection reset",
+    "network is unreachable",
+    "timeout",
+    "timed out",
+    "deadline exceeded",
+    "tls handshake",
+];
+
+// Sorts a failed gh call into a typed error from its stderr, which carries
+// "(HTTP nnn)" for REST calls and GitHub's own message for GraphQL
+fn classify_failure(stderr: &str, context: &str) -> GitHubGridError {
+    let message = stderr.trim().trim_start_matches("gh: ").to_string();
+    let lower = message.to_lowercase();
+    if lower.contains("rate limit") || lower.contains("(http 429)") {
+        GitHubGridError::RateLimited { reset: None }
+    } else if NETWORK_FAILURES.iter().any(|failure| lower.contains(failure)) {
+        GitHubGridError::Network(message)
+    } else if lower.contains("(http 404)") || lower.contains("could not resolve to") || lower.contains("not found") {
+        GitHubGridError::NotFound(message)
+    } else if lower.contains("(http 403)") || lower.contains("saml") || lower.contains("forbidden") {
+        let sso_required = lower.contains("saml") || lower.contains("sso");
+        GitHubGridError::Forbidden { message, sso_required }
+    } else if lower.contains("(http 409)") || lower.contains("already exists") {
+        GitHubGridError::Conflict(message)
+    } else {
+        GitHubGridError::Repository(format!("{}: {}", context, message))
+    }
+}
+
+// Login stored in gh's local config by `gh auth login`. Reads no network,
+// so it works offline; None if gh is missing or not logged in.
+pub fn cached_username() -> Option<String> {
+    let output = Command::new("gh")
+        .args(["config", "get", "user", "-h", "github.com"])
+        .output()
+        .ok()?;
+    let username = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !username.is_empty()).then_some(username)
+}
 
 pub struct GitHubClient {
     username: String,
+    token: Option<String>,
+    // Acting as a GitHub App installation, which has no user of its own
+    installation: bool,
 }
 
+// Answers git's credential request for github.com with the token in GH_TOKEN
+pub(crate) const TOKEN_CREDENTIAL_HELPER: &str =
+    "credential.helper=!f() { echo username=x-access-token; echo \"password=$GH_TOKEN\"; }; f";
+
+const CALENDAR_QUERY: &str = "query($from: DateTime!, $to: DateTime!) { \
+    viewer { contributionsCollection(from: $from, to: $to) { contributionCalendar { \
+    weeks { contributionDays { date contributionCount } } } } } }";
+
+// Same as CALENDAR_QUERY for any user; private contributions only show as
+// counts if that user has enabled them on their profile
+const USER_CALENDAR_QUERY: &str = "query($login: String!, $from: DateTime!, $to: DateTime!) { \
+    user(login: $login) { contributionsCollection(from: $from, to: $to) { contributionCalendar { \
+    weeks { contributionDays { date contributionCount } } } } } }";
+
+// Per-repo commit days for a user. Each node is one repo-day, and a page
+// holds 100 of them, so callers keep the range under 100 days.
+const PUBLIC_COMMITS_QUERY: &str = "query($login: String!, $from: DateTime!, $to: DateTime!) { \
+    user(login: $login) { contributionsCollection(from: $from, to: $to) { \
+    commitContributionsByRepository(maxRepositories: 100) { repository { nameWithOwner isPrivate } \
+    contributions(first: 100) { nodes { occurredAt commitCount } } } } } }";
+
 impl GitHubClient {
-    pub fn new() -> Result<Self> {
+    pub fn new(source: TokenSource) -> Result<Self> {
+        let token = match source {
+            TokenSource::Gh => None,
+            TokenSource::Env => Some(
+                env::var("GH_TOKEN")
+                    .or_else(|_| env::var("GITHUB_TOKEN"))
+                    .map_err(|_| GitHubGridError::Authentication(
+                        "Token source 'env' requires GH_TOKEN or GITHUB_TOKEN to be set".to_string()
+                    ))?
+            ),
+            TokenSource::Keychain => Some(keychain_token().ok_or_else(|| GitHubGridError::Authentication(
+                "Token source 'keychain' found no github.com credentials in git's credential helper. \
+                 Push once with git so the helper stores them, or run 'gh auth setup-git'".to_string()
+            ))?),
+            TokenSource::App => return Err(GitHubGridError::Authentication(
+                "Token source 'app' needs the app's credentials; use GitHubClient::with_installation".to_string()
+            )),
+        };
+        Self::connect(token)
+    }
+    
+    // Client for an explicit token, such as one the user just typed in
+    pub fn with_token(token: String) -> Result<Self> {
+        Self::connect(Some(token))
+    }
+    
+    // Client for a GitHub App installation token on `login`'s account.
+    // The API has no viewer for an installation, so everything about the
+    // user goes through `login`; account emails can't be read at all.
+    pub fn with_installation(token: String, login: &str) -> Result<Self> {
+        if !gh_installed() {
+            return Err(GitHubGridError::Authentication(
+                "GitHub CLI (gh) is not installed. Install it from https://cli.github.com/".to_string()
+            ));
+        }
+        Ok(Self { username: login.to_string(), token: Some(token), installation: true })
+    }
+    
+    fn connect(token: Option<String>) -> Result<Self> {
+        let mut client = Self { username: String::new(), token, installation: false };
+        
         // Check if gh CLI is available and authenticated
-        Self::check_gh_cli()?;
+        client.check_gh_cli()?;
         
         // Temporarily set git protocol to https for token auth if needed
-        let original_protocol = Self::get_git_protocol().unwrap_or_else(|_| "ssh".to_string());
+        let original_protocol = client.get_git_protocol().unwrap_or_else(|_| "ssh".to_string());
         let changed_protocol = if original_protocol != "https" {
-            Self::set_git_protocol("https")?;
+            client.set_git_protocol("https")?;
             true
         } else {
             false
         };
         
         // Get username
-        let username = Self::get_github_username()?;
+        client.username = client.get_github_username()?;
         
         // Restore original protocol if we changed it
         if changed_protocol {
-            Self::set_git_protocol(&original_protocol)?;
+            client.set_git_protocol(&original_protocol)?;
         }
         
-        Ok(Self { username })
+        Ok(client)
     }
     
-    fn check_gh_cli() -> Result<()> {
-        let output = Command::new("gh")
-            .args(&["auth", "status"])
+    // gh invocation carrying the explicit token, if one was configured
+    fn gh(&self) -> Command {
+        let mut command = Command::new("gh");
+        if let Some(token) = &self.token {
+            command.env("GH_TOKEN", token);
+        }
+        command
+    }
+    
+    fn check_gh_cli(&self) -> Result<()> {
+        let output = self.gh()
+            .args(["auth", "status"])
             .output();
             
         match output {
@@ -46,9 +380,12 @@ impl GitHubClient {
         }
     }
     
-    fn get_github_username() -> Result<String> {
-        let output = Command::new("gh")
-            .args(&["api", "user", "--jq", ".login"])
+    fn get_github_username(&self) -> Result<String> {
+        if self.installation {
+            return Ok(self.username.clone());
+        }
+        let output = self.gh()
+            .args(["api", "user", "--jq", ".login"])
             .output()
             .map_err(|_| GitHubGridError::Authentication("Failed to get GitHub username".to_string()))?;
             
@@ -63,34 +400,246 @@ impl GitHubClient {
         &self.username
     }
     
+    // When the core REST and GraphQL limits reset, whichever is later.
+    // gh doesn't print response headers on failure, so it's asked for
+    // separately; the rate_limit endpoint doesn't count against the limit.
+    fn rate_limit_reset(&self) -> Option<DateTime<Utc>> {
+        let output = self.gh()
+            .args(["api", "rate_limit", "--jq", "[.resources.core, .resources.graphql] | map(select(.remaining == 0) | .reset) | max // empty"])
+            .output()
+            .ok()?;
+        let seconds = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        DateTime::from_timestamp(seconds, 0)
+    }
+    
+    // Turns a failed gh call into a typed error, looking up the reset time
+    // for rate limits
+    fn failure(&self, stderr: &[u8], context: &str) -> GitHubGridError {
+        match classify_failure(&String::from_utf8_lossy(stderr), context) {
+            GitHubGridError::RateLimited { .. } => GitHubGridError::RateLimited { reset: self.rate_limit_reset() },
+            error => error,
+        }
+    }
+    
+    // `gh api` call returning one output line per item. With `paginate`,
+    // gh follows Link headers so results beyond the first page are included.
+    // Waits out a rate limit that resets soon, or a network blip, instead
+    // of failing the run.
+    fn api_lines(&self, args: &[&str], paginate: bool) -> Result<Vec<String>> {
+        let mut attempt = 0;
+        loop {
+            match self.api_lines_once(args, paginate) {
+                Err(error) if error.is_retryable() && attempt < RATE_LIMIT_RETRIES => {
+                    // Secondary limits have no reset time; back off briefly
+                    let wait = match &error {
+                        GitHubGridError::RateLimited { reset: Some(reset) } => {
+                            (*reset - Utc::now()).to_std().unwrap_or_default() + std::time::Duration::from_secs(1)
+                        }
+                        GitHubGridError::RateLimited { reset: None } => std::time::Duration::from_secs(15 << attempt),
+                        _ => std::time::Duration::from_secs(2 << attempt),
+                    };
+                    if wait > MAX_RATE_LIMIT_WAIT {
+                        return Err(error);
+                    }
+                    thread::sleep(wait);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    fn api_lines_once(&self, args: &[&str], paginate: bool) -> Result<Vec<String>> {
+        let mut command = self.gh();
+        command.arg("api");
+        if paginate {
+            command.arg("--paginate");
+        }
+        let output = command
+            .args(args)
+            .output()
+            .map_err(|e| GitHubGridError::Repository(format!("Failed to run gh api: {}", e)))?;
+            
+        if !output.status.success() {
+            return Err(self.failure(&output.stderr, "GitHub API request failed"));
+        }
+        
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+    
+    // All email addresses on the account (needs the user:email scope)
+    pub fn user_emails(&self) -> Result<Vec<String>> {
+        if self.installation {
+            return Err(GitHubGridError::Forbidden {
+                message: "a GitHub App installation can't read account emails".to_string(),
+                sso_required: false,
+            });
+        }
+        self.api_lines(&["user/emails", "--jq", ".[].email"], true)
+    }
+    
+    // Daily contribution counts as shown on the profile graph. GitHub limits
+    // a single query to one year, so longer ranges are fetched a year at a time.
+    pub fn contribution_calendar(&self, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        match self.installation {
+            // Only public counts, plus private ones if the user shows them
+            true => self.calendar_for(Some(&self.username), from, to),
+            false => self.calendar_for(None, from, to),
+        }
+    }
+    
+    // The graph and the account's emails, fetched in parallel rather than
+    // paying for the gh round trips one after another
+    pub fn account_snapshot(&self, from: NaiveDate, to: NaiveDate) -> Result<AccountSnapshot> {
+        thread::scope(|scope| {
+            let emails = scope.spawn(|| self.user_emails());
+            let calendar = self.contribution_calendar(from, to)?;
+            let emails = emails.join().map_err(|_| GitHubGridError::Repository("GitHub API worker panicked".to_string()))?;
+            Ok(AccountSnapshot { calendar, emails: emails.ok() })
+        })
+    }
+    
+    // Another user's public contribution graph
+    pub fn user_contribution_calendar(&self, login: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        self.calendar_for(Some(login), from, to)
+    }
+    
+    fn calendar_for(&self, login: Option<&str>, from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u32>> {
+        let mut chunk_start = from;
+        let (query, root) = match login {
+            Some(_) => (USER_CALENDAR_QUERY, "user"),
+            None => (CALENDAR_QUERY, "viewer"),
+        };
+        let jq = format!(
+            ".data.{}.contributionsCollection.contributionCalendar.weeks[].contributionDays[] | \"\\(.date) \\(.contributionCount)\"",
+            root
+        );
+        
+        let mut chunks = Vec::new();
+        while chunk_start <= to {
+            let chunk_end = (chunk_start + Duration::days(364)).min(to);
+            chunks.push((chunk_start, chunk_end));
+            chunk_start = chunk_end + Duration::days(1);
+        }
+        
+        // Years are independent queries, so multi-year ranges fetch them all at once
+        let results: Vec<Result<Vec<String>>> = thread::scope(|scope| {
+            let workers: Vec<_> = chunks.iter().map(|&(chunk_start, chunk_end)| {
+                let jq = &jq;
+                scope.spawn(move || {
+                    let mut args = vec![
+                        "graphql".to_string(),
+                        "-f".to_string(), format!("query={}", query),
+                        "-f".to_string(), format!("from={}T00:00:00Z", chunk_start),
+                        "-f".to_string(), format!("to={}T23:59:59Z", chunk_end),
+                        "--jq".to_string(), jq.clone(),
+                    ];
+                    if let Some(login) = login {
+                        args.extend(["-f".to_string(), format!("login={}", login)]);
+                    }
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    self.api_lines(&args, false)
+                })
+            }).collect();
+            workers.into_iter()
+                .map(|worker| worker.join().unwrap_or_else(|_| {
+                    Err(GitHubGridError::Repository("GitHub API worker panicked".to_string()))
+                }))
+                .collect()
+        });
+        
+        let mut calendar = BTreeMap::new();
+        for lines in results {
+            for line in lines? {
+                let parsed = line.split_once(' ').and_then(|(date, count)| {
+                    Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, count.parse().ok()?))
+                });
+                if let Some((date, count)) = parsed {
+                    calendar.insert(date, count);
+                }
+            }
+        }
+        
+        Ok(calendar)
+    }
+    
+    // Per-day commit counts in the user's public repos, leaving out `skip`
+    // (owner/name, case-insensitive) such as a public grid repo. Fetched a
+    // quarter at a time so no repo overflows a page of contribution days.
+    pub fn public_commit_days(&self, from: NaiveDate, to: NaiveDate, skip: &[String]) -> Result<BTreeMap<NaiveDate, u32>> {
+        let mut days = BTreeMap::new();
+        let mut chunk_start = from;
+        
+        while chunk_start <= to {
+            let chunk_end = (chunk_start + Duration::days(89)).min(to);
+            let lines = self.api_lines(&[
+                "graphql",
+                "-f", &format!("query={}", PUBLIC_COMMITS_QUERY),
+                "-f", &format!("login={}", self.username),
+                "-f", &format!("from={}T00:00:00Z", chunk_start),
+                "-f", &format!("to={}T23:59:59Z", chunk_end),
+                "--jq", ".data.user.contributionsCollection.commitContributionsByRepository[] | select(.repository.isPrivate | not) | .repository.nameWithOwner as $repo | .contributions.nodes[] | \"\\($repo) \\(.occurredAt[:10]) \\(.commitCount)\"",
+            ], false)?;
+            
+            for line in lines {
+                let mut fields = line.split_whitespace();
+                let (Some(repo), Some(date), Some(count)) = (fields.next(), fields.next(), fields.next()) else {
+                    continue;
+                };
+                if skip.iter().any(|s| s.eq_ignore_ascii_case(repo)) {
+                    continue;
+                }
+                if let (Ok(date), Ok(count)) = (NaiveDate::parse_from_str(date, "%Y-%m-%d"), count.parse::<u32>()) {
+                    *days.entry(date).or_insert(0) += count;
+                }
+            }
+            chunk_start = chunk_end + Duration::days(1);
+        }
+        
+        Ok(days)
+    }
+    
     pub fn repo_exists(&self, repo_name: &str) -> Result<bool> {
         // Temporarily set git protocol to https for token auth if needed
-        let original_protocol = Self::get_git_protocol().unwrap_or_else(|_| "ssh".to_string());
+        let original_protocol = self.get_git_protocol().unwrap_or_else(|_| "ssh".to_string());
         let changed_protocol = if original_protocol != "https" {
-            Self::set_git_protocol("https")?;
+            self.set_git_protocol("https")?;
             true
         } else {
             false
         };
         
-        let output = Command::new("gh")
-            .args(&["repo", "view", &format!("{}/{}", self.username, repo_name)])
+        let output = self.gh()
+            .args(["repo", "view", &format!("{}/{}", self.username, repo_name), "--json", "name"])
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to check if repo exists".to_string()))?;
             
-        let result = Ok(output.status.success());
+        // Only a missing repo is "doesn't exist"; a rate limit or an auth
+        // failure mustn't lead init into creating a duplicate
+        let result = if output.status.success() {
+            Ok(true)
+        } else {
+            match self.failure(&output.stderr, "Failed to check if repo exists") {
+                GitHubGridError::NotFound(_) => Ok(false),
+                error => Err(error),
+            }
+        };
         
         // Restore original protocol if we changed it
         if changed_protocol {
-            Self::set_git_protocol(&original_protocol)?;
+            self.set_git_protocol(&original_protocol)?;
         }
         
         result
     }
     
     pub fn create_repo(&self, name: &str) -> Result<String> {
-        let output = Command::new("gh")
-            .args(&[
+        let output = self.gh()
+            .args([
                 "repo", "create", name,
                 "--private",
                 "--description", "GitHub contribution grid patterns generated by github-grid",
@@ -100,51 +649,144 @@ impl GitHubClient {
             .map_err(|_| GitHubGridError::Repository("Failed to create repository".to_string()))?;
             
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(GitHubGridError::Repository(
-                format!("Failed to create GitHub repository: {}", stderr)
-            ));
+            return Err(self.failure(&output.stderr, "Failed to create GitHub repository"));
         }
         
         Ok(format!("https://github.com/{}/{}.git", self.username, name))
     }
     
     pub fn delete_repo(&self, repo_name: &str) -> Result<()> {
-        let output = Command::new("gh")
-            .args(&["repo", "delete", &format!("{}/{}", self.username, repo_name), "--yes"])
+        let output = self.gh()
+            .args(["repo", "delete", &format!("{}/{}", self.username, repo_name), "--yes"])
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to delete repository".to_string()))?;
             
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(GitHubGridError::Repository(
-                format!("Failed to delete GitHub repository: {}", stderr)
-            ));
+            return Err(self.failure(&output.stderr, "Failed to delete GitHub repository"));
         }
             
         Ok(())
     }
     
+    // Keeps the old repo's history: renames it out of the way, then marks
+    // it read-only. Returns the new name.
+    pub fn archive_repo(&self, repo_name: &str, archived_name: &str) -> Result<String> {
+        let steps: [(Vec<String>, &str); 2] = [
+            (
+                vec!["repo".into(), "rename".into(), archived_name.into(), "--repo".into(), format!("{}/{}", self.username, repo_name), "--yes".into()],
+                "rename",
+            ),
+            (
+                vec!["repo".into(), "archive".into(), format!("{}/{}", self.username, archived_name), "--yes".into()],
+                "archive",
+            ),
+        ];
+        for (args, action) in steps {
+            let output = self.gh()
+                .args(&args)
+                .output()
+                .map_err(|_| GitHubGridError::Repository(format!("Failed to {} repository", action)))?;
+                
+            if !output.status.success() {
+                return Err(self.failure(&output.stderr, &format!("Failed to {} GitHub repository", action)));
+            }
+        }
+        
+        Ok(archived_name.to_string())
+    }
+    
+    // Clones with gh, falling back to the system git when gh can't get
+    // through (auth, proxy or protocol trouble), as pushes always do. The
+    // error lists every attempt if none works.
+    pub fn clone_repo(&self, repo_name: &str, local_path: &str, options: &CloneOptions) -> Result<()> {
+        let mut failures = Vec::new();
+        if options.method != CloneMethod::Git {
+            match self.gh_clone(repo_name, local_path, options) {
+                Ok(()) => return Ok(()),
+                Err(e) if options.method == CloneMethod::Gh => return Err(e),
+                Err(e) => failures.push(format!("gh: {}", e)),
+            }
+        }
+        
+        let protocols = match self.get_git_protocol().as_deref() {
+            Ok("ssh") => ["ssh", "https"],
+            _ => ["https", "ssh"],
+        };
+        for protocol in protocols {
+            match self.git_clone(repo_name, local_path, protocol, options) {
+                Ok(()) => return Ok(()),
+                Err(e) => failures.push(format!("git over {}: {}", protocol, e)),
+            }
+        }
+        Err(GitHubGridError::Repository(format!("Failed to clone GitHub repository:\n  {}", failures.join("\n  "))))
+    }
     
-    pub fn clone_repo(&self, repo_name: &str, local_path: &str) -> Result<()> {
-        let output = Command::new("gh")
-            .args(&["repo", "clone", &format!("{}/{}", self.username, repo_name), local_path])
+    fn gh_clone(&self, repo_name: &str, local_path: &str, options: &CloneOptions) -> Result<()> {
+        let mut args = vec!["repo".to_string(), "clone".to_string(), format!("{}/{}", self.username, repo_name), local_path.to_string()];
+        let extra = options.git_args();
+        if !extra.is_empty() {
+            // Everything after -- goes to git clone
+            args.push("--".to_string());
+            args.extend(extra);
+        }
+        let output = self.gh()
+            .args(&args)
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to clone repository".to_string()))?;
             
+        if !output.status.success() {
+            return Err(GitHubGridError::Repository(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        
+        Ok(())
+    }
+    
+    fn git_clone(&self, repo_name: &str, local_path: &str, protocol: &str, options: &CloneOptions) -> Result<()> {
+        let slug = format!("{}/{}", self.username, repo_name);
+        let url = match protocol {
+            "ssh" => format!("git@github.com:{}.git", slug),
+            _ => format!("https://github.com/{}.git", slug),
+        };
+        let mut command = Command::new("git");
+        // Fail instead of waiting on a prompt nobody will answer
+        command.env("GIT_TERMINAL_PROMPT", "0");
+        if let Some(proxy) = &options.proxy {
+            command.arg("-c").arg(format!("http.proxy={}", proxy));
+        }
+        if let (Some(token), "https") = (&self.token, protocol) {
+            // Hand git the token for this clone only, without writing it to config
+            command.env("GH_TOKEN", token).args(["-c", "credential.helper=", "-c", TOKEN_CREDENTIAL_HELPER]);
+        }
+        command.args(["clone", "--quiet"]).args(options.git_args()).arg(&url).arg(local_path);
+        
+        let output = command.output()
+            .map_err(|_| GitHubGridError::Repository("Failed to run git".to_string()))?;
+        if !output.status.success() {
+            return Err(GitHubGridError::Repository(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(())
+    }
+    
+    // Bare mirror of every branch and tag, for a backup before deletion
+    pub fn mirror_repo(&self, repo_name: &str, dest: &str) -> Result<()> {
+        let output = self.gh()
+            .args(["repo", "clone", &format!("{}/{}", self.username, repo_name), dest, "--", "--mirror"])
+            .output()
+            .map_err(|_| GitHubGridError::Repository("Failed to back up repository".to_string()))?;
+            
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(GitHubGridError::Repository(
-                format!("Failed to clone GitHub repository: {}", stderr)
+                format!("Failed to back up GitHub repository: {}", stderr)
             ));
         }
         
         Ok(())
     }
     
-    fn get_git_protocol() -> Result<String> {
-        let output = Command::new("gh")
-            .args(&["config", "get", "git_protocol"])
+    fn get_git_protocol(&self) -> Result<String> {
+        let output = self.gh()
+            .args(["config", "get", "git_protocol"])
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to get git protocol".to_string()))?;
             
@@ -156,9 +798,9 @@ impl GitHubClient {
         }
     }
     
-    fn set_git_protocol(protocol: &str) -> Result<()> {
-        let output = Command::new("gh")
-            .args(&["config", "set", "git_protocol", protocol])
+    fn set_git_protocol(&self, protocol: &str) -> Result<()> {
+        let output = self.gh()
+            .args(["config", "set", "git_protocol", protocol])
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to set git protocol".to_string()))?;
             
@@ -169,8 +811,8 @@ impl GitHubClient {
         }
         
         // Also run setup-git to apply the change
-        Command::new("gh")
-            .args(&["auth", "setup-git"])
+        self.gh()
+            .args(["auth", "setup-git"])
             .output()
             .map_err(|_| GitHubGridError::Repository("Failed to setup git auth".to_string()))?;
             
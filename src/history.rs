@@ -0,0 +1,173 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::progress::RunStats;
+use github_grid::error::{GitHubGridError, Result};
+
+const HISTORY_FILE: &str = "history.toml";
+
+// One finished run, as stored in the history file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: u32,
+    pub finished: DateTime<Local>,
+    pub command: String,
+    pub status: String,
+    pub dry_run: bool,
+    pub repo: Option<String>,
+    pub branch: String,
+    pub pattern: Option<String>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub planned: usize,
+    pub created: usize,
+    pub pushed: usize,
+    pub base_commit: Option<String>,
+    pub first_commit: Option<String>,
+    pub last_commit: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub rolled_back: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryFile {
+    #[serde(default)]
+    runs: Vec<RunRecord>,
+}
+
+impl RunRecord {
+    pub fn new(command: &str, branch: &str, stats: RunStats, dry_run: bool, error: Option<String>) -> Self {
+        Self {
+            id: 0,
+            finished: Local::now(),
+            command: command.to_string(),
+            status: if error.is_some() { "failed" } else { "ok" }.to_string(),
+            dry_run,
+            repo: stats.repo.map(|repo| canonical_repo(&repo)),
+            branch: branch.to_string(),
+            pattern: stats.pattern,
+            start: stats.start,
+            end: stats.end,
+            planned: stats.planned,
+            created: stats.created,
+            pushed: stats.pushed,
+            base_commit: stats.base_commit,
+            first_commit: stats.first_commit,
+            last_commit: stats.last_commit,
+            error,
+            rolled_back: false,
+        }
+    }
+}
+
+// Absolute form of a repo path so records stay usable from any directory
+pub fn canonical_repo(path: &str) -> String {
+    fs::canonicalize(path).map_or_else(|_| path.to_string(), |p| p.display().to_string())
+}
+
+// Directory for per-user state, following XDG on every platform
+pub fn config_dir() -> Result<PathBuf> {
+    let base = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(
+            env::var("HOME").map_err(|_| GitHubGridError::Config("HOME is not set".to_string()))?
+        ).join(".config"),
+    };
+    Ok(base.join("github-grid"))
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(HISTORY_FILE))
+}
+
+pub fn load() -> Result<Vec<RunRecord>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file: HistoryFile = toml::from_str(&fs::read_to_string(&path)?)
+        .map_err(|e| GitHubGridError::Parse(format!("Invalid history file {}: {}", path.display(), e)))?;
+    Ok(file.runs)
+}
+
+fn save(runs: Vec<RunRecord>) -> Result<()> {
+    let path = history_path()?;
+    fs::create_dir_all(path.parent().unwrap())?;
+    let content = toml::to_string(&HistoryFile { runs })
+        .map_err(|e| GitHubGridError::Parse(format!("Failed to serialize history: {}", e)))?;
+
+    // Write-then-rename so an interrupted run can't truncate the history
+    let tmp = path.with_extension("toml.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+// Appends the record with the next free ID and returns that ID
+pub fn record(mut record: RunRecord) -> Result<u32> {
+    let mut runs = load()?;
+    record.id = runs.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+    let id = record.id;
+    runs.push(record);
+    save(runs)?;
+    Ok(id)
+}
+
+pub fn find(id: u32) -> Result<RunRecord> {
+    load()?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| GitHubGridError::Config(format!("No run with ID {} in history", id)))
+}
+
+pub fn mark_rolled_back(id: u32) -> Result<()> {
+    let mut runs = load()?;
+    if let Some(run) = runs.iter_mut().find(|r| r.id == id) {
+        run.rolled_back = true;
+    }
+    save(runs)
+}
+
+pub fn show(limit: usize, repo: Option<&str>) -> Result<()> {
+    let runs: Vec<RunRecord> = load()?
+        .into_iter()
+        .filter(|r| repo.is_none_or(|repo| r.repo.as_deref() == Some(repo)))
+        .collect();
+
+    if runs.is_empty() {
+        println!("No runs recorded yet");
+        return Ok(());
+    }
+
+    println!("{:>4}  {:<16}  {:<9}  {:<7}  {:<23}  {:<14}  {:>7}  {:>6}  Repo",
+        "ID", "Finished", "Command", "Status", "Range", "Pattern", "Created", "Pushed");
+    for run in runs.iter().rev().take(limit) {
+        let range = match (run.start, run.end) {
+            (Some(start), Some(end)) => format!("{}..{}", start, end),
+            _ => "-".to_string(),
+        };
+        let status = if run.dry_run {
+            "dry-run"
+        } else if run.rolled_back {
+            "undone"
+        } else {
+            run.status.as_str()
+        };
+        println!("{:>4}  {:<16}  {:<9}  {:<7}  {:<23}  {:<14}  {:>7}  {:>6}  {}",
+            run.id,
+            run.finished.format("%Y-%m-%d %H:%M"),
+            run.command,
+            status,
+            range,
+            run.pattern.as_deref().unwrap_or("-"),
+            run.created,
+            run.pushed,
+            run.repo.as_deref().unwrap_or("-"),
+        );
+    }
+    println!("\nHistory file: {}", history_path()?.display());
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rand::{rng, Rng};
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::CommitInfo;
+
+// Built-in tables, one rule per line as `RULE [observed|substitute] = NAME`:
+//   MM-DD               every year on that date
+//   YYYY-MM-DD          once
+//   N WEEKDAY MM        Nth (1-5 or `last`) weekday of the month
+//   easter+N / easter-N days from Western Easter Sunday
+//   equinox-03 / -09    Japan's equinox days
+// `observed` moves a weekend date to the nearest weekday (US style);
+// `substitute` moves it to the next free weekday (UK style). Only public
+// holidays that most offices close for are listed; lunar and regional ones
+// vary too much and belong in a custom file.
+const PRESETS: [(&str, &str, &str); 6] = [
+    ("US", "United States", "\
+01-01 observed = New Year's Day
+3 mon 01 = Martin Luther King Jr. Day
+3 mon 02 = Presidents' Day
+last mon 05 = Memorial Day
+06-19 observed = Juneteenth
+07-04 observed = Independence Day
+1 mon 09 = Labor Day
+4 thu 11 = Thanksgiving
+12-25 observed = Christmas Day"),
+    ("UK", "United Kingdom (England and Wales)", "\
+01-01 substitute = New Year's Day
+easter-2 = Good Friday
+easter+1 = Easter Monday
+1 mon 05 = Early May Bank Holiday
+last mon 05 = Spring Bank Holiday
+last mon 08 = Summer Bank Holiday
+12-25 substitute = Christmas Day
+12-26 substitute = Boxing Day"),
+    ("DE", "Germany (nationwide)", "\
+01-01 = Neujahr
+easter-2 = Karfreitag
+easter+1 = Ostermontag
+05-01 = Tag der Arbeit
+easter+39 = Christi Himmelfahrt
+easter+50 = Pfingstmontag
+10-03 = Tag der Deutschen Einheit
+12-25 = Erster Weihnachtstag
+12-26 = Zweiter Weihnachtstag"),
+    ("FR", "France", "\
+01-01 = Jour de l'an
+easter+1 = Lundi de Pâques
+05-01 = Fête du Travail
+05-08 = Victoire 1945
+easter+39 = Ascension
+easter+50 = Lundi de Pentecôte
+07-14 = Fête nationale
+08-15 = Assomption
+11-01 = Toussaint
+11-11 = Armistice
+12-25 = Noël"),
+    ("IN", "India (national holidays)", "\
+01-26 = Republic Day
+08-15 = Independence Day
+10-02 = Gandhi Jayanti"),
+    ("JP", "Japan", "\
+01-01 = 元日
+01-02 = Bank holiday
+01-03 = Bank holiday
+2 mon 01 = 成人の日
+02-11 = 建国記念の日
+02-23 = 天皇誕生日
+equinox-03 = 春分の日
+04-29 = 昭和の日
+05-03 = 憲法記念日
+05-04 = みどりの日
+05-05 = こどもの日
+3 mon 07 = 海の日
+08-11 = 山の日
+3 mon 09 = 敬老の日
+equinox-09 = 秋分の日
+2 mon 10 = スポーツの日
+11-03 = 文化の日
+11-23 = 勤労感謝の日
+12-31 = Year-end holiday"),
+];
+
+// Country codes and names of the built-in tables
+pub fn presets() -> impl Iterator<Item = (&'static str, &'static str)> {
+    PRESETS.iter().map(|(code, name, _)| (*code, *name))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shift {
+    None,
+    Observed,
+    Substitute,
+}
+
+#[derive(Debug, Clone)]
+enum When {
+    Fixed { month: u32, day: u32 },
+    Once(NaiveDate),
+    Nth { n: i32, weekday: Weekday, month: u32 },
+    Easter(i64),
+    Equinox { month: u32 },
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    when: When,
+    shift: Shift,
+    name: String,
+}
+
+// Holiday rules from one or more presets and custom files
+#[derive(Debug, Clone, Default)]
+pub struct Holidays {
+    rules: Vec<Rule>,
+}
+
+impl Holidays {
+    // A built-in table by country code (case-insensitive; GB is UK)
+    pub fn preset(code: &str) -> Result<Self> {
+        let code = if code.eq_ignore_ascii_case("GB") { "UK" } else { code };
+        let (_, _, table) = PRESETS.iter()
+            .find(|(c, _, _)| c.eq_ignore_ascii_case(code))
+            .ok_or_else(|| GitHubGridError::Config(format!(
+                "No holiday table for '{}' (available: {}); use --holidays-file for others",
+                code, presets().map(|(c, _)| c).collect::<Vec<_>>().join(", ")
+            )))?;
+        Self::parse(table, code)
+    }
+
+    // A custom file in the preset format; `#` starts a comment
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?, &path.display().to_string())
+    }
+
+    pub fn parse(text: &str, source: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |why: &str| GitHubGridError::Parse(format!("{} line {}: {} in '{}'", source, number + 1, why, line));
+            let (rule, name) = line.split_once('=').ok_or_else(|| invalid("expected RULE = NAME"))?;
+            let mut tokens: Vec<&str> = rule.split_whitespace().collect();
+            let shift = match tokens.last() {
+                Some(&"observed") => Shift::Observed,
+                Some(&"substitute") => Shift::Substitute,
+                _ => Shift::None,
+            };
+            if shift != Shift::None {
+                tokens.pop();
+            }
+            let when = parse_when(&tokens).ok_or_else(|| invalid("unrecognised rule"))?;
+            rules.push(Rule { when, shift, name: name.trim().to_string() });
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn merge(mut self, other: Holidays) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Every holiday between `start` and `end`, with its name. Weekend dates
+    // with a shift rule count on both the date and the weekday off.
+    pub fn between(&self, start: NaiveDate, end: NaiveDate) -> BTreeMap<NaiveDate, String> {
+        let mut days = BTreeMap::new();
+        for year in start.year()..=end.year() {
+            let mut substitutes = Vec::new();
+            for rule in &self.rules {
+                let Some(date) = rule.when.date(year) else {
+                    continue;
+                };
+                days.entry(date).or_insert_with(|| rule.name.clone());
+                match (rule.shift, date.weekday()) {
+                    (Shift::Observed, Weekday::Sat) => {
+                        days.entry(date - Duration::days(1)).or_insert_with(|| format!("{} (observed)", rule.name));
+                    }
+                    (Shift::Observed, Weekday::Sun) => {
+                        days.entry(date + Duration::days(1)).or_insert_with(|| format!("{} (observed)", rule.name));
+                    }
+                    (Shift::Substitute, Weekday::Sat | Weekday::Sun) => substitutes.push((date, &rule.name)),
+                    _ => {}
+                }
+            }
+            // After the fixed days, so a substitute skips past them
+            for (date, name) in substitutes {
+                let mut day = date + Duration::days(1);
+                while matches!(day.weekday(), Weekday::Sat | Weekday::Sun) || days.contains_key(&day) {
+                    day += Duration::days(1);
+                }
+                days.insert(day, format!("{} (substitute)", name));
+            }
+        }
+        days.retain(|day, _| (start..=end).contains(day));
+        days
+    }
+}
+
+impl When {
+    fn date(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            When::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            When::Once(date) => (date.year() == year).then_some(date),
+            When::Nth { n, weekday, month } if n > 0 => NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8),
+            When::Nth { weekday, month, .. } => {
+                let next_month = NaiveDate::from_ymd_opt(year + (month / 12) as i32, month % 12 + 1, 1)?;
+                let mut day = next_month - Duration::days(1);
+                while day.weekday() != weekday {
+                    day -= Duration::days(1);
+                }
+                Some(day)
+            }
+            When::Easter(offset) => Some(easter(year)? + Duration::days(offset)),
+            When::Equinox { month } => equinox(year, month),
+        }
+    }
+}
+
+fn parse_when(tokens: &[&str]) -> Option<When> {
+    match tokens {
+        [date] if date.starts_with("easter") => Some(When::Easter(date["easter".len()..].parse().ok().or(
+            date["easter".len()..].is_empty().then_some(0)
+        )?)),
+        [date] if date.starts_with("equinox-") => match &date["equinox-".len()..] {
+            "03" => Some(When::Equinox { month: 3 }),
+            "09" => Some(When::Equinox { month: 9 }),
+            _ => None,
+        },
+        [date] if date.len() == 10 => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok().map(When::Once),
+        [date] => {
+            let (month, day) = date.split_once('-')?;
+            let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+            // Feb 29 is allowed; it simply doesn't occur in other years
+            NaiveDate::from_ymd_opt(2024, month, day)?;
+            Some(When::Fixed { month, day })
+        }
+        [n, weekday, month] => {
+            let n = match *n {
+                "last" => -1,
+                n => n.parse().ok().filter(|n| (1..=5).contains(n))?,
+            };
+            let month = month.parse().ok().filter(|m| (1..=12).contains(m))?;
+            Some(When::Nth { n, weekday: weekday.parse().ok()?, month })
+        }
+        _ => None,
+    }
+}
+
+// Western Easter Sunday (anonymous Gregorian algorithm)
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+// Japan's vernal and autumnal equinox days, from the formula the National
+// Astronomical Observatory uses for 1980-2099
+fn equinox(year: i32, month: u32) -> Option<NaiveDate> {
+    let base = if month == 3 { 20.8431 } else { 23.2488 };
+    let years = (year - 1980) as f64;
+    let day = (base + 0.242194 * years - (years / 4.0).floor()).floor();
+    NaiveDate::from_ymd_opt(year, month, day as u32)
+}
+
+// The working day before a holiday, when people tend to wind down early
+pub fn is_eve(day: NaiveDate, holidays: &BTreeSet<NaiveDate>) -> bool {
+    !holidays.contains(&day) && holidays.contains(&(day + Duration::days(1)))
+}
+
+// Keeps about `keep` (0-1) of the commits on holiday eves
+pub fn thin_eves(mut commits: Vec<CommitInfo>, holidays: &BTreeSet<NaiveDate>, keep: f64) -> Vec<CommitInfo> {
+    let mut rng = rng();
+    commits.retain(|commit| !is_eve(commit.date.date_naive(), holidays) || rng.random_bool(keep.clamp(0.0, 1.0)));
+    commits
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+use chrono::{FixedOffset, Local, NaiveDate, TimeZone};
+use serde::Deserialize;
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{separate_timestamps, CommitInfo};
+
+// A stretch of days spent in another timezone
+#[derive(Debug, Clone)]
+pub struct Trip {
+    pub name: Option<String>,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub offset: FixedOffset,
+}
+
+#[derive(Deserialize)]
+struct TripEntry {
+    name: Option<String>,
+    start: NaiveDate,
+    end: NaiveDate,
+    // UTC offset at the destination, e.g. "+09:00"
+    utc_offset: String,
+}
+
+#[derive(Deserialize)]
+struct ItineraryFile {
+    #[serde(default)]
+    trip: Vec<TripEntry>,
+}
+
+// Travel plan that moves commits into the local time of wherever the
+// developer was. Commits keep their wall-clock hour at the destination, so
+// seen from home the working day shifts, and each records the
+// destination's UTC offset.
+#[derive(Debug, Clone, Default)]
+pub struct Itinerary {
+    trips: Vec<Trip>,
+}
+
+impl Itinerary {
+    // `[[trip]]` tables with `start`, `end`, `utc_offset` and an optional `name`
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: ItineraryFile = toml::from_str(content)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid itinerary: {}", e)))?;
+
+        let mut trips = Vec::new();
+        for entry in file.trip {
+            let label = entry.name.clone().unwrap_or_else(|| format!("{}..{}", entry.start, entry.end));
+            if entry.start > entry.end {
+                return Err(GitHubGridError::Config(format!("Trip {} ends before it starts", label)));
+            }
+            let offset: FixedOffset = entry.utc_offset.parse()
+                .map_err(|_| GitHubGridError::Parse(format!(
+                    "Invalid UTC offset '{}' for trip {}, expected e.g. +09:00", entry.utc_offset, label
+                )))?;
+            if let Some(other) = trips.iter().find(|t: &&Trip| t.start <= entry.end && entry.start <= t.end) {
+                return Err(GitHubGridError::Config(format!(
+                    "Trip {} overlaps trip {}..{}", label, other.start, other.end
+                )));
+            }
+            trips.push(Trip { name: entry.name, start: entry.start, end: entry.end, offset });
+        }
+        trips.sort_by_key(|t| t.start);
+        Ok(Self { trips })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn trips(&self) -> &[Trip] {
+        &self.trips
+    }
+
+    pub fn offset_on(&self, date: NaiveDate) -> Option<FixedOffset> {
+        self.trips.iter()
+            .find(|trip| (trip.start..=trip.end).contains(&date))
+            .map(|trip| trip.offset)
+    }
+
+    // Re-anchors commits on travel days to the destination's local time.
+    // Returns the number of commits moved.
+    pub fn apply(&self, commits: &mut [CommitInfo]) -> usize {
+        let mut moved = 0;
+        for commit in commits.iter_mut() {
+            let wall_clock = commit.date.naive_local();
+            let Some(offset) = self.offset_on(wall_clock.date()) else {
+                continue;
+            };
+            let Some(abroad) = offset.from_local_datetime(&wall_clock).single() else {
+                continue;
+            };
+            commit.date = abroad.with_timezone(&Local);
+            commit.utc_offset = Some(offset.local_minus_utc());
+            moved += 1;
+        }
+
+        if moved > 0 {
+            commits.sort_by_key(|c| c.date);
+            separate_timestamps(commits);
+        }
+        moved
+    }
+}
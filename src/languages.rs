@@ -0,0 +1,93 @@
+use rand::Rng;
+use crate::error::{GitHubGridError, Result};
+
+// Per extension: the directory files live in, the file stems rotated
+// through, and a snippet template where `{n}` is the file's next item number
+const LANGUAGES: &[(&str, &str, &[&str], &str)] = &[
+    ("rs", "src", &["lib", "config", "parser", "utils"], "pub fn step_{n}(input: u32) -> u32 {\n    input.wrapping_mul({n}).rotate_left(3)\n}\n\n"),
+    ("py", "scripts", &["main", "helpers", "models", "tasks"], "def step_{n}(value):\n    return (value * {n}) % 997\n\n\n"),
+    ("ts", "web/src", &["index", "api", "store", "format"], "export function step{n}(value: number): number {\n  return (value * {n}) % 997;\n}\n\n"),
+    ("js", "web", &["index", "api", "util", "events"], "export function step{n}(value) {\n  return (value * {n}) % 997;\n}\n\n"),
+    ("go", "cmd", &["main", "handlers", "store", "util"], "func step{n}(value int) int {\n\treturn (value * {n}) % 997\n}\n\n"),
+    ("java", "java", &["App", "Service", "Repository", "Util"], "    static int step{n}(int value) {\n        return (value * {n}) % 997;\n    }\n\n"),
+    ("c", "native", &["main", "buffer", "io", "util"], "int step_{n}(int value) {\n    return (value * {n}) % 997;\n}\n\n"),
+    ("cpp", "native", &["engine", "graph", "cache", "util"], "int step{n}(int value) {\n    return (value * {n}) % 997;\n}\n\n"),
+    ("rb", "lib", &["app", "helpers", "models", "tasks"], "def step_{n}(value)\n  (value * {n}) % 997\nend\n\n"),
+    ("sh", "bin", &["setup", "deploy", "check", "release"], "step_{n}() {\n  echo $(( ($1 * {n}) % 997 ))\n}\n\n"),
+    ("swift", "Sources", &["App", "Model", "Network", "Util"], "func step{n}(_ value: Int) -> Int {\n    return (value * {n}) % 997\n}\n\n"),
+    ("kt", "kotlin", &["App", "Service", "Store", "Util"], "fun step{n}(value: Int): Int = (value * {n}) % 997\n\n"),
+];
+
+// A file change for one commit: another snippet appended to `dir/file`
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub dir: &'static str,
+    pub file: String,
+    template: &'static str,
+}
+
+impl FileChange {
+    // The file's content after appending the next snippet
+    pub fn apply(&self, existing: &[u8]) -> Vec<u8> {
+        let items = String::from_utf8_lossy(existing).matches("step").count() + 1;
+        let mut content = existing.to_vec();
+        content.extend_from_slice(self.template.replace("{n}", &items.to_string()).as_bytes());
+        content
+    }
+}
+
+// Target mix of languages for the grid repo's files. GitHub's language bar
+// counts bytes, and every language gets snippets of about the same size,
+// so the share of commits per extension becomes the share of the bar.
+#[derive(Debug, Clone)]
+pub struct LanguageMix {
+    shares: Vec<(usize, f64)>,
+}
+
+impl LanguageMix {
+    // `EXT=WEIGHT` pairs, e.g. `rs=60,py=30,ts=10`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut shares = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (ext, weight) = part.split_once('=')
+                .ok_or_else(|| GitHubGridError::Parse(format!("Invalid language share '{}', expected EXT=WEIGHT", part)))?;
+            let ext = ext.trim().trim_start_matches('.');
+            let index = LANGUAGES.iter().position(|(known, ..)| *known == ext)
+                .ok_or_else(|| GitHubGridError::Config(format!(
+                    "Unsupported extension '{}' (supported: {})", ext, supported().join(", ")
+                )))?;
+            let weight: f64 = weight.trim().parse()
+                .ok()
+                .filter(|w: &f64| w.is_finite() && *w >= 0.0)
+                .ok_or_else(|| GitHubGridError::Parse(format!("Invalid weight in language share '{}'", part)))?;
+            if shares.iter().any(|(i, _)| *i == index) {
+                return Err(GitHubGridError::Config(format!("Extension '{}' is listed twice", ext)));
+            }
+            shares.push((index, weight));
+        }
+        if shares.iter().all(|(_, weight)| *weight == 0.0) {
+            return Err(GitHubGridError::Config("Language mix needs at least one positive weight".to_string()));
+        }
+        Ok(Self { shares })
+    }
+
+    pub fn pick(&self, rng: &mut impl Rng) -> FileChange {
+        let total: f64 = self.shares.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.random_range(0.0..total);
+        let (index, _) = self.shares.iter()
+            .find(|(_, weight)| {
+                roll -= weight;
+                roll < 0.0 && *weight > 0.0
+            })
+            .or_else(|| self.shares.iter().rev().find(|(_, weight)| *weight > 0.0))
+            .unwrap();
+
+        let (ext, dir, stems, template) = LANGUAGES[*index];
+        let stem = stems[rng.random_range(0..stems.len())];
+        FileChange { dir, file: format!("{}.{}", stem, ext), template }
+    }
+}
+
+pub fn supported() -> Vec<&'static str> {
+    LANGUAGES.iter().map(|(ext, ..)| *ext).collect()
+}
@@ -0,0 +1,39 @@
+//! Realistic Git commit pattern generation for GitHub contribution graphs.
+//!
+//! The `github-grid` binary is a thin CLI over this crate; other tools can
+//! embed the generator by implementing or configuring a [`Pattern`] and
+//! feeding its [`CommitInfo`] plan to [`GitOperations`].
+
+pub mod error;
+pub mod patterns;
+pub mod registry;
+pub mod messages;
+pub mod calibrate;
+pub mod fit;
+pub mod plan;
+pub mod git_ops;
+pub mod github;
+pub mod forge;
+pub mod dates;
+pub mod itinerary;
+pub mod calendar;
+pub mod holidays;
+pub mod crunch;
+pub mod organic;
+pub mod art;
+pub mod activity;
+pub mod verify;
+pub mod lint;
+pub mod languages;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use error::{GitHubGridError, Result};
+pub use patterns::{CommitInfo, ConfigurablePattern, IntensityLevel, Pattern, PatternConfig, PatternConfigBuilder, Workweek};
+pub use plan::Plan;
+pub use registry::{PatternFactory, PatternRegistry};
+pub use messages::MessageBank;
+pub use languages::LanguageMix;
+pub use git_ops::GitOperations;
+pub use github::{GitHubClient, TokenSource};
+pub use forge::{Forge, MockForge};
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use chrono::DateTime;
+use git2::Oid;
+use rand::{rng, Rng};
+use crate::git_ops::{is_autogen, CommitEdit, HistoryCommit};
+use crate::messages::MessageBank;
+
+// Too few commits make the :00-seconds share meaningless
+const MIN_SECONDS_SAMPLE: usize = 20;
+// Real commits land on :00 about 1 time in 60
+const ZERO_SECONDS_SHARE: f64 = 0.5;
+// Longest message cycle looked for, in commits
+const MAX_CYCLE: usize = 10;
+// Local hours nobody plausibly commits in, as start..end
+const IMPOSSIBLE_HOURS: (i64, i64) = (2, 5);
+// Daytime window that impossible-hour commits are moved into
+const DAYTIME_HOURS: (i64, i64) = (9, 22);
+
+// A kind of tell that a history was generated rather than lived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Artifact {
+    DuplicateTimestamp,
+    ZeroSeconds,
+    MessageCycle,
+    ImpossibleHour,
+}
+
+impl Artifact {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Artifact::DuplicateTimestamp => "share a timestamp with another commit",
+            Artifact::ZeroSeconds => "are timestamped on :00 seconds",
+            Artifact::MessageCycle => "repeat messages in a fixed cycle",
+            Artifact::ImpossibleHour => "were made between 02:00 and 05:00 local time",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LintReport {
+    pub scanned: usize,
+    pub findings: BTreeMap<Artifact, Vec<Oid>>,
+}
+
+impl LintReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+// `commits` oldest first, as returned by `GitOperations::history`
+pub fn lint(commits: &[HistoryCommit]) -> LintReport {
+    let mut findings = BTreeMap::new();
+    let mut add = |artifact, oids: Vec<Oid>| {
+        if !oids.is_empty() {
+            findings.insert(artifact, oids);
+        }
+    };
+
+    // Every commit after the first one on a given second
+    let mut seen = HashSet::new();
+    add(Artifact::DuplicateTimestamp, commits.iter()
+        .filter(|c| !seen.insert(c.seconds))
+        .map(|c| c.oid)
+        .collect());
+
+    let zero: Vec<Oid> = commits.iter().filter(|c| c.seconds % 60 == 0).map(|c| c.oid).collect();
+    if commits.len() >= MIN_SECONDS_SAMPLE && zero.len() as f64 / commits.len() as f64 > ZERO_SECONDS_SHARE {
+        add(Artifact::ZeroSeconds, zero);
+    }
+
+    add(Artifact::MessageCycle, message_cycles(commits));
+
+    add(Artifact::ImpossibleHour, commits.iter()
+        .filter(|c| (IMPOSSIBLE_HOURS.0..IMPOSSIBLE_HOURS.1).contains(&(local_seconds_of_day(c) / 3600)))
+        .map(|c| c.oid)
+        .collect());
+
+    LintReport { scanned: commits.len(), findings }
+}
+
+// Commits whose message equals the one `period` commits earlier, for runs
+// long enough to cover the cycle at least twice (and never shorter than 4,
+// so a couple of "wip" commits in a row don't count)
+fn message_cycles(commits: &[HistoryCommit]) -> Vec<Oid> {
+    let mut flagged = vec![false; commits.len()];
+    for period in 1..=MAX_CYCLE {
+        let min_run = (2 * period).max(4);
+        let mut run_start = period;
+        for i in period..=commits.len() {
+            let repeats = i < commits.len() && commits[i].message == commits[i - period].message;
+            if repeats {
+                continue;
+            }
+            if i - run_start >= min_run {
+                flagged[run_start..i].iter_mut().for_each(|f| *f = true);
+            }
+            run_start = i + 1;
+        }
+    }
+    commits.iter().zip(flagged).filter(|(_, f)| *f).map(|(c, _)| c.oid).collect()
+}
+
+fn local_seconds_of_day(commit: &HistoryCommit) -> i64 {
+    (commit.seconds + commit.offset_minutes as i64 * 60).rem_euclid(86_400)
+}
+
+// Edits that clear the chosen artifacts. Times stay on the same local day
+// so the contribution graph doesn't change, and never collide with another
+// commit. Messages are only replaced on [AutoGen] commits, with new ones
+// drawn from `messages`.
+pub fn fixes(
+    commits: &[HistoryCommit],
+    report: &LintReport,
+    artifacts: &[Artifact],
+    messages: &mut MessageBank,
+) -> HashMap<Oid, CommitEdit> {
+    let flagged = |artifact: Artifact| -> HashSet<Oid> {
+        if !artifacts.contains(&artifact) {
+            return HashSet::new();
+        }
+        report.findings.get(&artifact).map(|oids| oids.iter().copied().collect()).unwrap_or_default()
+    };
+    let (duplicate, zero, cycle, impossible) = (
+        flagged(Artifact::DuplicateTimestamp),
+        flagged(Artifact::ZeroSeconds),
+        flagged(Artifact::MessageCycle),
+        flagged(Artifact::ImpossibleHour),
+    );
+
+    let mut rng = rng();
+    let mut used: HashSet<i64> = commits.iter().map(|c| c.seconds).collect();
+    let mut edits = HashMap::new();
+
+    for (i, commit) in commits.iter().enumerate() {
+        let mut edit = CommitEdit::default();
+
+        let restamp = duplicate.contains(&commit.oid) || zero.contains(&commit.oid);
+        if restamp || impossible.contains(&commit.oid) {
+            let day_start = commit.seconds - local_seconds_of_day(commit);
+            let mut seconds = if impossible.contains(&commit.oid) {
+                day_start
+                    + rng.random_range(DAYTIME_HOURS.0..DAYTIME_HOURS.1) * 3600
+                    + rng.random_range(0..60) * 60
+            } else {
+                commit.seconds - commit.seconds % 60
+            };
+            seconds += rng.random_range(1..60);
+
+            // Nudge forward past any commit already on that second, staying within the day
+            while used.contains(&seconds) || seconds % 60 == 0 {
+                seconds += 1;
+                if seconds >= day_start + 86_400 {
+                    seconds = day_start + 1;
+                }
+            }
+            used.insert(seconds);
+            edit.seconds = Some(seconds);
+        }
+
+        // A timestamp chrono can't represent has no date to number tickets from
+        let date = commit.seconds.checked_add(commit.offset_minutes as i64 * 60)
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+            .map(|time| time.date_naive());
+        if cycle.contains(&commit.oid) && is_autogen(&commit.message) && let Some(date) = date {
+            let neighbours: Vec<&str> = [i.checked_sub(1), Some(i + 1)].into_iter()
+                .flatten()
+                .filter_map(|j| commits.get(j))
+                .map(|c| c.message.as_str())
+                .collect();
+
+            // A tiny message bank may not have anything else to offer
+            let mut message = messages.message_for(date);
+            for _ in 0..20 {
+                if !neighbours.contains(&message.as_str()) {
+                    break;
+                }
+                message = messages.message_for(date);
+            }
+            edit.message = Some(message);
+        }
+
+        if edit.seconds.is_some() || edit.message.is_some() {
+            edits.insert(commit.oid, edit);
+        }
+    }
+    edits
+}
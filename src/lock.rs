@@ -0,0 +1,310 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use chrono::{DateTime, Duration, Local};
+use github_grid::error::{GitHubGridError, Result};
+
+const LOCK_FILE: &str = "github-grid.lock";
+// Held while a stale lock is replaced, so only one run can take it over
+const BREAK_FILE: &str = "github-grid.lock.break";
+
+// A break file is only held for a moment, so one without readable details
+// (its run died before writing them) is abandoned after this long
+const BREAK_STALE_AFTER: std::time::Duration = std::time::Duration::from_secs(60);
+
+// Locks from another host, whose pid can't be checked, are considered
+// abandoned after this long
+const STALE_AFTER_HOURS: i64 = 12;
+
+// Exclusive per-repository run lock stored in the repo's git dir, so a cron
+// job and a manual run can't interleave commit creation and pushes.
+// Released when dropped.
+pub struct RunLock {
+    path: PathBuf,
+}
+
+struct LockInfo {
+    pid: u32,
+    host: String,
+    started: Option<DateTime<Local>>,
+}
+
+impl RunLock {
+    pub fn acquire(git_dir: &Path, force_unlock: bool) -> Result<Self> {
+        let path = git_dir.join(LOCK_FILE);
+        let break_path = git_dir.join(BREAK_FILE);
+
+        if force_unlock && path.exists() {
+            eprintln!("🔓 Removing existing lock {}", path.display());
+            fs::remove_file(&path)?;
+        }
+        if force_unlock && break_path.exists() {
+            fs::remove_file(&break_path)?;
+        }
+
+        // Written in full before it appears under the lock name, so another
+        // run never sees an empty lock
+        let temp = git_dir.join(format!("{}.{}.tmp", LOCK_FILE, std::process::id()));
+        let _ = fs::remove_file(&temp);
+        write_lock_info(&temp)?;
+        let result = Self::place(&temp, &path, &break_path);
+        let _ = fs::remove_file(&temp);
+        result
+    }
+
+    fn place(temp: &Path, path: &Path, break_path: &Path) -> Result<Self> {
+        // A hard link, unlike a rename, fails when the lock already exists
+        match fs::hard_link(temp, path) {
+            Ok(()) => return Ok(Self { path: path.to_path_buf() }),
+            Err(e) if e.kind() != ErrorKind::AlreadyExists => return Err(e.into()),
+            Err(_) => {}
+        }
+
+        let held = || match read_lock_info(path) {
+            Some(info) => GitHubGridError::Repository(format!(
+                "Another github-grid run holds the lock (pid {} on {}, started {}). \
+                 Wait for it to finish or pass --force-unlock",
+                info.pid,
+                info.host,
+                info.started.map_or("at an unknown time".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string()),
+            )),
+            None => GitHubGridError::Repository(format!(
+                "Another github-grid run holds the lock {}. Wait for it to finish or pass --force-unlock",
+                path.display()
+            )),
+        };
+
+        // Only one run may replace a stale lock; the rest see it as held
+        if !claim_break(break_path)? {
+            return Err(held());
+        }
+        let _guard = BreakGuard(break_path);
+        if !read_lock_info(path).is_some_and(|info| info.is_stale()) {
+            return Err(held());
+        }
+        eprintln!("🔓 Removing stale lock {}", path.display());
+        fs::remove_file(path)?;
+        match fs::hard_link(temp, path) {
+            Ok(()) => Ok(Self { path: path.to_path_buf() }),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(held()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// Creates the break file with this run's details. One left by a run that
+// died while breaking a lock is cleared the way a stale lock is, so it
+// can't block every later run.
+fn claim_break(break_path: &Path) -> Result<bool> {
+    for _ in 0..2 {
+        match write_lock_info(break_path) {
+            Ok(()) => return Ok(true),
+            Err(GitHubGridError::Io(e)) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        let abandoned = match read_lock_info(break_path) {
+            Some(info) => info.is_stale(),
+            None => fs::metadata(break_path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > BREAK_STALE_AFTER)),
+        };
+        if !abandoned {
+            return Ok(false);
+        }
+        eprintln!("🔓 Removing abandoned {}", break_path.display());
+        let _ = fs::remove_file(break_path);
+    }
+    Ok(false)
+}
+
+struct BreakGuard<'a>(&'a Path);
+
+impl Drop for BreakGuard<'_> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(self.0);
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl LockInfo {
+    fn is_stale(&self) -> bool {
+        // Liveness can only be checked for processes on this machine
+        if self.host == hostname() {
+            !process_alive(self.pid)
+        } else {
+            self.started.is_some_and(|started| Local::now() - started > Duration::hours(STALE_AFTER_HOURS))
+        }
+    }
+}
+
+fn write_lock_info(path: &Path) -> Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    writeln!(file, "pid={}", std::process::id())?;
+    writeln!(file, "host={}", hostname())?;
+    writeln!(file, "started={}", Local::now().to_rfc3339())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn read_lock_info(path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut pid = None;
+    let mut host = String::from("unknown host");
+    let mut started = None;
+
+    for line in content.lines() {
+        match line.split_once('=') {
+            Some(("pid", value)) => pid = value.trim().parse().ok(),
+            Some(("host", value)) => host = value.trim().to_string(),
+            Some(("started", value)) => {
+                started = DateTime::parse_from_rfc3339(value.trim())
+                    .ok()
+                    .map(|t| t.with_timezone(&Local));
+            }
+            _ => {}
+        }
+    }
+
+    Some(LockInfo { pid: pid?, host, started })
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+fn process_alive(pid: u32) -> bool {
+    if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+            .unwrap_or(true)
+    } else {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("github-grid-lock-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_lock(dir: &Path, pid: u32, host: &str, started: DateTime<Local>) {
+        let content = format!("pid={}\nhost={}\nstarted={}\n", pid, host, started.to_rfc3339());
+        fs::write(dir.join(LOCK_FILE), content).unwrap();
+    }
+
+    // A pid that has exited and been reaped
+    fn dead_pid() -> u32 {
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        let dir = git_dir("exclusive");
+        let lock = RunLock::acquire(&dir, false).unwrap();
+        assert!(RunLock::acquire(&dir, false).is_err());
+        drop(lock);
+        assert!(!dir.join(LOCK_FILE).exists());
+        assert!(RunLock::acquire(&dir, false).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lock_of_a_dead_process_is_taken_over() {
+        let dir = git_dir("dead");
+        write_lock(&dir, dead_pid(), &hostname(), Local::now());
+        let _lock = RunLock::acquire(&dir, false).unwrap();
+        let info = read_lock_info(&dir.join(LOCK_FILE)).unwrap();
+        assert_eq!(info.pid, std::process::id());
+        assert!(!dir.join(BREAK_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lock_of_a_live_process_is_held() {
+        let dir = git_dir("live");
+        write_lock(&dir, std::process::id(), &hostname(), Local::now() - Duration::days(2));
+        assert!(RunLock::acquire(&dir, false).is_err());
+        assert!(dir.join(LOCK_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lock_from_another_host_goes_stale_with_age() {
+        let dir = git_dir("remote");
+        write_lock(&dir, 1, "elsewhere.example", Local::now() - Duration::hours(1));
+        assert!(RunLock::acquire(&dir, false).is_err());
+        write_lock(&dir, 1, "elsewhere.example", Local::now() - Duration::hours(STALE_AFTER_HOURS + 1));
+        assert!(RunLock::acquire(&dir, false).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unreadable_lock_is_held_unless_forced() {
+        let dir = git_dir("empty");
+        fs::write(dir.join(LOCK_FILE), "").unwrap();
+        assert!(RunLock::acquire(&dir, false).is_err());
+        assert!(RunLock::acquire(&dir, true).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn abandoned_break_file_is_cleared() {
+        let dir = git_dir("abandoned");
+        write_lock(&dir, dead_pid(), &hostname(), Local::now());
+        fs::write(dir.join(BREAK_FILE), format!("pid={}\nhost={}\n", dead_pid(), hostname())).unwrap();
+        let _lock = RunLock::acquire(&dir, false).unwrap();
+        assert!(!dir.join(BREAK_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_break_file_is_cleared_once_old() {
+        let dir = git_dir("old-break");
+        write_lock(&dir, dead_pid(), &hostname(), Local::now());
+        let break_file = fs::File::create(dir.join(BREAK_FILE)).unwrap();
+        break_file.set_modified(std::time::SystemTime::now() - BREAK_STALE_AFTER * 2).unwrap();
+        assert!(RunLock::acquire(&dir, false).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn only_one_run_breaks_a_stale_lock() {
+        let dir = git_dir("break");
+        write_lock(&dir, dead_pid(), &hostname(), Local::now());
+        fs::write(dir.join(BREAK_FILE), format!("pid={}\nhost={}\n", std::process::id(), hostname())).unwrap();
+        assert!(RunLock::acquire(&dir, false).is_err());
+        fs::write(dir.join(BREAK_FILE), "").unwrap();
+        assert!(RunLock::acquire(&dir, false).is_err());
+        assert!(dir.join(BREAK_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
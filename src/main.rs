@@ -1,329 +1,3546 @@
-use chrono::{Local, NaiveDate, Datelike};
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Local, NaiveDate, Datelike, Weekday};
+use clap::{Args, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap::parser::ValueSource;
 use git2::{Repository, Signature};
-use indicatif::{ProgressBar, ProgressStyle};
-use std::path::PathBuf;
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::time::Duration as StdDuration;
 
-mod patterns;
-mod git_ops;
-mod github;
-mod error;
+mod progress;
+mod editor;
+mod daemon;
+mod schedule;
+mod actions;
+mod lock;
+mod notify;
+mod history;
+mod repo_config;
+mod fleet;
+mod repos;
+mod skeleton;
+mod readme_stats;
+mod compare;
+mod gif;
+mod playback;
+mod run_report;
+mod email;
 
-use patterns::{Pattern, CommitInfo, RealisticPattern, SteadyPattern, SporadicPattern, ContractorPattern, CasualPattern, ActivePattern, MaintainerPattern, HyperactivePattern, ExtremePattern, PatternConfig, IntensityLevel, ConfigurablePattern};
-use git_ops::*;
-use github::GitHubClient;
-use error::{GitHubGridError, Result};
+use github_grid::{art, calendar, crunch, dates, git_ops, github, holidays, lint, patterns, plan, verify};
+use github_grid::plan::Plan;
+use github_grid::patterns::{Pattern, CommitInfo, ConfigurablePattern, FourDayPattern, PatternConfig};
+use github_grid::calibrate::{self, Calibration};
+use github_grid::fit::{self, Constraints, Distribution, PlanStats};
+use github_grid::registry::{PatternCategory, PatternRegistry};
+use github_grid::messages::MessageBank;
+use github_grid::languages::LanguageMix;
+use github_grid::itinerary::Itinerary;
+use github_grid::holidays::Holidays;
+use github_grid::crunch::{CrunchPattern, CrunchWindow};
+use github_grid::organic::{DayNoise, OrganicPattern};
+use github_grid::activity::CodingTime;
+use github_grid::git_ops::*;
+use github_grid::forge::{CloneMethod, CloneOptions, Forge, MockForge};
+use github_grid::github::{GitHubClient, TokenSource};
+use github_grid::error::{exit_code, GitHubGridError, Result};
+use progress::{ProgressMode, ProgressReporter};
+use lock::RunLock;
+use notify::{NotifyOptions, RunSummary};
+use repo_config::RepoConfig;
 
-#[derive(Parser)]
+#[derive(Parser, Clone)]
 #[command(name = "github-grid")]
 #[command(about = "Generate realistic Git commit patterns for GitHub contribution graphs")]
 struct Cli {
     /// Target repository path
-    #[arg(short, long)]
+    #[arg(short, long, global = true, env = "GITHUB_GRID_REPO")]
     repo: Option<PathBuf>,
     
+    /// Branch to commit to and push
+    #[arg(long, env = "GITHUB_GRID_BRANCH", default_value = "main")]
+    branch: String,
+    
+    #[command(flatten)]
+    range: RangeArgs,
+    
+    /// Fleet file listing several target repos ([[repo]] path/pattern/target_total/branch) to run in turn
+    #[arg(long, value_name = "FILE", conflicts_with = "repo", global = true, env = "GITHUB_GRID_FLEET")]
+    fleet: Option<PathBuf>,
+    
+    /// Target total commits for the year (overrides pattern)
+    #[arg(long, env = "GITHUB_GRID_TARGET_TOTAL")]
+    target_total: Option<u32>,
+    
+    /// Add or remove commits after generation so --target-total is hit exactly
+    #[arg(long, requires = "target_total", env = "GITHUB_GRID_EXACT")]
+    exact: bool,
+    
+    /// Per-year override of --target-total as YEAR=TOTAL (repeatable)
+    #[arg(long = "year-target", value_name = "YEAR=TOTAL", requires = "target_total", env = "GITHUB_GRID_YEAR_TARGETS", value_delimiter = ',')]
+    year_targets: Vec<String>,
+    
+    /// What to do with days that already have [AutoGen] commits
+    #[arg(long, value_enum, default_value_t = ExistingDays::Skip, env = "GITHUB_GRID_ON_EXISTING")]
+    on_existing: ExistingDays,
+    
+    /// Which existing commits count toward --target-total
+    #[arg(long, value_enum, default_value_t = TargetScope::All, env = "GITHUB_GRID_TARGET_SCOPE")]
+    target_scope: TargetScope,
+    
+    /// Allowed deviation from --target-total in percent when calibrating
+    #[arg(long, default_value = "2", env = "GITHUB_GRID_TOLERANCE")]
+    tolerance: f64,
+    
+    /// Fraction of commits that fall on weekends (0-1), e.g. 0.1; any pattern is adjusted to match
+    #[arg(long, value_name = "RATIO", env = "GITHUB_GRID_WEEKEND_RATIO")]
+    weekend_ratio: Option<f64>,
+    
+    /// Share of days in the range that get at least one commit, e.g. 70% (the total stays the same)
+    #[arg(long, value_name = "PERCENT", value_parser = patterns::parse_share, env = "GITHUB_GRID_ACTIVE_DAYS")]
+    active_days: Option<f64>,
+    
+    /// Fewest commits on any day that has commits; thin days are topped up from busier ones
+    #[arg(long, value_name = "N", env = "GITHUB_GRID_MIN_PER_DAY")]
+    min_per_day: Option<u32>,
+    
+    /// Most commits on any single day; clipped commits move to other days so the total holds
+    #[arg(long, value_name = "N", env = "GITHUB_GRID_MAX_PER_DAY")]
+    max_per_day: Option<u32>,
+    
+    /// Pattern to use: a name from `patterns`, or a .toml file written by `fit`
+    #[arg(short, long, env = "GITHUB_GRID_PATTERN", default_value = "realistic")]
+    pattern: String,
+    
+    /// Weekday off for the four-day pattern (default Fri)
+    #[arg(long, value_name = "DAY", env = "GITHUB_GRID_DAY_OFF")]
+    day_off: Option<Weekday>,
+    
+    /// Crunch window layered onto the pattern (START..ENDxMULTIPLIER, repeatable)
+    #[arg(long, value_name = "START..ENDxN", value_delimiter = ',', env = "GITHUB_GRID_CRUNCH",
+          conflicts_with = "target_total")]
+    crunch: Vec<String>,
+    
+    /// Intensify the end of every calendar quarter by this multiplier (e.g. 1.8)
+    #[arg(long, value_name = "MULTIPLIER", env = "GITHUB_GRID_QUARTER_END", conflicts_with = "target_total")]
+    quarter_end: Option<f64>,
+    
+    /// How many weeks before each quarter's end the push starts
+    #[arg(long, value_name = "WEEKS", default_value_t = 2, requires = "quarter_end", env = "GITHUB_GRID_QUARTER_END_WEEKS")]
+    quarter_end_weeks: u32,
+    
+    /// Hackathon weekends a year: Saturday and Sunday bursts that run late into the night
+    #[arg(long, value_name = "N", env = "GITHUB_GRID_HACKATHONS", conflicts_with = "target_total")]
+    hackathons: Option<u32>,
+    
+    /// Let a smooth noise field over the year drive intensity, clustering busy and quiet stretches
+    #[arg(long, env = "GITHUB_GRID_ORGANIC", conflicts_with = "target_total")]
+    organic: bool,
+    
+    /// Typical length of an organic hot or cold stretch, in days
+    #[arg(long, value_name = "DAYS", default_value_t = 42, requires = "organic", env = "GITHUB_GRID_ORGANIC_PERIOD")]
+    organic_period: u32,
+    
+    /// Noise seed for --organic, to reproduce an earlier run's shape (see its --report)
+    #[arg(long, value_name = "SEED", requires = "organic", env = "GITHUB_GRID_ORGANIC_SEED")]
+    organic_seed: Option<u64>,
+    
+    /// Show preview without committing
+    #[arg(long, env = "GITHUB_GRID_DRY_RUN")]
+    dry_run: bool,
+    
+    /// Commits created between pushes
+    #[arg(long, env = "GITHUB_GRID_BATCH_SIZE", default_value_t = 500)]
+    batch_size: usize,
+    
+    /// Use a local mock of GitHub instead of the API: no token or network needed (see "Offline Mode" in the README)
+    #[arg(long, global = true, env = "GITHUB_GRID_OFFLINE")]
+    offline: bool,
+    
+    /// Where GitHub credentials come from
+    #[arg(long, value_enum, global = true, env = "GITHUB_GRID_TOKEN_SOURCE", default_value_t = TokenSource::Gh)]
+    token_source: TokenSource,
+    
+    /// GitHub App ID, for --token-source app
+    #[arg(long, value_name = "ID", global = true, required_if_eq("token_source", "app"), env = "GITHUB_GRID_APP_ID")]
+    app_id: Option<String>,
+    
+    /// The GitHub App's private key (.pem), for --token-source app
+    #[arg(long, value_name = "FILE", global = true, required_if_eq("token_source", "app"), env = "GITHUB_GRID_APP_KEY")]
+    app_key: Option<PathBuf>,
+    
+    /// Account the GitHub App is installed on, whose graph and repos it manages
+    #[arg(long, value_name = "LOGIN", global = true, required_if_eq("token_source", "app"), env = "GITHUB_GRID_APP_LOGIN")]
+    app_login: Option<String>,
+    
+    /// Installation ID for --token-source app (looked up from --app-login by default)
+    #[arg(long, value_name = "ID", global = true, env = "GITHUB_GRID_APP_INSTALLATION")]
+    app_installation: Option<u64>,
+    
+    /// Progress output format (json emits JSON-lines events on stdout)
+    #[arg(long, value_enum, env = "GITHUB_GRID_PROGRESS", default_value_t = ProgressMode::Bar)]
+    progress: ProgressMode,
+    
+    /// Exit with code 5 instead of 0 when a run had nothing to do (see "Exit Codes" in the README)
+    #[arg(long, global = true, env = "GITHUB_GRID_DETAILED_EXIT_CODES")]
+    detailed_exit_codes: bool,
+    
+    /// Commit message file replacing the built-in list (.txt one per line, or .toml with weighted categories)
+    #[arg(long, value_name = "FILE", global = true, env = "GITHUB_GRID_MESSAGES")]
+    messages: Option<PathBuf>,
+    
+    /// Relative weight of a message category as NAME=WEIGHT (repeatable; built-in: features, fixes, chores)
+    #[arg(long = "message-weight", value_name = "NAME=WEIGHT", global = true, env = "GITHUB_GRID_MESSAGE_WEIGHTS", value_delimiter = ',')]
+    message_weights: Vec<String>,
+    
+    /// How generated commit messages are formatted
+    #[arg(long, value_enum, global = true, default_value_t = MessageStyle::Plain, env = "GITHUB_GRID_MESSAGE_STYLE")]
+    message_style: MessageStyle,
+    
+    /// Project key for --message-style ticket (e.g. PROJ gives `PROJ-1423: ...`)
+    #[arg(long, value_name = "KEY", global = true, required_if_eq("message_style", "ticket"), env = "GITHUB_GRID_TICKET_PREFIX")]
+    ticket_prefix: Option<String>,
+    
+    /// Share of messages that reference an issue, e.g. `(#123)` or `Fixes #456` (0-1)
+    #[arg(long, value_name = "SHARE", global = true, env = "GITHUB_GRID_ISSUE_REFS")]
+    issue_refs: Option<f64>,
+    
+    /// Author name for generated commits (defaults to git's user.name)
+    #[arg(long, global = true, env = "GITHUB_GRID_AUTHOR_NAME")]
+    author_name: Option<String>,
+    
+    /// Author email for generated commits (defaults to git's user.email)
+    #[arg(long, global = true, env = "GITHUB_GRID_AUTHOR_EMAIL")]
+    author_email: Option<String>,
+    
+    /// Proxy URL for pushes, e.g. http://proxy.corp:3128 (defaults to git's http.proxy or HTTPS_PROXY)
+    #[arg(long, value_name = "URL", global = true, env = "GITHUB_GRID_PROXY")]
+    proxy: Option<String>,
+    
+    /// Append a DCO `Signed-off-by:` trailer with your git identity to every generated commit
+    #[arg(long, global = true, env = "GITHUB_GRID_SIGNOFF")]
+    signoff: bool,
+    
+    /// Commit hooks in the target repo: skip them with a warning, run them for every commit, or refuse to commit
+    #[arg(long, value_enum, global = true, default_value_t = HooksMode::Skip, env = "GITHUB_GRID_HOOKS")]
+    hooks: HooksMode,
+    
+    /// What each generated commit changes (log: appends a dated line to log/YYYY-MM.jsonl)
+    #[arg(long, value_enum, global = true, default_value_t = ContentMode::Empty, env = "GITHUB_GRID_CONTENT")]
+    content: ContentMode,
+    
+    /// Give each commit a file change in these languages by weight, e.g. rs=60,py=30,ts=10 (shapes the profile language bar)
+    #[arg(long, value_name = "EXT=WEIGHT,...", global = true, conflicts_with = "content", env = "GITHUB_GRID_LANGUAGES")]
+    languages: Option<String>,
+    
+    /// WakaTime (.json) or RescueTime (.csv) export: spread commits over days in proportion to tracked coding time
+    #[arg(long, value_name = "FILE", global = true, env = "GITHUB_GRID_CODING_TIME")]
+    coding_time: Option<PathBuf>,
+    
+    /// Only generate on days without commits in your public repos, leaving the authentic parts of the graph alone
+    #[arg(long, global = true, env = "GITHUB_GRID_FILL_INACTIVE")]
+    fill_inactive: bool,
+    
+    /// Keep a section in the target repo's README with this year's grid and stats, committed after each run
+    #[arg(long, global = true, env = "GITHUB_GRID_README_STATS")]
+    readme_stats: bool,
+    
+    /// TOML travel itinerary ([[trip]] start/end/utc_offset) shifting commit times and offsets
+    #[arg(long, value_name = "FILE", global = true, env = "GITHUB_GRID_ITINERARY")]
+    itinerary: Option<PathBuf>,
+    
+    /// Adjust the generated plan day-by-day in an interactive grid editor
+    #[arg(long, env = "GITHUB_GRID_EDIT")]
+    edit: bool,
+    
+    /// Create commits as the pattern generates them instead of planning the whole range first (for huge ranges)
+    #[arg(long, env = "GITHUB_GRID_STREAM", conflicts_with_all = [
+        "target_total", "edit", "coding_time", "weekend_ratio", "active_days", "min_per_day", "max_per_day", "dry_run",
+    ])]
+    stream: bool,
+    
+    /// Create commits locally without pushing (publish later with `push`)
+    #[arg(long, global = true, env = "GITHUB_GRID_NO_PUSH")]
+    no_push: bool,
+    
+    /// Don't fetch origin and fast-forward the branch before generating
+    #[arg(long, global = true, env = "GITHUB_GRID_NO_FETCH")]
+    no_fetch: bool,
+    
+    /// Don't check the live contribution graph after pushing
+    #[arg(long, global = true, env = "GITHUB_GRID_NO_VERIFY")]
+    no_verify: bool,
+    
+    /// How long post-push verification waits for the graph to update (e.g. 90s, 10m)
+    #[arg(long, global = true, value_name = "DURATION", default_value = "60s", value_parser = dates::parse_wait, env = "GITHUB_GRID_VERIFY_WAIT")]
+    verify_wait: StdDuration,
+    
+    /// Skip the confirmation prompt (for scripts)
+    #[arg(short, long, env = "GITHUB_GRID_YES")]
+    yes: bool,
+    
+    /// Show a desktop notification when the run finishes
+    #[arg(long, global = true, env = "GITHUB_GRID_NOTIFY_DESKTOP")]
+    notify_desktop: bool,
+    
+    /// POST a JSON run summary to this URL when the run finishes (repeatable)
+    #[arg(long = "webhook", value_name = "URL", global = true, env = "GITHUB_GRID_WEBHOOKS", value_delimiter = ',')]
+    webhooks: Vec<String>,
+    
+    /// Post a readable run summary to this Slack incoming webhook when the run finishes (repeatable)
+    #[arg(long = "slack-webhook", value_name = "URL", global = true, env = "GITHUB_GRID_SLACK_WEBHOOKS", value_delimiter = ',')]
+    slack_webhooks: Vec<String>,
+    
+    /// Post a readable run summary to this Discord webhook when the run finishes (repeatable)
+    #[arg(long = "discord-webhook", value_name = "URL", global = true, env = "GITHUB_GRID_DISCORD_WEBHOOKS", value_delimiter = ',')]
+    discord_webhooks: Vec<String>,
+    
+    /// Write a report of the run (parameters, seed, monthly totals, grid, warnings); .html for HTML, otherwise markdown
+    #[arg(long, value_name = "FILE", global = true, env = "GITHUB_GRID_REPORT")]
+    report: Option<PathBuf>,
+    
+    /// Email the run report when the run finishes, using the SMTP settings in ~/.config/github-grid/smtp.toml
+    #[arg(long, global = true, env = "GITHUB_GRID_EMAIL")]
+    email: bool,
+    
+    /// Remove an existing run lock on the target repo before starting
+    #[arg(long, global = true)]
+    force_unlock: bool,
+    
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Args, Clone)]
+struct RangeArgs {
     /// Start date (YYYY-MM-DD)
-    #[arg(long)]
+    #[arg(long, env = "GITHUB_GRID_START")]
     start: Option<String>,
     
-    /// End date (YYYY-MM-DD)
-    #[arg(long)]
-    end: Option<String>,
+    /// End date (YYYY-MM-DD)
+    #[arg(long, env = "GITHUB_GRID_END")]
+    end: Option<String>,
+    
+    /// Generate for a full calendar year (e.g. 2023)
+    #[arg(long, env = "GITHUB_GRID_YEAR", conflicts_with_all = ["start", "end", "last", "month", "since_last_run"])]
+    year: Option<i32>,
+    
+    /// Generate for a trailing window ending today (e.g. 90d, 12w, 6m, 1y)
+    #[arg(long, env = "GITHUB_GRID_LAST", conflicts_with_all = ["start", "end", "month", "since_last_run"])]
+    last: Option<String>,
+    
+    /// Generate for a full calendar month (YYYY-MM)
+    #[arg(long, env = "GITHUB_GRID_MONTH", conflicts_with_all = ["start", "end", "since_last_run"])]
+    month: Option<String>,
+    
+    /// Continue from the day after the last [AutoGen] commit
+    #[arg(long, env = "GITHUB_GRID_SINCE_LAST_RUN", conflicts_with = "start")]
+    since_last_run: bool,
+    
+    /// Explicit range to generate (START..END, repeatable for disjoint periods)
+    #[arg(long = "range", value_name = "START..END", env = "GITHUB_GRID_RANGES", value_delimiter = ',',
+          conflicts_with_all = ["start", "end", "year", "last", "month", "since_last_run"])]
+    ranges: Vec<String>,
+    
+    /// Blackout range with no commits (START..END or a single day, repeatable)
+    #[arg(long, value_name = "START..END", env = "GITHUB_GRID_EXCLUDE", value_delimiter = ',')]
+    exclude: Vec<String>,
+    
+    /// Calendar export (.ics) whose matching all-day events become blackout days
+    #[arg(long, value_name = "FILE", env = "GITHUB_GRID_CALENDAR")]
+    calendar: Option<PathBuf>,
+    
+    /// Words in an all-day event's title that mark it as time off (comma-separated)
+    #[arg(long, value_name = "WORDS", value_delimiter = ',', requires = "calendar", env = "GITHUB_GRID_CALENDAR_KEYWORDS",
+          default_values_t = calendar::DEFAULT_KEYWORDS.iter().map(|k| k.to_string()))]
+    calendar_keywords: Vec<String>,
+    
+    /// Public holidays to skip, by country code (US, UK, DE, FR, IN, JP; comma-separated)
+    #[arg(long, value_name = "COUNTRIES", value_delimiter = ',', env = "GITHUB_GRID_HOLIDAYS")]
+    holidays: Vec<String>,
+    
+    /// Custom holiday table, in the same format as the built-in ones
+    #[arg(long, value_name = "FILE", env = "GITHUB_GRID_HOLIDAYS_FILE")]
+    holidays_file: Option<PathBuf>,
+    
+    /// Share of the usual commits kept on the working day before a holiday (0-1)
+    #[arg(long, value_name = "SHARE", default_value_t = 0.5, env = "GITHUB_GRID_HOLIDAY_EVE_SHARE")]
+    holiday_eve_share: f64,
+    
+    /// Keep commits dated after now instead of clamping ranges to today
+    #[arg(long, env = "GITHUB_GRID_ALLOW_FUTURE")]
+    allow_future: bool,
+}
+
+#[derive(Subcommand, Clone)]
+enum Commands {
+    /// Show available patterns
+    Patterns,
+    /// Preview commits for date range
+    Preview {
+        #[arg(long)]
+        start: String,
+        #[arg(long)]
+        end: String,
+        #[arg(short, long, default_value = "realistic")]
+        pattern: String,
+        /// Also write an animated GIF of the grid filling in week by week
+        #[arg(long, value_name = "PATH")]
+        gif: Option<PathBuf>,
+        /// Replay the plan day by day in the terminal
+        #[arg(long)]
+        animate: bool,
+        /// Playback speed for --animate, in days per second
+        #[arg(long, value_name = "DAYS", default_value_t = 30.0, requires = "animate")]
+        speed: f64,
+    },
+    /// Generate many sample plans and report the spread of totals, active days and streaks
+    Simulate {
+        #[arg(short, long, default_value = "realistic")]
+        pattern: String,
+        /// Sample plans to generate
+        #[arg(long, default_value = "500")]
+        runs: usize,
+        /// Days per plan
+        #[arg(long, default_value = "365")]
+        days: i64,
+    },
+    /// Search pattern parameters that meet the given constraints and write a pattern file
+    Fit {
+        /// Commits per year
+        #[arg(long)]
+        total: Option<u32>,
+        /// Share of days with at least one commit, e.g. 75%
+        #[arg(long, value_name = "PERCENT", value_parser = patterns::parse_share)]
+        active_days: Option<f64>,
+        /// Fraction of commits on weekends, e.g. 0.12
+        #[arg(long, value_name = "RATIO", value_parser = patterns::parse_share)]
+        weekend_ratio: Option<f64>,
+        /// Longest run of consecutive active days allowed
+        #[arg(long, value_name = "DAYS")]
+        max_streak: Option<u32>,
+        /// Candidate configurations to try
+        #[arg(long, default_value = "300")]
+        candidates: usize,
+        /// Write the pattern here instead of stdout (use it with --pattern FILE)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Create only the commits owed since the last [AutoGen] commit, for unattended daily runs
+    CatchUp,
+    /// Push commits previously created with --no-push
+    Push,
+    /// Execute a `date,count` CSV from another system as a plan
+    Import {
+        /// CSV file with one `YYYY-MM-DD,count` row per day (header optional)
+        file: PathBuf,
+        /// Blend in this pattern's day-to-day variation for natural noise
+        #[arg(long, value_name = "PATTERN")]
+        blend: Option<String>,
+        /// How much of each day comes from the --blend pattern (0-1)
+        #[arg(long, default_value = "0.3", requires = "blend")]
+        noise: f64,
+    },
+    /// Recreate your per-day commit counts from private local repos (times only, no content)
+    Mirror {
+        /// Local repository to read your commits from (repeatable)
+        #[arg(long = "source", value_name = "PATH", required = true)]
+        sources: Vec<PathBuf>,
+        /// Author email that counts as yours (repeatable; defaults to the commit identity)
+        #[arg(long = "email")]
+        emails: Vec<String>,
+    },
+    /// Copy another repo's exact commit times into [AutoGen] commits, e.g. to restore a deleted or private repo's graph 1:1
+    Replay {
+        /// Repository whose history is replayed (every local branch)
+        #[arg(long, value_name = "PATH")]
+        from_repo: PathBuf,
+        /// Only replay commits by this author email (repeatable; defaults to every author)
+        #[arg(long = "email")]
+        emails: Vec<String>,
+        /// Skip commits before this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Skip commits after this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Write text or draw a preset shape onto the contribution graph
+    Art {
+        /// Text to draw: letters, digits, spaces and . ! ? - : '
+        #[arg(required_unless_present_any = ["shape", "invert"])]
+        text: Option<String>,
+        /// Draw a preset shape instead of text
+        #[arg(long, value_enum, conflicts_with = "text")]
+        shape: Option<art::Shape>,
+        /// Fill every day that's empty on your live graph, leaving real activity as the negative space
+        #[arg(long, conflicts_with_all = ["text", "shape"])]
+        invert: bool,
+        /// Pixel font
+        #[arg(long, value_enum, default_value = "5x7")]
+        font: art::Font,
+        /// Blank weeks between characters
+        #[arg(long, default_value_t = 1)]
+        spacing: usize,
+        /// Where the text sits across the year
+        #[arg(long, value_enum, default_value = "center")]
+        align: art::Align,
+        /// Week (0-52) the text starts at, instead of aligning it
+        #[arg(long, value_name = "WEEK", conflicts_with = "align")]
+        offset: Option<usize>,
+        /// Shade of the drawing, from 1 (lightest) to 4 (darkest)
+        #[arg(long, default_value_t = art::MAX_LEVEL, value_parser = clap::value_parser!(u8).range(1..=4))]
+        level: u8,
+        /// Commits on each day at the darkest shade
+        #[arg(long, value_name = "N", default_value_t = 12)]
+        commits: u32,
+    },
+    /// Remove exactly the commits a recorded run created and force-push
+    Rollback {
+        /// Run ID as shown by `history`
+        run_id: u32,
+    },
+    /// Check that a run's days show up on the live contribution graph
+    Verify {
+        /// Run ID to check (defaults to the latest run on this repo)
+        #[arg(long)]
+        run: Option<u32>,
+        /// Keep re-polling until the days appear or this much time passes (e.g. 10m)
+        #[arg(long, value_name = "DURATION", value_parser = dates::parse_wait)]
+        wait: Option<StdDuration>,
+    },
+    /// Compare the live graph with what the target or pattern expects by now, failing (exit 7) when it has fallen behind
+    Check {
+        /// How far behind the graph may be, as commits (25) or a share of the expected count (10%)
+        #[arg(long, value_name = "N|PERCENT", default_value = "10%", value_parser = verify::DriftThreshold::parse)]
+        threshold: verify::DriftThreshold,
+        /// First day to count (defaults to Jan 1), for grids started partway through the year
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+    },
+    /// Scan the branch history for tells of generated commits
+    Lint {
+        /// Offer to rewrite the offending commits (keeps a backup branch, force-pushes)
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Rewrite the author and committer identity on every [AutoGen] commit and force-push
+    Scrub {
+        /// Email to credit the commits to (must be verified on your GitHub account)
+        #[arg(long)]
+        new_email: String,
+        /// Author name to use (defaults to each commit's current name)
+        #[arg(long)]
+        new_name: Option<String>,
+    },
+    /// Compare two users' public contribution graphs side by side
+    CompareUsers {
+        /// GitHub logins to compare
+        #[arg(num_args = 2, value_names = ["USER_A", "USER_B"], required = true)]
+        users: Vec<String>,
+        /// Calendar year to compare (defaults to the last 365 days)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// List recorded runs, newest first
+    History {
+        /// Number of runs to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// List, add or remove the repos this tool manages
+    Repos {
+        #[command(subcommand)]
+        action: Option<ReposAction>,
+    },
+    /// Stay running and create today's commits as their scheduled times arrive
+    Daemon,
+    /// Manage a daily system timer (systemd/launchd) that keeps the grid up to date
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleAction,
+    },
+    /// Initialize or reset a private GitHub repo for commit patterns
+    Init {
+        /// Repository name (defaults to username-grid)
+        #[arg(short, long, env = "GITHUB_GRID_REPO_NAME")]
+        name: Option<String>,
+        /// Force recreate if repo exists
+        #[arg(long)]
+        force: bool,
+        /// Confirm deleting the existing repo without a prompt, by its full name (for scripts)
+        #[arg(long, value_name = "OWNER/NAME", requires = "force")]
+        confirm: Option<String>,
+        /// Rename and archive the existing repo instead of deleting it, keeping its history
+        #[arg(long, requires = "force", conflicts_with = "confirm")]
+        archive: bool,
+        /// Local directory to clone to (defaults to ~/github/repo-name)
+        #[arg(long, env = "GITHUB_GRID_LOCAL_DIR")]
+        local_dir: Option<String>,
+        /// Commit a GitHub Actions workflow that keeps the grid updated on a schedule
+        #[arg(long)]
+        with_action: bool,
+        /// Cron expression for the workflow schedule (UTC)
+        #[arg(long, default_value = "17 6 * * *", requires = "with_action")]
+        cron: String,
+        /// Scaffold a small project (sources, tests, CI, docs) so the repo looks like real work
+        #[arg(long, value_enum)]
+        skeleton: Option<skeleton::Template>,
+        /// Check out only the top-level files when cloning (for adopting big repos)
+        #[arg(long)]
+        sparse: bool,
+        /// Partial clone filter, e.g. blob:none to fetch file contents only when needed
+        #[arg(long, value_name = "SPEC")]
+        filter: Option<String>,
+        /// Clone with gh, the system git, or whichever works (auto)
+        #[arg(long, value_enum, default_value_t = CloneMethod::Auto, env = "GITHUB_GRID_CLONE_VIA")]
+        clone_via: CloneMethod,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum ReposAction {
+    /// Show each managed repo with its last run, coverage range and totals (default)
+    List,
+    /// Start managing an existing local repo
+    Add {
+        path: PathBuf,
+        /// Remote URL to show (defaults to the repo's origin)
+        #[arg(long)]
+        remote: Option<String>,
+    },
+    /// Stop managing a repo (the repo itself is left untouched)
+    Remove {
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum ScheduleAction {
+    /// Write and enable the timer, capturing the current repo/pattern/target options
+    Install {
+        /// Daily run time (HH:MM, local time)
+        #[arg(long, default_value = "09:00")]
+        at: String,
+    },
+    /// Disable and delete the timer
+    Remove,
+    /// Show whether the timer is installed and when it runs next
+    Status,
+}
+
+fn main() -> ExitCode {
+    match run_cli() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(GitHubGridError::NothingToDo(message)) => {
+            eprintln!("ℹ️  Nothing to do: {}", message);
+            ExitCode::from(exit_code::NOTHING_TO_DO)
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn run_cli() -> Result<()> {
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if cli.fleet.is_none() && matches!(cli.command, None | Some(Commands::CatchUp | Commands::Daemon | Commands::Push
+        | Commands::Lint { .. } | Commands::Scrub { .. } | Commands::Mirror { .. } | Commands::Replay { .. } | Commands::Import { .. }
+        | Commands::Art { .. }))
+    {
+        apply_repo_config(&mut cli, &matches)?;
+    }
+    
+    match cli.command.take() {
+        Some(Commands::Patterns) => {
+            show_patterns();
+            return Ok(());
+        }
+        Some(Commands::Preview { start, end, pattern, gif, animate, speed }) => {
+            let start_date = dates::parse_date(&start)?;
+            let end_date = dates::parse_date(&end)?;
+            preview_pattern(&pattern, start_date, end_date, gif.as_deref(), animate.then_some(speed))?;
+            return Ok(());
+        }
+        Some(Commands::Init { name, force, confirm, archive, local_dir, with_action, cron, skeleton, sparse, filter, clone_via }) => {
+            let force = match (force, archive) {
+                (false, _) => None,
+                (true, true) => Some(Recreate::Archive),
+                (true, false) => Some(Recreate::Delete { confirm }),
+            };
+            let clone = CloneOptions { sparse, filter, method: clone_via, proxy: cli.proxy.clone() };
+            let local_path = init_github_repo(&cli, connect(&cli)?.as_ref(), name, force, local_dir, &clone)?;
+            track_repo(Path::new(&local_path), "init");
+            if let Some(template) = skeleton {
+                add_skeleton(&cli, &local_path, template)?;
+            }
+            if with_action {
+                add_action_workflow(&cli, &local_path, &cron)?;
+            }
+            return Ok(());
+        }
+        Some(Commands::Simulate { pattern, runs, days }) => {
+            simulate_pattern(&pattern, runs, days)?;
+            return Ok(());
+        }
+        Some(Commands::Fit { total, active_days, weekend_ratio, max_streak, candidates, output }) => {
+            let constraints = Constraints { total, active_share: active_days, weekend_share: weekend_ratio, max_streak };
+            fit_pattern(&constraints, candidates, output.as_deref())?;
+            return Ok(());
+        }
+        Some(Commands::Schedule { action }) => {
+            match action {
+                ScheduleAction::Install { at } => {
+                    let at = schedule::ScheduleTime::parse(&at)?;
+                    schedule::install(&scheduled_run_args(&cli)?, &at)?;
+                }
+                ScheduleAction::Remove => schedule::remove()?,
+                ScheduleAction::Status => schedule::status()?,
+            }
+            return Ok(());
+        }
+        Some(Commands::CatchUp) => {
+            let mut reporter = ProgressReporter::new(cli.progress);
+            let result = run_catch_up(&cli, &mut reporter);
+            return finish_run(&cli, "catch-up", &reporter, result);
+        }
+        Some(Commands::Push) => {
+            let (repo_path, mut git_ops) = open_target_repo(&cli)?;
+            let _lock = lock_target_repo(&cli, &mut git_ops)?;
+            println!("🚀 Pushing {} to origin/{}...", repo_path.display(), cli.branch);
+            git_ops.push_commits()?;
+            println!("✅ Pushed");
+            return Ok(());
+        }
+        Some(Commands::Import { file, blend, noise }) => {
+            let mut reporter = ProgressReporter::new(cli.progress);
+            let result = run_import(&cli, &file, blend.as_deref(), noise, &mut reporter);
+            return finish_run(&cli, "import", &reporter, result);
+        }
+        Some(Commands::Art { text, shape, invert, font, spacing, align, offset, level, commits }) => {
+            let mut reporter = ProgressReporter::new(cli.progress);
+            let options = art::TextOptions { font, spacing, align, offset, level };
+            let result = run_art(&cli, |weeks| match (shape, &text) {
+                _ if invert => Ok(art::Canvas::filled(weeks, level)),
+                (Some(shape), _) => art::render_shape(shape, &options, weeks),
+                (None, text) => art::render_text(text.as_deref().unwrap_or_default(), &options, weeks),
+            }, invert, commits, &mut reporter);
+            return finish_run(&cli, "art", &reporter, result);
+        }
+        Some(Commands::Mirror { sources, emails }) => {
+            let mut reporter = ProgressReporter::new(cli.progress);
+            let result = run_mirror(&cli, &sources, emails, &mut reporter);
+            return finish_run(&cli, "mirror", &reporter, result);
+        }
+        Some(Commands::Replay { from_repo, emails, since, until }) => {
+            let mut reporter = ProgressReporter::new(cli.progress);
+            let result = run_replay(&cli, &from_repo, &emails, since.as_deref(), until.as_deref(), &mut reporter);
+            return finish_run(&cli, "replay", &reporter, result);
+        }
+        Some(Commands::Rollback { run_id }) => {
+            rollback_run(&cli, run_id)?;
+            return Ok(());
+        }
+        Some(Commands::Verify { run, wait }) => {
+            verify_run(&cli, run, wait.unwrap_or(StdDuration::ZERO))?;
+            return Ok(());
+        }
+        Some(Commands::Check { threshold, since }) => {
+            let since = since.as_deref().map(dates::parse_date).transpose()?;
+            check_drift(&cli, threshold, since)?;
+            return Ok(());
+        }
+        Some(Commands::Lint { fix }) => {
+            lint_history(&cli, fix)?;
+            return Ok(());
+        }
+        Some(Commands::Scrub { new_email, new_name }) => {
+            scrub_identity(&cli, &new_email, new_name.as_deref())?;
+            return Ok(());
+        }
+        Some(Commands::CompareUsers { users, year }) => {
+            compare_users(&cli, &users[0], &users[1], year)?;
+            return Ok(());
+        }
+        Some(Commands::History { limit }) => {
+            let repo = cli.repo.as_ref().map(|path| history::canonical_repo(&path.display().to_string()));
+            history::show(limit, repo.as_deref())?;
+            return Ok(());
+        }
+        Some(Commands::Repos { action }) => {
+            match action.unwrap_or(ReposAction::List) {
+                ReposAction::List => repos::show()?,
+                ReposAction::Add { path, remote } => {
+                    Repository::open(&path)?;
+                    if repos::register(&path, remote, "add")? {
+                        println!("✅ Now managing {}", path.display());
+                    } else {
+                        println!("ℹ️  {} is already managed", path.display());
+                    }
+                }
+                ReposAction::Remove { path } => {
+                    repos::unregister(&path)?;
+                    println!("✅ No longer managing {}", path.display());
+                }
+            }
+            return Ok(());
+        }
+        Some(Commands::Daemon) => {
+            let reporter = ProgressReporter::new(cli.progress);
+            let result = run_daemon(&cli, &reporter);
+            if let Err(e) = &result {
+                reporter.error(e);
+            }
+            return result;
+        }
+        None => {}
+    }
+    
+    if let Some(path) = cli.fleet.clone() {
+        return run_fleet(&cli, &matches, &path);
+    }
+    
+    let mut reporter = ProgressReporter::new(cli.progress);
+    let result = run(&cli, &mut reporter);
+    finish_run(&cli, "run", &reporter, result)
+}
+
+// Generates one plan from the command-line settings, counting existing
+// commits across the whole fleet, and shares each day's commits among the
+// repos by weight so several projects look active at once
+fn run_allocated_fleet(cli: &Cli, matches: &ArgMatches, fleet: &fleet::Fleet) -> Result<()> {
+    let mut repo_clis = Vec::new();
+    let mut repos = Vec::new();
+    let mut locks = Vec::new();
+    for entry in &fleet.repos {
+        let mut repo_cli = cli.clone();
+        repo_cli.fleet = None;
+        repo_cli.repo = Some(entry.path.clone());
+        // The shared plan comes from the command line, but each repo keeps
+        // its own branch and identity
+        apply_repo_config(&mut repo_cli, matches)?;
+        if let Some(branch) = &entry.branch {
+            repo_cli.branch = branch.clone();
+        }
+        let (_, mut git_ops) = open_target_repo(&repo_cli)?;
+        locks.push(prepare_target_repo(&repo_cli, &mut git_ops)?);
+        repos.push(git_ops);
+        repo_clis.push(repo_cli);
+    }
+    
+    let mut reporter = ProgressReporter::new(cli.progress);
+    let Some(plan) = plan_run(cli, &mut repos, &mut reporter)? else {
+        return Ok(());
+    };
+    let total = plan.commits.len();
+    let (allocations, dropped) = fleet.allocate(plan.commits);
+    
+    reporter.info(&format!("\n🚢 Allocation across {} repos:", fleet.repos.len()));
+    for (entry, commits) in fleet.repos.iter().zip(&allocations) {
+        reporter.info(&format!("   {:<40}  {:>6} commits ({:.0}%)",
+            entry.path.display(), commits.len(), commits.len() as f64 / total.max(1) as f64 * 100.0));
+    }
+    if dropped > 0 {
+        reporter.warn(&format!("{} commits dropped: every repo was at its max_per_day", dropped));
+    }
+    
+    if cli.dry_run {
+        return Ok(());
+    }
+    if !cli.yes {
+        eprintln!();
+        eprintln!("📋 Plan: {} commits over {} days across {} repos", total - dropped, plan.days_in_range, fleet.repos.len());
+        if !confirm_prompt("Proceed?")? {
+            reporter.info("❎ Aborted, no commits were created");
+            return Ok(());
+        }
+    }
+    
+    let mut outcomes = Vec::new();
+    for ((mut git_ops, repo_cli), commits) in repos.into_iter().zip(&repo_clis).zip(allocations) {
+        let mut reporter = ProgressReporter::new(repo_cli.progress);
+        let path = repo_cli.repo.as_ref().unwrap().display().to_string();
+        reporter.target(&path);
+        reporter.pattern(&cli.target_total.map_or(cli.pattern.clone(), |total| format!("target-{}", total)));
+        let result = git_ops.branch_head().and_then(|head| {
+            reporter.base_commit(head.map(|oid| oid.to_string()));
+            if commits.is_empty() {
+                return Ok(());
+            }
+            reporter.generated(&commits, commits[0].date.date_naive(), commits[commits.len() - 1].date.date_naive());
+            execute_commits(&mut git_ops, commits, repo_cli, &mut reporter)
+        });
+        let result = record_run(repo_cli, "fleet", &reporter, result);
+        outcomes.push((reporter.stats(), result.err()));
+    }
+    drop(locks);
+    
+    report_fleet(cli, outcomes)
+}
+
+// Runs every repo in the fleet file with its own settings, carrying on past
+// failures, then prints one combined report
+fn run_fleet(cli: &Cli, matches: &ArgMatches, path: &Path) -> Result<()> {
+    let fleet = fleet::Fleet::load(path)?;
+    for entry in &fleet.repos {
+        track_repo(&entry.path, "fleet");
+    }
+    if fleet.allocate {
+        return run_allocated_fleet(cli, matches, &fleet);
+    }
+    let total = fleet.repos.len();
+    let mut outcomes = Vec::new();
+    
+    for (index, entry) in fleet.repos.iter().enumerate() {
+        let mut repo_cli = cli.clone();
+        repo_cli.fleet = None;
+        repo_cli.repo = Some(entry.path.clone());
+        
+        let mut reporter = ProgressReporter::new(repo_cli.progress);
+        reporter.info(&format!("\n🚢 [{}/{}] {}", index + 1, total, entry.path.display()));
+        reporter.target(&entry.path.display().to_string());
+        let result = apply_repo_config(&mut repo_cli, matches).and_then(|()| {
+            if let Some(pattern) = &entry.pattern {
+                repo_cli.pattern = pattern.clone();
+            }
+            if let Some(target_total) = entry.target_total {
+                repo_cli.target_total = Some(target_total);
+            }
+            if let Some(branch) = &entry.branch {
+                repo_cli.branch = branch.clone();
+            }
+            run(&repo_cli, &mut reporter)
+        });
+        
+        let result = record_run(&repo_cli, "fleet", &reporter, result);
+        outcomes.push((reporter.stats(), result.err()));
+    }
+    
+    report_fleet(cli, outcomes)
+}
+
+// Combined table for a fleet run; fails if any repo failed
+fn report_fleet(cli: &Cli, outcomes: Vec<(progress::RunStats, Option<GitHubGridError>)>) -> Result<()> {
+    let total = outcomes.len();
+    let reporter = ProgressReporter::new(cli.progress);
+    reporter.info("\n🚢 Fleet summary:");
+    reporter.info(&format!("   {:<40}  {:<7}  {:>7}  {:>7}  {:>6}", "Repo", "Status", "Planned", "Created", "Pushed"));
+    let mut combined = progress::RunStats { repo: Some(format!("fleet of {} repos", total)), ..Default::default() };
+    for (stats, error) in &outcomes {
+        reporter.info(&format!("   {:<40}  {:<7}  {:>7}  {:>7}  {:>6}",
+            stats.repo.as_deref().unwrap_or("-"),
+            if error.is_some() { "failed" } else { "ok" },
+            stats.planned,
+            stats.created,
+            stats.pushed,
+        ));
+        combined.planned += stats.planned;
+        combined.created += stats.created;
+        combined.pushed += stats.pushed;
+    }
+    let failures: Vec<String> = outcomes.iter()
+        .filter_map(|(stats, error)| error.as_ref().map(|e| format!("{}: {}", stats.repo.as_deref().unwrap_or("-"), e)))
+        .collect();
+    for failure in &failures {
+        reporter.info(&format!("   ❌ {}", failure));
+    }
+    
+    let result = if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(GitHubGridError::Repository(format!("{} of {} fleet repos failed", failures.len(), total)))
+    };
+    notify_run(cli, "fleet", combined, &result);
+    result
+}
+
+// The built-in commit messages, or a --messages file, with the run's
+// category weights and message style
+fn message_bank(cli: &Cli) -> Result<MessageBank> {
+    let mut bank = match &cli.messages {
+        Some(path) => MessageBank::load(path)?,
+        None => MessageBank::builtin(),
+    };
+    for spec in &cli.message_weights {
+        let (name, weight) = spec.split_once('=')
+            .ok_or_else(|| GitHubGridError::Parse(format!("Invalid message weight '{}', expected NAME=WEIGHT", spec)))?;
+        let weight: f64 = weight.trim().parse()
+            .map_err(|_| GitHubGridError::Parse(format!("Invalid weight in '{}'", spec)))?;
+        bank.set_weight(name.trim(), weight)?;
+    }
+    if let (MessageStyle::Ticket, Some(prefix)) = (cli.message_style, &cli.ticket_prefix) {
+        bank = bank.with_ticket_prefix(prefix);
+    }
+    if let Some(share) = cli.issue_refs {
+        bank = bank.with_issue_refs(share)?;
+    }
+    Ok(bank)
+}
+
+// Reports the run's outcome to the progress stream and any configured
+// notification targets, passing the result through unchanged
+fn finish_run(cli: &Cli, command: &str, reporter: &ProgressReporter, result: Result<()>) -> Result<()> {
+    let stats = reporter.stats();
+    // Only a failure that left commits unpublished calls for `push`
+    let result = result.map_err(|e| match stats.pushed {
+        pushed if pushed > 0 && pushed < stats.created => GitHubGridError::PartialPush { pushed, source: Box::new(e) },
+        _ => e,
+    });
+    let result = record_run(cli, command, reporter, result);
+    if let Some(path) = &cli.report {
+        write_run_report(cli, command, reporter, &result, path);
+    }
+    notify_run(cli, command, reporter.stats(), &result);
+    if cli.detailed_exit_codes && result.is_ok() && !cli.dry_run && stats.created == 0 {
+        return Err(GitHubGridError::NothingToDo("no commits were created".to_string()));
+    }
+    result
+}
+
+// Reports a failure to the progress stream and records the run in history
+fn record_run(cli: &Cli, command: &str, reporter: &ProgressReporter, result: Result<()>) -> Result<()> {
+    if let Err(e) = &result {
+        reporter.error(e);
+    }
+    
+    let error = result.as_ref().err().map(|e| e.to_string());
+    let record = history::RunRecord::new(command, &cli.branch, reporter.stats(), cli.dry_run, error);
+    match history::record(record) {
+        Ok(id) => reporter.info(&format!("🗂️  Recorded as run #{}", id)),
+        Err(e) => eprintln!("⚠️  Could not record run history: {}", e),
+    }
+    result
+}
+
+// Like notifications, a report that can't be written is only a warning
+fn write_run_report(cli: &Cli, command: &str, reporter: &ProgressReporter, result: &Result<()>, path: &Path) {
+    let stats = reporter.stats();
+    let error = result.as_ref().err().map(|e| e.to_string());
+    let report = run_report(cli, command, &stats, error);
+    match fs::write(path, report.render(run_report::ReportFormat::for_path(path))) {
+        Ok(()) => reporter.info(&format!("📝 Wrote the run report to {}", path.display())),
+        Err(e) => eprintln!("⚠️  Could not write the run report to {}: {}", path.display(), e),
+    }
+}
+
+fn run_report<'a>(cli: &Cli, command: &'a str, stats: &'a progress::RunStats, error: Option<String>) -> run_report::RunReport<'a> {
+    run_report::RunReport {
+        command,
+        args: redacted_args(env::args()),
+        finished: Local::now(),
+        stats,
+        dry_run: cli.dry_run,
+        error,
+    }
+}
+
+// Options whose values are credentials: a webhook URL's path is its
+// secret, and a proxy URL may carry user:password
+const SECRET_OPTIONS: [&str; 4] = ["--webhook", "--slack-webhook", "--discord-webhook", "--proxy"];
+
+// The command line with secret option values masked, for reports
+fn redacted_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut redacted = Vec::new();
+    let mut mask_next = false;
+    for arg in args {
+        if mask_next {
+            redacted.push("<redacted>".to_string());
+            mask_next = false;
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((name, _)) if SECRET_OPTIONS.contains(&name) => redacted.push(format!("{}=<redacted>", name)),
+            _ => {
+                mask_next = SECRET_OPTIONS.contains(&arg.as_str());
+                redacted.push(arg);
+            }
+        }
+    }
+    redacted
+}
+
+fn notify_run(cli: &Cli, command: &str, stats: progress::RunStats, result: &Result<()>) {
+    let options = NotifyOptions {
+        desktop: cli.notify_desktop,
+        webhooks: cli.webhooks.clone(),
+        slack: cli.slack_webhooks.clone(),
+        discord: cli.discord_webhooks.clone(),
+    };
+    if options.is_empty() && !cli.email {
+        return;
+    }
+    let summary = RunSummary {
+        command,
+        stats,
+        dry_run: cli.dry_run,
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    notify::send(&options, &summary);
+    if cli.email {
+        email_run_report(cli, &summary);
+    }
+}
+
+// The markdown run report as a plain-text email, for catch-up jobs on
+// machines nobody watches
+fn email_run_report(cli: &Cli, summary: &RunSummary) {
+    let report = run_report(cli, summary.command, &summary.stats, summary.error.clone());
+    let sent = email::SmtpConfig::load()
+        .and_then(|smtp| smtp.send(&summary.headline(), &report.render(run_report::ReportFormat::Markdown)));
+    if let Err(e) = sent {
+        eprintln!("⚠️  Email summary failed: {}", e);
+    }
+}
+
+// Best effort: a broken repos file shouldn't fail the run that found the repo
+fn track_repo(path: &Path, source: &str) {
+    if let Err(e) = repos::register(path, None, source) {
+        eprintln!("⚠️  Could not record {} as a managed repo: {}", path.display(), e);
+    }
+}
+
+fn open_target_repo(cli: &Cli) -> Result<(PathBuf, GitOperations)> {
+    let repo_path = target_repo_path(cli)?;
+    let repo = Repository::open(&repo_path)?;
+    let git_ops = GitOperations::new(repo)
+        .with_branch(&cli.branch)
+        .with_signoff(cli.signoff)
+        .with_hooks(cli.hooks == HooksMode::Run)
+        .with_proxy(cli.proxy.clone())
+        .with_push_token(push_token(cli))
+        .with_identity(cli.author_name.clone(), cli.author_email.clone())
+        .with_content(commit_content(cli)?)
+        .with_messages(message_bank(cli)?);
+    Ok((repo_path, git_ops))
+}
+
+fn commit_content(cli: &Cli) -> Result<CommitContent> {
+    Ok(match (&cli.languages, cli.content) {
+        (Some(spec), _) => CommitContent::Languages(LanguageMix::parse(spec)?),
+        (None, ContentMode::Log) => CommitContent::Log,
+        (None, ContentMode::Empty) => CommitContent::Empty,
+    })
+}
+
+// Fills options left at their defaults from the target repo's
+// .github-grid.toml; flags and environment variables still take precedence
+fn apply_repo_config(cli: &mut Cli, matches: &ArgMatches) -> Result<()> {
+    // An unresolvable repo is reported by whatever opens it next
+    let Ok(repo_path) = target_repo_path(cli) else {
+        return Ok(());
+    };
+    let Some(config) = RepoConfig::load(&repo_path)? else {
+        return Ok(());
+    };
+    let unset = |id: &str| matches!(matches.value_source(id), None | Some(ValueSource::DefaultValue));
+    
+    if let (Some(pattern), true) = (&config.pattern, unset("pattern")) {
+        cli.pattern = pattern.clone();
+    }
+    if let (Some(branch), true) = (&config.branch, unset("branch")) {
+        cli.branch = branch.clone();
+    }
+    if let (Some(target_total), true) = (config.target_total, unset("target_total")) {
+        cli.target_total = Some(target_total);
+    }
+    if !config.year_targets.is_empty() && unset("year_targets") {
+        cli.year_targets = config.year_target_args();
+    }
+    if let (Some(name), true) = (&config.identity.name, unset("author_name")) {
+        cli.author_name = Some(name.clone());
+    }
+    if let (Some(email), true) = (&config.identity.email, unset("author_email")) {
+        cli.author_email = Some(email.clone());
+    }
+    if !config.holidays.is_empty() && unset("holidays") {
+        cli.range.holidays = config.holidays.clone();
+    }
+    if let (Some(file), true) = (&config.holidays_file, unset("holidays_file")) {
+        // Relative to the repo, so the config travels with it
+        cli.range.holidays_file = Some(repo_path.join(file));
+    }
+    if let (Some(share), true) = (config.holiday_eve_share, unset("holiday_eve_share")) {
+        cli.range.holiday_eve_share = share;
+    }
+    
+    if cli.progress != ProgressMode::Json {
+        println!("⚙️  Using settings from {}", repo_path.join(repo_config::REPO_CONFIG_FILE).display());
+    }
+    Ok(())
+}
+
+// The GitHub API, or with --offline a mock forge whose remotes are bare
+// repos in the config directory
+fn connect(cli: &Cli) -> Result<Box<dyn Forge>> {
+    if cli.offline {
+        let username = github::cached_username().unwrap_or_else(|| "offline".to_string());
+        return Ok(Box::new(MockForge::new(history::config_dir()?.join("mock-forge"), &username)));
+    }
+    if let Some(token) = PROMPTED_TOKEN.get() {
+        return Ok(Box::new(GitHubClient::with_token(token.clone())?));
+    }
+    if let Some(app) = github_app(cli) {
+        let token = app_token(&app)?;
+        return Ok(Box::new(GitHubClient::with_installation(token, &app.login)?));
+    }
+    match GitHubClient::new(cli.token_source) {
+        // With nothing to authenticate with, ask rather than fail, as git does
+        Err(GitHubGridError::Authentication(message)) if github::gh_installed() && can_prompt() => {
+            eprintln!("🔑 {}", message);
+            Ok(Box::new(prompt_for_token()?))
+        }
+        client => Ok(Box::new(client?)),
+    }
+}
+
+// A token typed in at a prompt, reused for the rest of the run
+static PROMPTED_TOKEN: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+// Installation token for --token-source app, minted once per run and used
+// for both API calls and pushes
+static APP_TOKEN: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+// The app behind --token-source app, unless the run is offline
+fn github_app(cli: &Cli) -> Option<github::GitHubApp> {
+    if cli.token_source != TokenSource::App || cli.offline {
+        return None;
+    }
+    Some(github::GitHubApp {
+        app_id: cli.app_id.clone().expect("--app-id is required with --token-source app"),
+        private_key: cli.app_key.clone().expect("--app-key is required with --token-source app"),
+        login: cli.app_login.clone().expect("--app-login is required with --token-source app"),
+        installation: cli.app_installation,
+    })
+}
+
+fn app_token(app: &github::GitHubApp) -> Result<String> {
+    if let Some(token) = APP_TOKEN.get() {
+        return Ok(token.clone());
+    }
+    let token = app.installation_token()?;
+    Ok(APP_TOKEN.get_or_init(|| token).clone())
+}
+
+// What git authenticates fetches and pushes with when the run has its own
+// token: the app's, minted once one of them actually runs, or one typed
+// in at a prompt by then
+fn push_token(cli: &Cli) -> Option<git_ops::PushToken> {
+    let app = github_app(cli);
+    let from_env = cli.token_source == TokenSource::Env && !cli.offline;
+    Some(Box::new(move || match &app {
+        Some(app) => app_token(app).map(Some),
+        None if from_env => Ok(env::var("GH_TOKEN").or_else(|_| env::var("GITHUB_TOKEN")).ok()),
+        None => Ok(PROMPTED_TOKEN.get().cloned()),
+    }))
+}
+
+fn can_prompt() -> bool {
+    io::stdin().is_terminal() || ["GIT_ASKPASS", "SSH_ASKPASS"].iter().any(|var| env::var(var).is_ok_and(|v| !v.is_empty()))
+}
+
+// Asks for a GitHub username and personal access token (through the
+// askpass program when there is one), checks the token, and offers to keep
+// it in git's credential helper for `--token-source keychain`
+fn prompt_for_token() -> Result<GitHubClient> {
+    let username = match github::askpass("Username for 'https://github.com': ") {
+        Some(username) => username,
+        None => {
+            eprint!("GitHub username: ");
+            io::stderr().flush()?;
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer)?;
+            answer.trim().to_string()
+        }
+    };
+    let prompt = format!("Personal access token for '{}' (repo and read:user scopes): ", username);
+    let token = match github::askpass(&prompt) {
+        Some(token) => token,
+        None => read_hidden(&prompt)?,
+    };
+    if username.is_empty() || token.is_empty() {
+        return Err(GitHubGridError::Authentication("No GitHub credentials entered".to_string()));
+    }
+    let client = GitHubClient::with_token(token.clone())?;
+    eprintln!("✅ Authenticated as {}", client.username());
+    let _ = PROMPTED_TOKEN.set(token.clone());
+    
+    if io::stdin().is_terminal() && confirm_prompt("Save the token in git's credential helper for next time?")? {
+        match github::store_keychain_token(&username, &token) {
+            Ok(()) => eprintln!("🔐 Saved; pass --token-source keychain (or set GITHUB_GRID_TOKEN_SOURCE=keychain) to use it"),
+            Err(e) => eprintln!("⚠️  Could not save the token: {}", e),
+        }
+    }
+    Ok(client)
+}
+
+// Reads a line from the terminal without echoing it
+fn read_hidden(prompt: &str) -> Result<String> {
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use ratatui::crossterm::terminal;
+    
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    terminal::enable_raw_mode()?;
+    let mut value = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(value),
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(GitHubGridError::Authentication("Cancelled".to_string()));
+                }
+                KeyCode::Char(c) => value.push(c),
+                KeyCode::Esc => break Err(GitHubGridError::Authentication("Cancelled".to_string())),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result
+}
+
+fn target_repo_path(cli: &Cli) -> Result<PathBuf> {
+    // Use default path if not specified
+    let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let repo_path = match &cli.repo {
+        Some(path) => path.clone(),
+        None => {
+            // Get username for the default path, from gh's local config when
+            // possible so offline runs don't need the API
+            let username = match github::cached_username() {
+                Some(username) => username,
+                None => connect(cli)?.username().to_string(),
+            };
+            PathBuf::from(format!("{}/github/{}-grid", home_dir, username))
+        }
+    };
+    Ok(repo_path)
+}
+
+// Arguments the scheduled job runs with: the current target options
+// followed by the catch-up subcommand
+fn scheduled_run_args(cli: &Cli) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    
+    if let Some(repo) = &cli.repo {
+        args.push("--repo".to_string());
+        args.push(fs::canonicalize(repo)?.display().to_string());
+    }
+    args.extend(pattern_args(cli));
+    if let Some(messages) = &cli.messages {
+        args.push("--messages".to_string());
+        args.push(fs::canonicalize(messages)?.display().to_string());
+    }
+    for weight in &cli.message_weights {
+        args.push("--message-weight".to_string());
+        args.push(weight.clone());
+    }
+    if let (MessageStyle::Ticket, Some(prefix)) = (cli.message_style, &cli.ticket_prefix) {
+        args.extend(["--message-style".to_string(), "ticket".to_string(), "--ticket-prefix".to_string(), prefix.clone()]);
+    }
+    if let Some(share) = cli.issue_refs {
+        args.extend(["--issue-refs".to_string(), share.to_string()]);
+    }
+    if let Some(languages) = &cli.languages {
+        args.extend(["--languages".to_string(), languages.clone()]);
+    }
+    if let Some(itinerary) = &cli.itinerary {
+        args.push("--itinerary".to_string());
+        args.push(fs::canonicalize(itinerary)?.display().to_string());
+    }
+    if let Some(coding_time) = &cli.coding_time {
+        args.push("--coding-time".to_string());
+        args.push(fs::canonicalize(coding_time)?.display().to_string());
+    }
+    if let Some(calendar) = &cli.range.calendar {
+        args.push("--calendar".to_string());
+        args.push(fs::canonicalize(calendar)?.display().to_string());
+        args.push("--calendar-keywords".to_string());
+        args.push(cli.range.calendar_keywords.join(","));
+    }
+    if !cli.range.holidays.is_empty() {
+        args.push("--holidays".to_string());
+        args.push(cli.range.holidays.join(","));
+    }
+    if let Some(holidays_file) = &cli.range.holidays_file {
+        args.push("--holidays-file".to_string());
+        args.push(fs::canonicalize(holidays_file)?.display().to_string());
+    }
+    if !cli.range.holidays.is_empty() || cli.range.holidays_file.is_some() {
+        args.push("--holiday-eve-share".to_string());
+        args.push(cli.range.holiday_eve_share.to_string());
+    }
+    
+    if cli.token_source != TokenSource::Gh {
+        args.push("--token-source".to_string());
+        args.push(cli.token_source.to_possible_value().unwrap().get_name().to_string());
+    }
+    if let (TokenSource::App, Some(id), Some(key), Some(login)) = (cli.token_source, &cli.app_id, &cli.app_key, &cli.app_login) {
+        args.extend(["--app-id".to_string(), id.clone()]);
+        args.extend(["--app-key".to_string(), fs::canonicalize(key)?.display().to_string()]);
+        args.extend(["--app-login".to_string(), login.clone()]);
+        if let Some(installation) = cli.app_installation {
+            args.extend(["--app-installation".to_string(), installation.to_string()]);
+        }
+    }
+    if cli.notify_desktop {
+        args.push("--notify-desktop".to_string());
+    }
+    for url in &cli.webhooks {
+        args.push("--webhook".to_string());
+        args.push(url.clone());
+    }
+    if cli.email {
+        args.push("--email".to_string());
+    }
+    for url in &cli.slack_webhooks {
+        args.push("--slack-webhook".to_string());
+        args.push(url.clone());
+    }
+    for url in &cli.discord_webhooks {
+        args.push("--discord-webhook".to_string());
+        args.push(url.clone());
+    }
+    args.push("catch-up".to_string());
+    
+    Ok(args)
+}
+
+// Branch and pattern/target options shared by scheduled and workflow runs
+fn pattern_args(cli: &Cli) -> Vec<String> {
+    let mut args = vec!["--branch".to_string(), cli.branch.clone()];
+    
+    match cli.target_total {
+        Some(target_total) => {
+            args.push("--target-total".to_string());
+            args.push(target_total.to_string());
+            for year_target in &cli.year_targets {
+                args.push("--year-target".to_string());
+                args.push(year_target.clone());
+            }
+            args.push("--target-scope".to_string());
+            args.push(cli.target_scope.to_possible_value().unwrap().get_name().to_string());
+        }
+        None => {
+            args.push("--pattern".to_string());
+            args.push(cli.pattern.clone());
+            if let Some(day_off) = cli.day_off {
+                args.extend(["--day-off".to_string(), day_off.to_string()]);
+            }
+            if !cli.crunch.is_empty() {
+                args.extend(["--crunch".to_string(), cli.crunch.join(",")]);
+            }
+            if let Some(multiplier) = cli.quarter_end {
+                args.extend(["--quarter-end".to_string(), multiplier.to_string()]);
+                args.extend(["--quarter-end-weeks".to_string(), cli.quarter_end_weeks.to_string()]);
+            }
+            if let Some(per_year) = cli.hackathons {
+                args.extend(["--hackathons".to_string(), per_year.to_string()]);
+            }
+            if cli.organic {
+                args.extend(["--organic".to_string(), "--organic-period".to_string(), cli.organic_period.to_string()]);
+                // Pinned at install so every scheduled run continues the same stretches
+                let seed = cli.organic_seed.unwrap_or_else(|| rand::rng().random());
+                args.extend(["--organic-seed".to_string(), seed.to_string()]);
+            }
+        }
+    }
+    if let Some(ratio) = cli.weekend_ratio {
+        args.extend(["--weekend-ratio".to_string(), ratio.to_string()]);
+    }
+    if let Some(share) = cli.active_days {
+        args.extend(["--active-days".to_string(), format!("{}%", share * 100.0)]);
+    }
+    if let Some(min) = cli.min_per_day {
+        args.extend(["--min-per-day".to_string(), min.to_string()]);
+    }
+    if let Some(max) = cli.max_per_day {
+        args.extend(["--max-per-day".to_string(), max.to_string()]);
+    }
+    if cli.signoff {
+        args.push("--signoff".to_string());
+    }
+    if cli.hooks != HooksMode::Skip {
+        args.extend(["--hooks".to_string(), cli.hooks.to_possible_value().unwrap().get_name().to_string()]);
+    }
+    if let Some(proxy) = &cli.proxy {
+        args.extend(["--proxy".to_string(), proxy.clone()]);
+    }
+    if cli.fill_inactive {
+        args.push("--fill-inactive".to_string());
+    }
+    if cli.readme_stats {
+        args.push("--readme-stats".to_string());
+    }
+    if cli.content != ContentMode::Empty {
+        args.push("--content".to_string());
+        args.push(cli.content.to_possible_value().unwrap().get_name().to_string());
+    }
+    
+    args
+}
+
+fn add_action_workflow(cli: &Cli, local_path: &str, cron: &str) -> Result<()> {
+    let mut git_ops = GitOperations::new(Repository::open(local_path)?)
+        .with_branch(&cli.branch)
+        .with_proxy(cli.proxy.clone())
+        .with_push_token(push_token(cli));
+    let (name, email) = git_ops::author_identity()?;
+    
+    let mut args = vec!["--repo".to_string(), ".".to_string()];
+    args.extend(pattern_args(cli));
+    args.extend(["--token-source".to_string(), "env".to_string(), "catch-up".to_string()]);
+    
+    let workflow_path = Path::new(actions::WORKFLOW_PATH);
+    let full_path = Path::new(local_path).join(workflow_path);
+    fs::create_dir_all(full_path.parent().unwrap())?;
+    fs::write(&full_path, actions::workflow_yaml(cron, &name, &email, &args))?;
+    
+    git_ops.commit_paths(&[workflow_path], "Add scheduled github-grid workflow")?;
+    println!("🚀 Pushing workflow to GitHub...");
+    git_ops.push_commits()?;
+    
+    println!("⚙️  Workflow committed: {} (cron: {})", actions::WORKFLOW_PATH, cron);
+    println!("💡 Optionally add a GRID_TOKEN repo secret to push with a personal token instead of GITHUB_TOKEN");
+    Ok(())
+}
+
+// Writes the template's files that don't exist yet (the stock README counts
+// as missing) and commits them in one go
+fn add_skeleton(cli: &Cli, local_path: &str, template: skeleton::Template) -> Result<()> {
+    let mut git_ops = GitOperations::new(Repository::open(local_path)?)
+        .with_branch(&cli.branch)
+        .with_proxy(cli.proxy.clone())
+        .with_push_token(push_token(cli));
+    let repo_name = Path::new(local_path).file_name().map_or("project".to_string(), |n| n.to_string_lossy().to_string());
+    
+    let mut written = Vec::new();
+    for (path, content) in skeleton::files(template, &repo_name) {
+        let full_path = Path::new(local_path).join(&path);
+        let replaceable = fs::read_to_string(&full_path).map_or(true, |existing| existing == GRID_README);
+        if !replaceable {
+            continue;
+        }
+        fs::create_dir_all(full_path.parent().unwrap())?;
+        fs::write(&full_path, content)?;
+        written.push(PathBuf::from(path));
+    }
+    if written.is_empty() {
+        println!("🧱 Project skeleton already in place");
+        return Ok(());
+    }
+    
+    let paths: Vec<&Path> = written.iter().map(PathBuf::as_path).collect();
+    git_ops.commit_paths(&paths, "Set up project structure")?;
+    println!("🚀 Pushing project skeleton to GitHub...");
+    git_ops.push_commits()?;
+    println!("🧱 Added {} skeleton files", written.len());
+    Ok(())
+}
+
+// Pattern paced for the rest of the year: target mode calibrates the daily
+// rate from `from` through Dec 31 so partial-day runs don't front-load
+// commits. None once the year's target is already met.
+fn pattern_for_remaining_year(
+    cli: &Cli,
+    git_ops: &GitOperations,
+    from: NaiveDate,
+    reporter: &ProgressReporter,
+) -> Result<Option<Box<dyn Pattern>>> {
+    match target_for_year(cli, from.year())? {
+        Some(target_total) => {
+            let (_, year_end) = dates::year_range(from.year())?;
+            let existing = git_ops.count_commits_in_year(from.year())?;
+            let commits_needed = target_total.saturating_sub(counted_toward_target(&existing, cli.target_scope));
+            let days_left = (year_end - from).num_days() + 1;
+            reporter.info(&format!("🎯 Target: {} commits, {} still needed over {} days", target_total, commits_needed, days_left));
+            // Calibrating for zero still yields a light pattern
+            if commits_needed == 0 {
+                return Ok(None);
+            }
+            let calibration = calibrate::calibrate(commits_needed, &[(from, year_end)], cli.tolerance / 100.0);
+            report_calibration(&calibration, reporter);
+            Ok(Some(Box::new(ConfigurablePattern::new(calibration.config))))
+        }
+        None => run_pattern(cli, reporter).map(Some),
+    }
+}
+
+// Readies the repo for a run that creates commits: applies --hooks, then
+// locks and syncs it
+fn prepare_target_repo(cli: &Cli, git_ops: &mut GitOperations) -> Result<Option<RunLock>> {
+    if cli.dry_run {
+        return Ok(None);
+    }
+    let repo = git_ops.repo();
+    let hooks = git_ops::installed_hooks(repo);
+    let repo_path = repo.workdir().unwrap_or(repo.path());
+    if !hooks.is_empty() {
+        match cli.hooks {
+            HooksMode::Skip => eprintln!(
+                "⚠️  {} has commit hooks ({}) that generated commits skip; pass --hooks run to run them or --hooks refuse to stop",
+                repo_path.display(), hooks.join(", ")
+            ),
+            HooksMode::Refuse => {
+                return Err(GitHubGridError::Config(format!(
+                    "{} has commit hooks ({}) and --hooks refuse is set", repo_path.display(), hooks.join(", ")
+                )));
+            }
+            HooksMode::Run => {}
+        }
+    }
+    lock_target_repo(cli, git_ops).map(Some)
+}
+
+// Locks the repo for the run, then fetches origin and fast-forwards the
+// branch so the range picks up after commits pushed from other machines
+fn lock_target_repo(cli: &Cli, git_ops: &mut GitOperations) -> Result<RunLock> {
+    let lock = RunLock::acquire(git_ops.repo().path(), cli.force_unlock)?;
+    if cli.no_fetch {
+        return Ok(lock);
+    }
+    match git_ops.fetch_and_fast_forward() {
+        Ok(RemoteSync::FastForwarded(count)) => {
+            eprintln!("⬇️  Fast-forwarded {} by {} commit(s) from origin", cli.branch, count);
+        }
+        Ok(RemoteSync::Diverged { ahead, behind }) => {
+            return Err(GitHubGridError::Repository(format!(
+                "{} has diverged from origin ({} local and {} remote commit(s)); rebase or reset it first, or pass --no-fetch",
+                cli.branch, ahead, behind
+            )));
+        }
+        Ok(_) => {}
+        // Offline runs can still commit locally
+        Err(e) => eprintln!("⚠️  Could not fetch origin, continuing with the local branch: {}", e),
+    }
+    Ok(lock)
+}
+
+fn run_catch_up(cli: &Cli, reporter: &mut ProgressReporter) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    reporter.pattern(&cli.target_total.map_or(cli.pattern.clone(), |total| format!("target-{}", total)));
+    let today = Local::now().date_naive();
+    
+    // Without prior autogen history, start from today rather than backfilling a year
+    let start_date = match git_ops.get_latest_autogen_commit()? {
+        Some(last_commit) => last_commit.date_naive() + chrono::Duration::days(1),
+        None => today,
+    };
+    
+    if start_date > today {
+        reporter.info("✅ Already caught up, nothing to do");
+        return Ok(());
+    }
+    
+    reporter.start_generation();
+    let Some(pattern) = pattern_for_remaining_year(cli, &git_ops, start_date, reporter)? else {
+        reporter.info("✅ Target reached, nothing to do");
+        return Ok(());
+    };
+    let commits = pattern.generate(start_date, today);
+    let days_off = days_off(cli, reporter)?;
+    let commits = apply_exclusions(commits, &days_off);
+    let commits = thin_holiday_eves(cli, commits, &days_off);
+    let mut commits = apply_itinerary(cli, commits, reporter)?;
+    let public_days = public_active_days(cli, std::slice::from_ref(&git_ops), start_date, today, reporter)?;
+    commits.retain(|c| !public_days.contains(&c.date.date_naive()));
+    let mut days = plan_days(&[(start_date, today)], &days_off);
+    days.retain(|day| !public_days.contains(day));
+    let mut commits = apply_coding_time(cli, commits, &days, reporter)?;
+    let now = (!cli.range.allow_future).then(Local::now);
+    if let Some(now) = now {
+        commits.retain(|c| c.date <= now);
+    }
+    let commits = apply_weekend_ratio(cli, commits, &days, now, reporter)?;
+    let commits = apply_active_days(cli, commits, &days, now, reporter)?;
+    let commits = apply_day_caps(cli, commits, &days, &BTreeMap::new(), now, reporter)?;
+    
+    reporter.info(&format!("Catching up {} to {}: {} commits", start_date, today, commits.len()));
+    reporter.generated(&commits, start_date, today);
+    
+    if cli.dry_run {
+        if !reporter.is_json() {
+            show_commit_summary(&commits);
+        }
+        return Ok(());
+    }
+    
+    if commits.is_empty() {
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, commits, cli, reporter)
+}
+
+// Creates the CSV's per-day counts, minus generated commits already on
+// those days so a re-run only tops up
+fn run_import(cli: &Cli, file: &Path, blend: Option<&str>, noise: f64, reporter: &mut ProgressReporter) -> Result<()> {
+    if !(0.0..=1.0).contains(&noise) {
+        return Err(GitHubGridError::Config(format!("--noise must be between 0 and 1, got {}", noise)));
+    }
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    
+    let mut counts = plan::parse_counts_csv(&fs::read_to_string(file)?)?;
+    let (start_date, end_date) = (*counts.keys().next().unwrap(), *counts.keys().next_back().unwrap());
+    reporter.info(&format!("📥 {} commits over {} day(s), {} to {}",
+        counts.values().sum::<u32>(), counts.len(), start_date, end_date));
+    match blend {
+        Some(name) => {
+            let pattern = create_pattern(name)?;
+            counts = plan::blend_counts(&counts, &pattern.generate(start_date, end_date), noise);
+            reporter.pattern(&format!("import+{}", name));
+        }
+        None => reporter.pattern("import"),
+    }
+    
+    let existing = git_ops.autogen_days(start_date, end_date)?;
+    for (day, count) in counts.iter_mut() {
+        *count = count.saturating_sub(existing.get(day).copied().unwrap_or(0));
+    }
+    counts.retain(|_, count| *count > 0);
+    let commits = if counts.is_empty() { Vec::new() } else { Plan::from_counts(&counts)?.commits };
+    let commits = apply_exclusions(commits, &days_off(cli, reporter)?);
+    let commits = apply_itinerary(cli, commits, reporter)?;
+    reporter.generated(&commits, start_date, end_date);
+    
+    if cli.dry_run {
+        if !reporter.is_json() {
+            show_commit_summary(&commits);
+        }
+        return Ok(());
+    }
+    if commits.is_empty() {
+        reporter.info("✅ Every day in the file is already covered");
+        return Ok(());
+    }
+    let days_in_range = (end_date - start_date).num_days() + 1;
+    if !cli.yes && !confirm_execution(commits.len(), days_in_range, &repo_path)? {
+        reporter.info("❎ Aborted, no commits were created");
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, commits, cli, reporter)
+}
+
+// Draws a canvas onto the graph for --year, or by default the one on the
+// profile page (the 53 weeks up to this one). Each lit day gets commits in
+// proportion to its shade, topping up days that already have some. With
+// `around_activity`, days that show activity on the live graph stay empty.
+fn run_art(
+    cli: &Cli,
+    draw: impl FnOnce(usize) -> Result<art::Canvas>,
+    around_activity: bool,
+    commits_per_day: u32,
+    reporter: &mut ProgressReporter,
+) -> Result<()> {
+    if commits_per_day == 0 {
+        return Err(GitHubGridError::Config("--commits must be at least 1".to_string()));
+    }
+    let today = Local::now().date_naive();
+    let (start_date, end_date) = match cli.range.year {
+        Some(year) => dates::year_range(year)?,
+        None => (art::week_start(today) - chrono::Duration::weeks(52), today),
+    };
+    let end_date = if cli.range.allow_future { end_date } else { end_date.min(today) };
+    let first_sunday = art::week_start(start_date);
+    let weeks = ((end_date - first_sunday).num_days() / 7 + 1) as usize;
+    let canvas = draw(weeks)?;
+    
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    reporter.pattern("art");
+    
+    let days_off = days_off(cli, reporter)?;
+    let mut levels = canvas.days(first_sunday);
+    if around_activity {
+        reporter.info("🔍 Fetching your contribution graph...");
+        let active = connect(cli)?.contribution_calendar(start_date, end_date)?;
+        levels.retain(|day, _| active.get(day).is_none_or(|count| *count == 0));
+        reporter.info(&format!("🔍 {} day(s) already show activity and stay empty", active.values().filter(|count| **count > 0).count()));
+    }
+    let mut counts: BTreeMap<NaiveDate, u32> = levels.iter()
+        .filter(|(day, _)| (start_date..=end_date).contains(*day) && !days_off.contains(**day))
+        .map(|(day, level)| (*day, (commits_per_day * *level as u32).div_ceil(art::MAX_LEVEL as u32)))
+        .collect();
+    if counts.len() < levels.len() && !around_activity {
+        reporter.warn(&format!("{} lit day(s) fall outside {}..{} or on excluded days and are skipped",
+            levels.len() - counts.len(), start_date, end_date));
+    }
+    let existing = git_ops.autogen_days(start_date, end_date)?;
+    for (day, count) in counts.iter_mut() {
+        *count = count.saturating_sub(existing.get(day).copied().unwrap_or(0));
+    }
+    counts.retain(|_, count| *count > 0);
+    let commits = if counts.is_empty() { Vec::new() } else { Plan::from_counts(&counts)?.commits };
+    let commits = apply_itinerary(cli, commits, reporter)?;
+    reporter.generated(&commits, start_date, end_date);
+    
+    if cli.dry_run {
+        if !reporter.is_json() {
+            println!("\n🎨 {} to {}:\n\n{}\n", start_date, end_date, canvas.to_text());
+            show_commit_summary(&commits);
+        }
+        return Ok(());
+    }
+    if commits.is_empty() {
+        reporter.info("✅ The graph already shows this drawing");
+        return Ok(());
+    }
+    let days_in_range = (end_date - start_date).num_days() + 1;
+    if !cli.yes && !confirm_execution(commits.len(), days_in_range, &repo_path)? {
+        reporter.info("❎ Aborted, no commits were created");
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, commits, cli, reporter)
+}
+
+// Matches the grid to the per-day commit counts in the source repos over the
+// chosen range, topping up days that already have generated commits. Each
+// mirrored commit keeps the original's time and UTC offset.
+fn run_mirror(cli: &Cli, sources: &[PathBuf], emails: Vec<String>, reporter: &mut ProgressReporter) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    reporter.pattern("mirror");
+    
+    let emails = if emails.is_empty() { vec![git_ops.identity()?.1] } else { emails };
+    let ranges = determine_date_ranges(&mut git_ops, &cli.range)?;
+    let (start_date, end_date) = (ranges[0].0, ranges[ranges.len() - 1].1);
+    let days: std::collections::HashSet<NaiveDate> = plan_days(&ranges, &days_off(cli, reporter)?).into_iter().collect();
+    
+    // The same commit can be reachable from several sources (e.g. two clones)
+    let mut seen = std::collections::HashSet::new();
+    let mut by_day: BTreeMap<NaiveDate, Vec<chrono::DateTime<chrono::FixedOffset>>> = BTreeMap::new();
+    for source in sources {
+        let repo = Repository::open(source)?;
+        for (oid, time) in git_ops::authored_commits(&repo, &emails)? {
+            if days.contains(&time.date_naive()) && seen.insert(oid) {
+                by_day.entry(time.date_naive()).or_default().push(time);
+            }
+        }
+    }
+    let found: usize = by_day.values().map(Vec::len).sum();
+    reporter.info(&format!("🪞 {} commits by {} in {} source repo(s), {} to {}",
+        found, emails.join(", "), sources.len(), start_date, end_date));
+    
+    let existing = git_ops.autogen_days(start_date, end_date)?;
+    let mut commits = Vec::new();
+    for (day, mut times) in by_day {
+        times.sort();
+        let already = existing.get(&day).copied().unwrap_or(0) as usize;
+        for time in times.into_iter().skip(already) {
+            commits.push(CommitInfo {
+                date: time.with_timezone(&Local),
+                message: String::new(),
+                utc_offset: Some(time.offset().local_minus_utc()),
+            });
+        }
+    }
+    commits.sort_by_key(|c| c.date);
+    patterns::separate_timestamps(&mut commits);
+    if !existing.is_empty() {
+        reporter.info(&format!("♻️  {} day(s) already mirrored in part or full, {} commits to add", existing.len(), commits.len()));
+    }
+    reporter.generated(&commits, start_date, end_date);
+    
+    if cli.dry_run {
+        if !reporter.is_json() {
+            show_commit_summary(&commits);
+        }
+        return Ok(());
+    }
+    if commits.is_empty() {
+        reporter.info("✅ Grid already mirrors the source repos");
+        return Ok(());
+    }
+    let days_in_range = (end_date - start_date).num_days() + 1;
+    if !cli.yes && !confirm_execution(commits.len(), days_in_range, &repo_path)? {
+        reporter.info("❎ Aborted, no commits were created");
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, commits, cli, reporter)
+}
+
+// `replay` subcommand: unlike `mirror`, every commit keeps its exact
+// author time and offset (no date range, no nudging apart of commits in
+// the same second), so the graph matches the source commit for commit.
+// Times already replayed into the target are skipped, so reruns only add
+// what's new.
+fn run_replay(
+    cli: &Cli,
+    source: &Path,
+    emails: &[String],
+    since: Option<&str>,
+    until: Option<&str>,
+    reporter: &mut ProgressReporter,
+) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    reporter.pattern("replay");
+    
+    let since = since.map(dates::parse_date).transpose()?;
+    let until = until.map(dates::parse_date).transpose()?;
+    let mut times: Vec<chrono::DateTime<chrono::FixedOffset>> = git_ops::authored_commits(&Repository::open(source)?, emails)?
+        .into_iter()
+        .map(|(_, time)| time)
+        .filter(|time| since.is_none_or(|since| time.date_naive() >= since) && until.is_none_or(|until| time.date_naive() <= until))
+        .collect();
+    times.sort();
+    let (Some(first), Some(last)) = (times.first().copied(), times.last().copied()) else {
+        reporter.info(&format!("ℹ️  No commits to replay in {}", source.display()));
+        return Ok(());
+    };
+    let authors = if emails.is_empty() { "every author".to_string() } else { emails.join(", ") };
+    reporter.info(&format!("⏪ {} commits by {} in {}, {} to {}",
+        times.len(), authors, source.display(), first.date_naive(), last.date_naive()));
+    
+    // Each existing [AutoGen] commit accounts for one source commit at the same instant
+    let mut replayed: BTreeMap<i64, usize> = BTreeMap::new();
+    for commit in git_ops.history()?.into_iter().filter(|c| is_autogen(&c.message)) {
+        *replayed.entry(commit.seconds).or_insert(0) += 1;
+    }
+    let mut commits = Vec::new();
+    for time in times {
+        if let Some(count) = replayed.get_mut(&time.timestamp()).filter(|count| **count > 0) {
+            *count -= 1;
+            continue;
+        }
+        commits.push(CommitInfo {
+            date: time.with_timezone(&Local),
+            message: String::new(),
+            utc_offset: Some(time.offset().local_minus_utc()),
+        });
+    }
+    let (start_date, end_date) = (first.date_naive(), last.date_naive());
+    reporter.generated(&commits, start_date, end_date);
+    
+    if cli.dry_run {
+        if !reporter.is_json() {
+            show_commit_summary(&commits);
+        }
+        return Ok(());
+    }
+    if commits.is_empty() {
+        reporter.info("✅ Everything in the source is already replayed");
+        return Ok(());
+    }
+    let days_in_range = (end_date - start_date).num_days() + 1;
+    if !cli.yes && !confirm_execution(commits.len(), days_in_range, &repo_path)? {
+        reporter.info("❎ Aborted, no commits were created");
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, commits, cli, reporter)
+}
+
+fn rollback_run(cli: &Cli, run_id: u32) -> Result<()> {
+    let record = history::find(run_id)?;
+    let last_commit = match (&record.last_commit, record.dry_run, record.rolled_back) {
+        (_, true, _) => return Err(GitHubGridError::Config(format!("Run #{} was a dry run and created no commits", run_id))),
+        (_, _, true) => return Err(GitHubGridError::Config(format!("Run #{} was already rolled back", run_id))),
+        (None, _, _) => return Err(GitHubGridError::Config(format!("Run #{} created no commits", run_id))),
+        (Some(oid), _, _) => git2::Oid::from_str(oid)?,
+    };
+    let base_commit = record.base_commit.as_deref().map(git2::Oid::from_str).transpose()?;
+    let repo_path = record.repo.clone()
+        .ok_or_else(|| GitHubGridError::Config(format!("Run #{} has no recorded repository", run_id)))?;
+    
+    let mut git_ops = GitOperations::new(Repository::open(&repo_path)?)
+        .with_branch(&record.branch)
+        .with_proxy(cli.proxy.clone())
+        .with_push_token(push_token(cli));
+    let _lock = RunLock::acquire(git_ops.repo().path(), cli.force_unlock)?;
+    
+    println!("⏪ Run #{} ({}, {}) created {} commits on {} in {}",
+        run_id, record.command, record.pattern.as_deref().unwrap_or("-"), record.created, record.branch, repo_path);
+    if !cli.yes && !confirm_prompt("Rewrite history to remove them and force-push?")? {
+        println!("❎ Aborted, nothing was changed");
+        return Ok(());
+    }
+    
+    let backup_branch = format!("github-grid-backup-{}-{}", run_id, Local::now().format("%Y%m%d%H%M%S"));
+    let result = git_ops.remove_commit_range(base_commit, last_commit, &backup_branch)?;
+    println!("✂️  Removed {} commits, replayed {} later commits ({} → {})",
+        result.removed, result.replayed, result.old_tip, result.new_tip);
+    println!("💾 Previous history saved on branch {}", backup_branch);
+    
+    if cli.no_push {
+        println!("📦 Not pushing (--no-push); run `git push --force-with-lease origin {}` when ready", record.branch);
+    } else {
+        git_ops.push_branch(&backup_branch)?;
+        git_ops.force_push()?;
+        println!("🚀 Force-pushed {} (backup pushed as {})", record.branch, backup_branch);
+    }
+    
+    history::mark_rolled_back(run_id)?;
+    Ok(())
+}
+
+fn lint_history(cli: &Cli, fix: bool) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let commits = git_ops.history()?;
+    let report = lint::lint(&commits);
+    
+    println!("🔍 Scanned {} commits on {} in {}", report.scanned, cli.branch, repo_path.display());
+    if report.is_clean() {
+        println!("✅ No artificial-looking artifacts found");
+        return Ok(());
+    }
+    for (artifact, oids) in &report.findings {
+        println!("   ⚠️  {} commits {}", oids.len(), artifact.describe());
+    }
+    if !fix {
+        println!("\nRun `lint --fix` to rewrite them");
+        return Ok(());
+    }
+    
+    let _lock = RunLock::acquire(git_ops.repo().path(), cli.force_unlock)?;
+    let mut chosen = Vec::new();
+    for (artifact, oids) in &report.findings {
+        let question = format!("Rewrite the {} commits that {}?", oids.len(), artifact.describe());
+        if cli.yes || confirm_prompt(&question)? {
+            chosen.push(*artifact);
+        }
+    }
+    
+    let edits = lint::fixes(&commits, &report, &chosen, &mut message_bank(cli)?);
+    if edits.is_empty() {
+        println!("❎ Nothing to rewrite, history unchanged");
+        return Ok(());
+    }
+    
+    let backup_branch = format!("github-grid-lint-backup-{}", Local::now().format("%Y%m%d%H%M%S"));
+    let result = git_ops.rewrite_commits(&edits, &backup_branch)?;
+    println!("✏️  Rewrote {} commits, replayed {} descendants ({} → {})",
+        result.edited, result.replayed, result.old_tip, result.new_tip);
+    println!("💾 Previous history saved on branch {}", backup_branch);
+    
+    if cli.no_push {
+        println!("📦 Not pushing (--no-push); run `git push --force-with-lease origin {}` when ready", cli.branch);
+    } else {
+        git_ops.push_branch(&backup_branch)?;
+        git_ops.force_push()?;
+        println!("🚀 Force-pushed {} (backup pushed as {})", cli.branch, backup_branch);
+    }
+    Ok(())
+}
+
+// Re-credits generated commits, for runs made with an email GitHub doesn't
+// associate with the account (which earn no contributions)
+fn scrub_identity(cli: &Cli, new_email: &str, new_name: Option<&str>) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = RunLock::acquire(git_ops.repo().path(), cli.force_unlock)?;
+    
+    let edits: std::collections::HashMap<_, _> = git_ops.history()?
+        .into_iter()
+        .filter(|commit| is_autogen(&commit.message))
+        .filter(|commit| commit.author_email != new_email || new_name.is_some())
+        .map(|commit| (commit.oid, git_ops::CommitEdit {
+            name: new_name.map(str::to_string),
+            email: Some(new_email.to_string()),
+            ..Default::default()
+        }))
+        .collect();
+    if edits.is_empty() {
+        println!("✅ Every [AutoGen] commit on {} already uses {}", cli.branch, new_email);
+        return Ok(());
+    }
+    
+    println!("🪪 {} [AutoGen] commits on {} in {} will be re-credited to {}",
+        edits.len(), cli.branch, repo_path.display(), new_email);
+    if !cli.yes && !confirm_prompt("Rewrite history and force-push?")? {
+        println!("❎ Aborted, nothing was changed");
+        return Ok(());
+    }
+    
+    let backup_branch = format!("github-grid-scrub-backup-{}", Local::now().format("%Y%m%d%H%M%S"));
+    let result = git_ops.rewrite_commits(&edits, &backup_branch)?;
+    println!("✏️  Rewrote {} commits, replayed {} others ({} → {})",
+        result.edited, result.replayed, result.old_tip, result.new_tip);
+    println!("💾 Previous history saved on branch {}", backup_branch);
+    
+    if cli.no_push {
+        println!("📦 Not pushing (--no-push); run `git push --force-with-lease origin {}` when ready", cli.branch);
+    } else {
+        git_ops.push_branch(&backup_branch)?;
+        git_ops.force_push()?;
+        println!("🚀 Force-pushed {} (backup pushed as {})", cli.branch, backup_branch);
+    }
+    
+    println!("💡 Future runs use git's user.email; set it with `git config --global user.email {}`", new_email);
+    Ok(())
+}
+
+fn run_daemon(cli: &Cli, reporter: &ProgressReporter) -> Result<()> {
+    let (_repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    
+    let today = Local::now().date_naive();
+    let Some(pattern) = pattern_for_remaining_year(cli, &git_ops, today, reporter)? else {
+        reporter.info("✅ Target reached, nothing to do");
+        return Ok(());
+    };
+    
+    daemon::run_daemon(&mut git_ops, pattern.as_ref(), reporter, !cli.no_push)
+}
+
+fn run(cli: &Cli, reporter: &mut ProgressReporter) -> Result<()> {
+    let (repo_path, mut git_ops) = open_target_repo(cli)?;
+    let _lock = prepare_target_repo(cli, &mut git_ops)?;
+    reporter.target(&repo_path.display().to_string());
+    reporter.base_commit(git_ops.branch_head()?.map(|oid| oid.to_string()));
+    
+    if cli.stream {
+        return run_streaming(cli, &repo_path, git_ops, reporter);
+    }
+    
+    let mut repos = [git_ops];
+    let Some(plan) = plan_run(cli, &mut repos, reporter)? else {
+        return Ok(());
+    };
+    let [mut git_ops] = repos;
+    
+    if cli.dry_run {
+        show_dry_run(&plan, reporter);
+        return Ok(());
+    }
+    
+    if !cli.yes && !confirm_execution(plan.commits.len(), plan.days_in_range, &repo_path)? {
+        reporter.info("❎ Aborted, no commits were created");
+        return Ok(());
+    }
+    
+    execute_commits(&mut git_ops, plan.commits, cli, reporter)?;
+    
+    Ok(())
+}
+
+// --stream: the pattern's commits go through the per-commit filters of
+// plan_run (exclusions, public days, existing days, travel) and straight
+// into the repo, so memory stays flat however long the range is
+fn run_streaming(cli: &Cli, repo_path: &Path, mut git_ops: GitOperations, reporter: &mut ProgressReporter) -> Result<()> {
+    let ranges = determine_date_ranges(&mut git_ops, &cli.range)?;
+    let start_date = ranges[0].0;
+    let end_date = ranges[ranges.len() - 1].1;
+    let days_in_range: i64 = ranges.iter().map(|(start, end)| (*end - *start).num_days() + 1).sum();
+    for (start, end) in &ranges {
+        reporter.info(&format!("Streaming commits from {} to {}", start, end));
+    }
+    reporter.info(&format!("Pattern: {}", cli.pattern));
+    reporter.pattern(&cli.pattern);
+    reporter.streaming(start_date, end_date);
+    
+    let pattern = run_pattern(cli, reporter)?;
+    let days_off = days_off(cli, reporter)?;
+    let public_days = public_active_days(cli, std::slice::from_ref(&git_ops), start_date, end_date, reporter)?;
+    let existing = git_ops.autogen_days(start_date, end_date)?;
+    let itinerary = cli.itinerary.as_deref().map(Itinerary::load).transpose()?;
+    
+    if !cli.yes {
+        eprintln!();
+        eprintln!("📋 Plan: stream '{}' commits over {} days to {}", cli.pattern, days_in_range, repo_path.display());
+        if !confirm_prompt("Proceed?")? {
+            reporter.info("❎ Aborted, no commits were created");
+            return Ok(());
+        }
+    }
+    
+    let now = Local::now();
+    let mut existing_filter = ExistingFilter::new(&existing, cli.on_existing);
+    let mut rng = rand::rng();
+    let commits = ranges.iter()
+        .flat_map(|(start, end)| pattern.stream(*start, *end))
+        .filter(|commit| !days_off.contains(commit.date.date_naive()))
+        .filter(|commit| {
+            !holidays::is_eve(commit.date.date_naive(), &days_off.holidays) || rng.random_bool(cli.range.holiday_eve_share)
+        })
+        // Travel first, as it can change a commit's day and push it past now
+        .map(|mut commit| {
+            if let Some(itinerary) = &itinerary {
+                itinerary.apply(std::slice::from_mut(&mut commit));
+            }
+            commit
+        })
+        .filter(|commit| {
+            !public_days.contains(&commit.date.date_naive()) && (cli.range.allow_future || commit.date <= now)
+        })
+        .filter(|commit| existing_filter.keep(commit));
+    execute_stream(&mut git_ops, commits, None, cli, reporter)
+}
+
+// What a run is about to create
+struct PlannedRun {
+    commits: Vec<CommitInfo>,
+    days_in_range: i64,
+    year_targets: Option<Vec<YearTarget>>,
+}
+
+// Generates the commits for a run against the history of `repos` (one
+// repo normally, every repo of an allocating fleet): existing commits and
+// filled days are counted across all of them, and the first one anchors
+// --since-last-run. Returns None when there's nothing to do.
+fn plan_run(cli: &Cli, repos: &mut [GitOperations], reporter: &mut ProgressReporter) -> Result<Option<PlannedRun>> {
+    reporter.start_generation();
+    let ranges = determine_date_ranges(&mut repos[0], &cli.range)?;
+    let start_date = ranges[0].0;
+    let end_date = ranges[ranges.len() - 1].1;
+    let days_in_range: i64 = ranges.iter().map(|(start, end)| (*end - *start).num_days() + 1).sum();
+    
+    for (start, end) in &ranges {
+        reporter.info(&format!("Generating commits from {} to {}", start, end));
+    }
+    
+    let mut existing_days: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for git_ops in repos.iter() {
+        for (day, count) in git_ops.autogen_days(start_date, end_date)? {
+            *existing_days.entry(day).or_insert(0) += count;
+        }
+    }
+    
+    let (pattern_name, commits, year_targets) = if let Some(target_total) = cli.target_total {
+        // Target-based generation, calibrated separately for each calendar year
+        let mut commits = Vec::new();
+        let mut year_targets = Vec::new();
+        
+        for (year, year_ranges) in dates::split_by_year(&ranges) {
+            let target = target_for_year(cli, year)?.unwrap_or(target_total);
+            let mut existing = CommitCounts::default();
+            for git_ops in repos.iter() {
+                let counts = git_ops.count_commits_in_year(year)?;
+                existing.autogen += counts.autogen;
+                existing.real += counts.real;
+            }
+            let needed = target.saturating_sub(counted_toward_target(&existing, cli.target_scope));
+            let year_days: i64 = year_ranges.iter().map(|(start, end)| (*end - *start).num_days() + 1).sum();
+            
+            reporter.info(&format!("🎯 {}: target {} commits", year, target));
+            reporter.info(&format!(
+                "📊 Existing: {} generated + {} real commits ({} counted toward target)",
+                existing.autogen,
+                existing.real,
+                counted_toward_target(&existing, cli.target_scope),
+            ));
+            
+            if needed == 0 {
+                reporter.info(&format!("✅ {} target already reached", year));
+            } else {
+                reporter.info(&format!("➕ Generating: ~{} commits over {} days", needed, year_days));
+                // Days that will be skipped can't take any of the target
+                let open_ranges = match cli.on_existing {
+                    ExistingDays::Skip => ranges_without(&year_ranges, &existing_days),
+                    _ => year_ranges.clone(),
+                };
+                let calibration = calibrate::calibrate(needed, &open_ranges, cli.tolerance / 100.0);
+                report_calibration(&calibration, reporter);
+                commits.extend(calibration.commits);
+            }
+            year_targets.push(YearTarget { year, needed, ranges: year_ranges });
+        }
+        
+        if year_targets.iter().all(|t| t.needed == 0) {
+            reporter.info("✅ Target already reached!");
+            return Ok(None);
+        }
+        
+        (format!("target-{}", target_total), commits, Some(year_targets))
+    } else {
+        // Traditional pattern-based generation
+        reporter.info(&format!("Pattern: {}", cli.pattern));
+        let pattern = run_pattern(cli, reporter)?;
+        let commits = generate_for_ranges(pattern.as_ref(), &ranges);
+        (cli.pattern.clone(), commits, None)
+    };
+    
+    reporter.pattern(&pattern_name);
+    let days_off = days_off(cli, reporter)?;
+    let commits = apply_exclusions(commits, &days_off);
+    let commits = thin_holiday_eves(cli, commits, &days_off);
+    let mut commits = apply_itinerary(cli, commits, reporter)?;
+    let public_days = public_active_days(cli, repos, start_date, end_date, reporter)?;
+    commits.retain(|c| !public_days.contains(&c.date.date_naive()));
+    let mut days = plan_days(&ranges, &days_off);
+    days.retain(|day| !public_days.contains(day));
+    let mut commits = apply_coding_time(cli, commits, &days, reporter)?;
+    // Today's range includes hours that haven't happened yet
+    let now = (!cli.range.allow_future).then(Local::now);
+    if let Some(now) = now {
+        commits.retain(|c| c.date <= now);
+    }
+    
+    if !existing_days.is_empty() {
+        let planned = commits.len();
+        commits = reconcile_existing_days(commits, &existing_days, cli.on_existing);
+        reporter.info(&format!(
+            "♻️  {} day(s) already have generated commits ({}): plan {} → {} commits",
+            existing_days.len(),
+            cli.on_existing.to_possible_value().unwrap().get_name(),
+            planned,
+            commits.len(),
+        ));
+    }
+    
+    if let (true, Some(year_targets)) = (cli.exact, &year_targets) {
+        let generated = commits.len();
+        let mut by_year = commits_by_year(commits);
+        commits = Vec::new();
+        for target in year_targets {
+            let year_commits = by_year.remove(&target.year).unwrap_or_default();
+            let mut days = plan_days(&target.ranges, &days_off);
+            if cli.on_existing == ExistingDays::Skip {
+                days.retain(|day| !existing_days.contains_key(day));
+            }
+            days.retain(|day| !public_days.contains(day));
+            commits.extend(patterns::fit_to_total(year_commits, target.needed as usize, &days));
+        }
+        reporter.info(&format!("🎯 Exact mode: adjusted plan from {} to {} commits", generated, commits.len()));
+    }
+    
+    if cli.on_existing == ExistingDays::Skip {
+        days.retain(|day| !existing_days.contains_key(day));
+    }
+    commits = apply_weekend_ratio(cli, commits, &days, now, reporter)?;
+    commits = apply_active_days(cli, commits, &days, now, reporter)?;
+    commits = apply_day_caps(cli, commits, &days, &existing_days, now, reporter)?;
+    
+    if cli.edit {
+        match editor::edit_plan(commits, start_date, end_date)? {
+            Some(edited) => commits = edited,
+            None => {
+                reporter.info("❎ Edit cancelled, no commits were created");
+                return Ok(None);
+            }
+        }
+    }
+    
+    reporter.info(&format!("Generated {} commits", commits.len()));
+    reporter.generated(&commits, start_date, end_date);
+    
+    Ok(Some(PlannedRun { commits, days_in_range, year_targets }))
+}
+
+fn show_dry_run(plan: &PlannedRun, reporter: &ProgressReporter) {
+    if reporter.is_json() {
+        return;
+    }
+    show_commit_summary(&plan.commits);
+    if let Some(year_targets) = &plan.year_targets {
+        let by_year = commits_by_year(plan.commits.clone());
+        println!("   Expected vs achieved:");
+        for target in year_targets {
+            let achieved = by_year.get(&target.year).map_or(0, |c| c.len());
+            println!("     {}: {} / {} commits ({:+.1}%)",
+                target.year, target.needed, achieved, percent_off(target.needed, achieved));
+        }
+    }
+}
+
+fn show_patterns() {
+    let entries = PatternRegistry::entries();
+    println!("Available patterns:");
+
+    for (category, heading) in [
+        (PatternCategory::Activity, "Activity levels (commits/year):"),
+        (PatternCategory::Legacy, "Legacy patterns:"),
+        (PatternCategory::Custom, "Custom patterns:"),
+    ] {
+        let group: Vec<_> = entries.iter().filter(|e| e.category == category).collect();
+        if group.is_empty() {
+            continue;
+        }
+        println!("\n{}", heading);
+        for entry in group {
+            println!("  {:<11} - {}", entry.name, entry.description);
+        }
+    }
+}
+
+fn preview_pattern(pattern_name: &str, start: NaiveDate, end: NaiveDate, gif_path: Option<&Path>, speed: Option<f64>) -> Result<()> {
+    if (gif_path.is_some() || speed.is_some()) && end < start {
+        return Err(GitHubGridError::Config("--gif and --animate need an end date on or after the start".to_string()));
+    }
+    if let Some(speed) = speed && !(speed.is_finite() && speed > 0.0) {
+        return Err(GitHubGridError::Config(format!("--speed must be a positive number of days per second, got {}", speed)));
+    }
+    let pattern = create_pattern(pattern_name)?;
+    let commits = pattern.generate(start, end);
+    let mut days: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for commit in &commits {
+        *days.entry(commit.date.date_naive()).or_default() += 1;
+    }
+    
+    match speed {
+        Some(speed) if io::stdout().is_terminal() => {
+            println!();
+            playback::play(&days, start, end, speed)?;
+            println!();
+        }
+        Some(_) => {
+            eprintln!("⚠️  --animate needs a terminal; showing the static calendar instead");
+            show_commit_calendar(&commits, start, end);
+        }
+        None => show_commit_calendar(&commits, start, end),
+    }
+    show_commit_summary(&commits);
+    
+    if let Some(path) = gif_path {
+        fs::write(path, gif::grid_animation(&days, start, end))?;
+        println!("🎞️  Wrote the grid animation to {}", path.display());
+    }
+    
+    Ok(())
+}
+
+// Shaded like GitHub: levels are cut at the quartiles of the plan's active
+// days, not at fixed counts
+fn show_commit_calendar(commits: &[CommitInfo], start: NaiveDate, end: NaiveDate) {
+    println!("\n📅 Commit Calendar:");
+    
+    let mut days: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for commit in commits {
+        *days.entry(commit.date.date_naive()).or_default() += 1;
+    }
+    let thresholds = readme_stats::quartile_thresholds(&days, start, end);
+    let level = readme_stats::quartile_levels(&days, start, end);
+    
+    if start.year() != end.year() {
+        show_yearly_grids(&days, start, end, &level);
+        println!("\n{}", readme_stats::legend(thresholds));
+        println!("Levels split the plan's active days into quartiles, as GitHub does\n");
+        return;
+    }
+    
+    let mut current = start;
+    while current <= end {
+        let symbol = readme_stats::LEVELS[level(days.get(&current).copied().unwrap_or(0))];
+        
+        if current.weekday().number_from_monday() == 1 {
+            println!();
+            print!("{:>10} ", current.format("%b %d"));
+        }
+        
+        print!("{}", symbol);
+        current = current.succ_opt().unwrap();
+    }
+    println!("\n\n{}", readme_stats::legend(thresholds));
+    println!("Levels split the plan's active days into quartiles, as GitHub does\n");
+}
+
+// One GitHub-layout grid per calendar year, stacked like the profile's
+// year selector, each with its own totals. Shades share the whole range's
+// thresholds so years can be compared at a glance.
+fn show_yearly_grids(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate, level: &dyn Fn(u32) -> usize) {
+    for year in start.year()..=end.year() {
+        let year_start = start.max(NaiveDate::from_ymd_opt(year, 1, 1).unwrap());
+        let year_end = end.min(NaiveDate::from_ymd_opt(year, 12, 31).unwrap());
+        let in_year: BTreeMap<NaiveDate, u32> = days.range(year_start..=year_end).map(|(d, c)| (*d, *c)).collect();
+        
+        println!("\n  {}", year);
+        let grid = readme_stats::labelled_grid(year_start, year_end, |day| {
+            readme_stats::LEVELS[level(in_year.get(&day).copied().unwrap_or(0))]
+        });
+        for line in grid {
+            println!("  {}", line);
+        }
+        
+        let total: u32 = in_year.values().sum();
+        let (longest, _) = readme_stats::streaks(&in_year, year_start, year_end);
+        let busiest = in_year.iter()
+            .max_by_key(|(day, count)| (**count, std::cmp::Reverse(**day)))
+            .map(|(day, count)| format!(", busiest {} ({})", day.format("%b %d"), count))
+            .unwrap_or_default();
+        println!("  {} commits on {} days, longest streak {} days{}",
+            total, in_year.values().filter(|&&c| c > 0).count(), longest, busiest);
+    }
+}
+
+fn show_commit_summary(commits: &[CommitInfo]) {
+    let total = commits.len();
+    let avg_per_day = if total > 0 {
+        commits.iter()
+            .map(|c| c.date.date_naive())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    } else {
+        0
+    };
+    
+    println!("Summary:");
+    println!("  Total commits: {}", total);
+    println!("  Active days: {}", avg_per_day);
+    if avg_per_day > 0 {
+        println!("  Avg commits/day: {:.1}", total as f64 / avg_per_day as f64);
+    }
+    
+    let weekend_commits = commits.iter()
+        .filter(|c| {
+            let weekday = c.date.weekday();
+            weekday == chrono::Weekday::Sat || weekday == chrono::Weekday::Sun
+        })
+        .count();
+    
+    println!("  Weekend commits: {} ({:.1}%)", weekend_commits, 
+             weekend_commits as f64 / total as f64 * 100.0);
+}
+
+fn determine_date_ranges(
+    git_ops: &mut GitOperations,
+    range: &RangeArgs,
+) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    let ranges = if range.ranges.is_empty() {
+        vec![determine_date_range(git_ops, range)?]
+    } else {
+        parse_explicit_ranges(range)?
+    };
+    
+    if range.allow_future {
+        return Ok(ranges);
+    }
+    clamp_to_today(ranges, Local::now().date_naive())
+}
+
+// GitHub renders future-dated commits oddly, so ranges stop at today
+// unless --allow-future is given
+fn clamp_to_today(ranges: Vec<(NaiveDate, NaiveDate)>, today: NaiveDate) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    let clamped: Vec<_> = ranges.into_iter()
+        .filter(|(start, _)| *start <= today)
+        .map(|(start, end)| (start, end.min(today)))
+        .collect();
+    
+    if clamped.is_empty() {
+        return Err(GitHubGridError::Config(format!(
+            "The requested range starts after today ({}); pass --allow-future to generate future-dated commits",
+            today
+        )));
+    }
+    Ok(clamped)
+}
+
+fn parse_explicit_ranges(range: &RangeArgs) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    let mut ranges = range.ranges.iter()
+        .map(|value| dates::parse_range(value))
+        .collect::<Result<Vec<_>>>()?;
+    ranges.sort();
+    
+    for pair in ranges.windows(2) {
+        if pair[1].0 <= pair[0].1 {
+            return Err(GitHubGridError::Config(format!(
+                "Ranges {}..{} and {}..{} overlap", pair[0].0, pair[0].1, pair[1].0, pair[1].1
+            )));
+        }
+    }
+    
+    Ok(ranges)
+}
+
+fn determine_date_range(
+    git_ops: &mut GitOperations,
+    range: &RangeArgs,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let today = Local::now().date_naive();
+    
+    if let Some(year) = range.year {
+        return dates::year_range(year);
+    }
+    if let Some(month) = &range.month {
+        return dates::month_range(month);
+    }
+    if let Some(last) = &range.last {
+        return dates::last_range(last, today);
+    }
+    
+    let end_date = match &range.end {
+        Some(date_str) => dates::parse_date(date_str)?,
+        None => today,
+    };
+    
+    let start_date = match &range.start {
+        Some(date_str) => dates::parse_date(date_str)?,
+        None => {
+            match git_ops.get_latest_autogen_commit()? {
+                Some(last_commit) => last_commit.date_naive() + chrono::Duration::days(1),
+                None if range.since_last_run => {
+                    return Err(GitHubGridError::Repository(
+                        "--since-last-run: no [AutoGen] commits found in repository".to_string()
+                    ));
+                }
+                None => end_date - chrono::Duration::days(365),
+            }
+        }
+    };
+    
+    Ok((start_date, end_date))
+}
+
+fn generate_for_ranges(pattern: &dyn Pattern, ranges: &[(NaiveDate, NaiveDate)]) -> Vec<CommitInfo> {
+    ranges.iter()
+        .flat_map(|(start, end)| pattern.generate(*start, *end))
+        .collect()
+}
+
+fn apply_exclusions(mut commits: Vec<CommitInfo>, days_off: &DaysOff) -> Vec<CommitInfo> {
+    commits.retain(|c| !days_off.contains(c.date.date_naive()));
+    commits
+}
+
+// Matching all-day calendar events, as blackout ranges
+fn calendar_ranges(range: &RangeArgs, reporter: &ProgressReporter) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    let Some(path) = &range.calendar else {
+        return Ok(Vec::new());
+    };
+    let events = calendar::load(path)?;
+    let ranges = calendar::blackout_ranges(&events, &range.calendar_keywords);
+    if !ranges.is_empty() {
+        let days: i64 = ranges.iter().map(|(start, end)| (*end - *start).num_days() + 1).sum();
+        reporter.info(&format!("🏖️  {} calendar event(s) block out {} day(s)", ranges.len(), days));
+    }
+    Ok(ranges)
+}
+
+// Every day off from --holidays and --holidays-file, from the start of
+// GitHub to the end of next year
+fn holiday_days(range: &RangeArgs) -> Result<BTreeSet<NaiveDate>> {
+    let mut table = Holidays::default();
+    for code in &range.holidays {
+        table = table.merge(Holidays::preset(code.trim())?);
+    }
+    if let Some(path) = &range.holidays_file {
+        table = table.merge(Holidays::load(path)?);
+    }
+    if table.is_empty() {
+        return Ok(BTreeSet::new());
+    }
+    let start = NaiveDate::from_ymd_opt(2008, 1, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(Local::now().year() + 1, 12, 31).unwrap();
+    Ok(table.between(start, end).into_keys().collect())
+}
+
+// Days no commit may land on: --exclude ranges, calendar events and holidays
+struct DaysOff {
+    ranges: Vec<(NaiveDate, NaiveDate)>,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl DaysOff {
+    fn contains(&self, day: NaiveDate) -> bool {
+        self.holidays.contains(&day) || self.ranges.iter().any(|(start, end)| (*start..=*end).contains(&day))
+    }
+}
+
+// Loaded only by the commands that generate commits
+fn days_off(cli: &Cli, reporter: &ProgressReporter) -> Result<DaysOff> {
+    if !(0.0..=1.0).contains(&cli.range.holiday_eve_share) {
+        return Err(GitHubGridError::Config(format!(
+            "--holiday-eve-share must be between 0 and 1, got {}", cli.range.holiday_eve_share
+        )));
+    }
+    let mut ranges = cli.range.exclude.iter()
+        .map(|value| dates::parse_range(value))
+        .collect::<Result<Vec<_>>>()?;
+    ranges.extend(calendar_ranges(&cli.range, reporter)?);
+    let holidays = holiday_days(&cli.range)?;
+    if !holidays.is_empty() {
+        reporter.info(&format!("🎉 Skipping {} holiday(s) through {}", holidays.len(), holidays.last().unwrap().year()));
+    }
+    Ok(DaysOff { ranges, holidays })
+}
+
+// Winds down the working day before each holiday
+fn thin_holiday_eves(cli: &Cli, commits: Vec<CommitInfo>, days_off: &DaysOff) -> Vec<CommitInfo> {
+    if days_off.holidays.is_empty() {
+        return commits;
+    }
+    holidays::thin_eves(commits, &days_off.holidays, cli.range.holiday_eve_share)
+}
+
+// Days with commits in the user's public repos, which --fill-inactive
+// leaves to the real activity. The target repos themselves don't count.
+fn public_active_days(
+    cli: &Cli,
+    repos: &[GitOperations],
+    start: NaiveDate,
+    end: NaiveDate,
+    reporter: &ProgressReporter,
+) -> Result<BTreeSet<NaiveDate>> {
+    if !cli.fill_inactive {
+        return Ok(BTreeSet::new());
+    }
+    let skip: Vec<String> = repos.iter().filter_map(origin_slug).collect();
+    let client = connect(cli)?;
+    let days: BTreeSet<NaiveDate> = client.public_commit_days(start, end, &skip)?
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(day, _)| day)
+        .collect();
+    reporter.info(&format!("🌱 {} day(s) with public commits elsewhere are left as they are", days.len()));
+    Ok(days)
+}
+
+// `owner/name` of the repo's origin remote, for HTTPS and SSH URLs alike
+fn origin_slug(git_ops: &GitOperations) -> Option<String> {
+    let remote = git_ops.repo().find_remote("origin").ok()?;
+    let url = remote.url()?.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = url.rsplit(['/', ':']);
+    let name = parts.next()?;
+    let owner = parts.next()?;
+    Some(format!("{}/{}", owner, name))
+}
+
+fn apply_weekend_ratio(
+    cli: &Cli,
+    commits: Vec<CommitInfo>,
+    days: &[NaiveDate],
+    not_after: Option<DateTime<Local>>,
+    reporter: &ProgressReporter,
+) -> Result<Vec<CommitInfo>> {
+    let Some(ratio) = weekend_ratio(cli)? else {
+        return Ok(commits);
+    };
+    let commits = patterns::rebalance_weekends(commits, ratio, days, not_after);
+    let weekend = commits.iter().filter(|c| matches!(c.date.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun)).count();
+    reporter.info(&format!(
+        "🗓️  Weekend share: {} of {} commits ({:.1}%, asked for {:.1}%)",
+        weekend,
+        commits.len(),
+        weekend as f64 * 100.0 / commits.len().max(1) as f64,
+        ratio * 100.0,
+    ));
+    Ok(commits)
+}
+
+// Spreads or concentrates the plan over --active-days of the days that may
+// receive commits
+fn apply_active_days(
+    cli: &Cli,
+    commits: Vec<CommitInfo>,
+    days: &[NaiveDate],
+    not_after: Option<DateTime<Local>>,
+    reporter: &ProgressReporter,
+) -> Result<Vec<CommitInfo>> {
+    let Some(share) = cli.active_days else {
+        return Ok(commits);
+    };
+    let target = (days.len() as f64 * share).round() as usize;
+    if commits.len() < target {
+        reporter.warn(&format!("Only {} commits planned, fewer than the {} active days asked for", commits.len(), target));
+    }
+    let commits = patterns::set_active_days(commits, target, days, not_after);
+    let active = commits.iter().map(|c| c.date.date_naive()).collect::<BTreeSet<_>>().len();
+    reporter.info(&format!(
+        "🟩 Active days: {} of {} ({:.0}%, asked for {:.0}%)",
+        active,
+        days.len(),
+        active as f64 * 100.0 / days.len().max(1) as f64,
+        share * 100.0,
+    ));
+    Ok(commits)
+}
+
+fn weekend_ratio(cli: &Cli) -> Result<Option<f64>> {
+    match cli.weekend_ratio {
+        Some(ratio) if !(0.0..=1.0).contains(&ratio) => {
+            Err(GitHubGridError::Config(format!("--weekend-ratio must be between 0 and 1, got {}", ratio)))
+        }
+        ratio => Ok(ratio),
+    }
+}
+
+// The effective --min-per-day, or None when no per-day limit is set
+fn min_per_day(cli: &Cli) -> Result<Option<u32>> {
+    if cli.min_per_day.is_none() && cli.max_per_day.is_none() {
+        return Ok(None);
+    }
+    let min = cli.min_per_day.unwrap_or(1);
+    if cli.max_per_day == Some(0) {
+        return Err(GitHubGridError::Config("--max-per-day must be at least 1".to_string()));
+    }
+    if let Some(max) = cli.max_per_day && min > max {
+        return Err(GitHubGridError::Config(format!("--min-per-day {} is above --max-per-day {}", min, max)));
+    }
+    Ok(Some(min))
+}
+
+// Enforces --min-per-day/--max-per-day on the finished plan, counting
+// commits already on each day and moving commits between days (new ones
+// only from `days`, none after `not_after`) instead of dropping them
+fn apply_day_caps(
+    cli: &Cli,
+    commits: Vec<CommitInfo>,
+    days: &[NaiveDate],
+    existing: &BTreeMap<NaiveDate, u32>,
+    not_after: Option<DateTime<Local>>,
+    reporter: &ProgressReporter,
+) -> Result<Vec<CommitInfo>> {
+    let Some(min) = min_per_day(cli)? else {
+        return Ok(commits);
+    };
+    
+    let planned = commits.len();
+    let (commits, dropped) = patterns::clamp_per_day(commits, min, cli.max_per_day, days, existing, not_after);
+    reporter.info(&format!(
+        "📏 Kept every active day within {}..{} commits",
+        min,
+        cli.max_per_day.map_or("∞".to_string(), |max| max.to_string()),
+    ));
+    if dropped > 0 {
+        reporter.warn(&format!("{} of {} commits didn't fit within the per-day limits and were left out", dropped, planned));
+    }
+    Ok(commits)
+}
+
+fn apply_coding_time(cli: &Cli, commits: Vec<CommitInfo>, days: &[NaiveDate], reporter: &ProgressReporter) -> Result<Vec<CommitInfo>> {
+    let Some(path) = &cli.coding_time else {
+        return Ok(commits);
+    };
+    let coding_time = CodingTime::load(path)?;
+    if let Some((first, last)) = coding_time.coverage() {
+        reporter.info(&format!("⏱️  Shaping days {} to {} by tracked coding time", first, last));
+    }
+    Ok(coding_time.shape(commits, days))
+}
+
+// Moves commits on trip days to the destination's local time. Runs before
+// any day-level planning, as a commit can change day on the way and a
+// westward trip can put it after now, which is dropped here.
+fn apply_itinerary(cli: &Cli, mut commits: Vec<CommitInfo>, reporter: &ProgressReporter) -> Result<Vec<CommitInfo>> {
+    let Some(path) = &cli.itinerary else {
+        return Ok(commits);
+    };
+    let itinerary = Itinerary::load(path)?;
+    let moved = itinerary.apply(&mut commits);
+    if moved > 0 {
+        reporter.info(&format!("✈️  {} commits moved to local time across {} trip(s)", moved, itinerary.trips().len()));
+    }
+    if !cli.range.allow_future {
+        let now = Local::now();
+        let planned = commits.len();
+        commits.retain(|c| c.date <= now);
+        if commits.len() < planned {
+            reporter.warn(&format!("{} commits abroad would be after the current time and were left out", planned - commits.len()));
+        }
+    }
+    Ok(commits)
+}
+
+// The ranges split around the given days
+fn ranges_without(ranges: &[(NaiveDate, NaiveDate)], days: &BTreeMap<NaiveDate, u32>) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut open = Vec::new();
+    for &(start, end) in ranges {
+        let mut from = start;
+        for &day in days.range(start..=end).map(|(day, _)| day) {
+            if day > from {
+                open.push((from, day.pred_opt().unwrap()));
+            }
+            from = day.succ_opt().unwrap();
+        }
+        if from <= end {
+            open.push((from, end));
+        }
+    }
+    open
+}
+
+// Every date in the generation ranges that isn't excluded
+fn plan_days(ranges: &[(NaiveDate, NaiveDate)], days_off: &DaysOff) -> Vec<NaiveDate> {
+    ranges.iter()
+        .flat_map(|(start, end)| start.iter_days().take_while(move |day| day <= end))
+        .filter(|day| !days_off.contains(*day))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageStyle {
+    /// Messages as written in the message bank
+    Plain,
+    /// Corporate-style `KEY-123: message` with ticket numbers rising over time
+    Ticket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ContentMode {
+    /// Empty commits that change no files
+    Empty,
+    /// Append `{"date": ..., "seq": N}` to a monthly log/YYYY-MM.jsonl file
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum HooksMode {
+    /// Commit without hooks (libgit2 never runs them), warning when the repo has some
+    Skip,
+    /// Run pre-commit, prepare-commit-msg, commit-msg and post-commit for every commit
+    Run,
+    /// Stop before committing if the repo has any commit hooks
+    Refuse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExistingDays {
+    /// Leave days with generated commits untouched
+    Skip,
+    /// Only add the commits a day is missing compared to the new plan
+    TopUp,
+    /// Generate on top of existing commits (may double-stack reruns)
+    Stack,
+}
+
+// Drops or trims planned commits on days that a previous run already
+// filled, so re-running over an overlapping range stays idempotent
+fn reconcile_existing_days(
+    commits: Vec<CommitInfo>,
+    existing: &BTreeMap<NaiveDate, u32>,
+    mode: ExistingDays,
+) -> Vec<CommitInfo> {
+    if mode == ExistingDays::Stack {
+        return commits;
+    }
+    
+    let mut filter = ExistingFilter::new(existing, mode);
+    commits.into_iter().filter(|commit| filter.keep(commit)).collect()
+}
+
+// Decides commit by commit, in date order, which ones reconcile_existing_days
+// keeps, so streamed runs can apply it too
+struct ExistingFilter<'a> {
+    existing: &'a BTreeMap<NaiveDate, u32>,
+    mode: ExistingDays,
+    seen: BTreeMap<NaiveDate, u32>,
+}
+
+impl<'a> ExistingFilter<'a> {
+    fn new(existing: &'a BTreeMap<NaiveDate, u32>, mode: ExistingDays) -> Self {
+        Self { existing, mode, seen: BTreeMap::new() }
+    }
+    
+    fn keep(&mut self, commit: &CommitInfo) -> bool {
+        let day = commit.date.date_naive();
+        let Some(&already) = self.existing.get(&day) else {
+            return true;
+        };
+        let index = self.seen.entry(day).or_insert(0);
+        *index += 1;
+        match self.mode {
+            ExistingDays::Skip => false,
+            ExistingDays::TopUp => *index > already,
+            ExistingDays::Stack => true,
+        }
+    }
+}
+
+// Commits still needed for one calendar year of the requested ranges
+struct YearTarget {
+    year: i32,
+    needed: u32,
+    ranges: Vec<(NaiveDate, NaiveDate)>,
+}
+
+// Every --year-target is checked, not just the one for `year`, so a typo
+// fails the run whichever year it covers
+fn target_for_year(cli: &Cli, year: i32) -> Result<Option<u32>> {
+    let targets = cli.year_targets.iter()
+        .map(|value| {
+            value.split_once('=')
+                .and_then(|(y, total)| Some((y.trim().parse::<i32>().ok()?, total.trim().parse::<u32>().ok()?)))
+                .ok_or_else(|| GitHubGridError::Parse(format!("Invalid --year-target '{}', expected YEAR=TOTAL", value)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(targets.into_iter().find(|(y, _)| *y == year).map(|(_, total)| total).or(cli.target_total))
+}
+
+fn commits_by_year(commits: Vec<CommitInfo>) -> BTreeMap<i32, Vec<CommitInfo>> {
+    let mut by_year: BTreeMap<i32, Vec<CommitInfo>> = BTreeMap::new();
+    for commit in commits {
+        by_year.entry(commit.date.year()).or_default().push(commit);
+    }
+    by_year
+}
+
+fn report_calibration(calibration: &Calibration, reporter: &ProgressReporter) {
+    reporter.info(&format!(
+        "🧮 Calibrated in {} iteration(s): expected {}, achieved {} ({:+.1}%)",
+        calibration.iterations,
+        calibration.expected,
+        calibration.achieved(),
+        calibration.deviation() * 100.0,
+    ));
+}
+
+fn percent_off(expected: u32, achieved: usize) -> f64 {
+    if expected == 0 {
+        return 0.0;
+    }
+    (achieved as f64 - expected as f64) / expected as f64 * 100.0
+}
+
+// The run's --pattern, with --day-off, --organic, --crunch, --quarter-end
+// and --hackathons applied
+fn run_pattern(cli: &Cli, reporter: &ProgressReporter) -> Result<Box<dyn Pattern>> {
+    let pattern: Box<dyn Pattern> = match cli.day_off {
+        Some(_) if cli.pattern != "four-day" => {
+            return Err(GitHubGridError::Config("--day-off only applies to --pattern four-day".to_string()));
+        }
+        Some(day_off) => Box::new(FourDayPattern::with_day_off(day_off)),
+        None => create_pattern(&cli.pattern)?,
+    };
+    // Crunches sit on top of the organic swells rather than being smoothed away
+    let pattern: Box<dyn Pattern> = match cli.organic {
+        true => {
+            let seed = cli.organic_seed.unwrap_or_else(|| rand::rng().random());
+            reporter.seed(seed);
+            Box::new(OrganicPattern::with_noise(pattern, DayNoise::new(seed, cli.organic_period)?))
+        }
+        false => pattern,
+    };
+    let mut windows = cli.crunch.iter().map(|value| CrunchWindow::parse(value)).collect::<Result<Vec<_>>>()?;
+    if let Some(multiplier) = cli.quarter_end {
+        // Every year a run could reach, like the holiday tables
+        windows.extend(CrunchWindow::quarter_ends(2008..=Local::now().year() + 1, cli.quarter_end_weeks, multiplier)?);
+    }
+    let hackathons = match cli.hackathons {
+        Some(per_year) => crunch::hackathon_weekends(2008..=Local::now().year() + 1, per_year)?,
+        None => Vec::new(),
+    };
+    if windows.is_empty() && hackathons.is_empty() {
+        return Ok(pattern);
+    }
+    Ok(Box::new(CrunchPattern::new(pattern, windows).with_hackathons(hackathons)))
+}
+
+fn create_pattern(name: &str) -> Result<Box<dyn Pattern>> {
+    if name.ends_with(".toml") && Path::new(name).is_file() {
+        let config = PatternConfig::from_toml(&fs::read_to_string(name)?)?;
+        return Ok(Box::new(ConfigurablePattern::new(config)));
+    }
+    PatternRegistry::create(name)
+}
+
+// `simulate` subcommand: patterns are random, so a single preview says
+// little about what a run will produce
+fn simulate_pattern(name: &str, runs: usize, days: i64) -> Result<()> {
+    if runs == 0 || days < 1 {
+        return Err(GitHubGridError::Config("simulate needs at least one run of at least one day".to_string()));
+    }
+    let pattern = create_pattern(name)?;
+    let end = Local::now().date_naive();
+    let start = end - chrono::Duration::days(days - 1);
+    let samples: Vec<PlanStats> = (0..runs)
+        .map(|_| PlanStats::measure(&pattern.generate(start, end), start, end))
+        .collect();
     
-    /// Target total commits for the year (overrides pattern)
-    #[arg(long)]
-    target_total: Option<u32>,
+    let row = |label: &str, values: Vec<f64>, format: &dyn Fn(f64) -> String| {
+        let d = Distribution::of(&values);
+        println!("  {:<16}{:>10}{:>10}{:>10}{:>10}", label, format(d.mean), format(d.p5), format(d.median), format(d.p95));
+    };
+    let count = |v: f64| format!("{:.0}", v);
+    let percent = |v: f64| format!("{:.1}%", v * 100.0);
     
-    /// Pattern to use
-    #[arg(short, long, default_value = "realistic")]
-    pattern: String,
+    println!("🎲 {} over {} days, {} runs\n", name, days, runs);
+    println!("  {:<16}{:>10}{:>10}{:>10}{:>10}", "", "mean", "p5", "median", "p95");
+    row("Total commits", samples.iter().map(|s| s.total).collect(), &count);
+    row("Active days", samples.iter().map(|s| s.active_share * days as f64).collect(), &count);
+    row("Active share", samples.iter().map(|s| s.active_share).collect(), &percent);
+    row("Weekend share", samples.iter().map(|s| s.weekend_share).collect(), &percent);
+    row("Longest streak", samples.iter().map(|s| s.longest_streak).collect(), &count);
+    Ok(())
+}
+
+// `fit` subcommand: searches over a year-long window and writes the best
+// config with the stats it's expected to produce as comments
+fn fit_pattern(constraints: &Constraints, candidates: usize, output: Option<&Path>) -> Result<()> {
+    let end = Local::now().date_naive();
+    let start = end - chrono::Duration::days(364);
+    eprintln!("🔧 Trying {} candidate configurations...", candidates);
+    let fit = fit::fit(constraints, start, end, candidates)?;
     
-    /// Show preview without committing
-    #[arg(long)]
-    dry_run: bool,
+    let stats = fit.stats;
+    let mut report = vec![format!("~{:.0} commits/year", stats.total)];
+    report.push(format!("{:.0}% active days", stats.active_share * 100.0));
+    report.push(format!("{:.0}% on weekends", stats.weekend_share * 100.0));
+    report.push(format!("longest streak ~{:.0} days", stats.longest_streak));
+    let file = format!(
+        "# Fitted by `github-grid fit` (error {:.3}); expect {}\n{}",
+        fit.error,
+        report.join(", "),
+        fit.config.to_toml()?,
+    );
     
-    #[command(subcommand)]
-    command: Option<Commands>,
+    eprintln!("📐 Best fit: {}", report.join(", "));
+    print_fit_misses(constraints, &stats);
+    match output {
+        Some(path) => {
+            fs::write(path, file)?;
+            eprintln!("✅ Wrote {} (use it with --pattern {})", path.display(), path.display());
+        }
+        None => print!("{}", file),
+    }
+    Ok(())
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Show available patterns
-    Patterns,
-    /// Preview commits for date range
-    Preview {
-        #[arg(long)]
-        start: String,
-        #[arg(long)]
-        end: String,
-        #[arg(short, long, default_value = "realistic")]
-        pattern: String,
-    },
-    /// Initialize or reset a private GitHub repo for commit patterns
-    Init {
-        /// Repository name (defaults to username-grid)
-        #[arg(short, long)]
-        name: Option<String>,
-        /// Force recreate if repo exists
-        #[arg(long)]
-        force: bool,
-        /// Local directory to clone to (defaults to ~/github/repo-name)
-        #[arg(long)]
-        local_dir: Option<String>,
-    },
+// Points out constraints the best config still misses noticeably, with the
+// post-processing flag that enforces them exactly
+fn print_fit_misses(constraints: &Constraints, stats: &PlanStats) {
+    if let Some(share) = constraints.active_share && (stats.active_share - share).abs() > 0.05 {
+        eprintln!("⚠️  Active days are off by {:.0} points; add --active-days {:.0}% when running", (stats.active_share - share).abs() * 100.0, share * 100.0);
+    }
+    if let Some(share) = constraints.weekend_share && (stats.weekend_share - share).abs() > 0.03 {
+        eprintln!("⚠️  Weekend share is off by {:.0} points; add --weekend-ratio {} when running", (stats.weekend_share - share).abs() * 100.0, share);
+    }
+    if let Some(max) = constraints.max_streak && stats.longest_streak > max as f64 {
+        eprintln!("⚠️  Streaks still run to ~{:.0} days, over the {} asked for", stats.longest_streak, max);
+    }
+}
+
+fn confirm_execution(commit_count: usize, days: i64, repo_path: &Path) -> Result<bool> {
+    // Prompt on stderr so JSON progress on stdout stays clean
+    eprintln!();
+    eprintln!("📋 Plan: {} commits over {} days to {}", commit_count, days, repo_path.display());
+    confirm_prompt("Proceed?")
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+const VERIFY_POLL_INTERVAL: StdDuration = StdDuration::from_secs(15);
+
+// Checks the pushed days against the live contribution graph. Problems are
+// reported as warnings; the commits themselves were already pushed. Returns
+// how many pushed commits the graph accounts for, if it could be checked.
+fn verify_pushed(cli: &Cli, expected: &BTreeMap<NaiveDate, u32>, reporter: &ProgressReporter) -> Option<usize> {
+    if expected.is_empty() {
+        return None;
+    }
+    reporter.info("🔎 Verifying the contribution graph...");
     
-    match cli.command {
-        Some(Commands::Patterns) => {
-            show_patterns();
-            return Ok(());
+    let client = match connect(cli) {
+        Ok(client) => client,
+        Err(e) => {
+            reporter.warn(&format!("Skipping verification: {}", e));
+            return None;
         }
-        Some(Commands::Preview { start, end, pattern }) => {
-            let start_date = NaiveDate::parse_from_str(&start, "%Y-%m-%d")?;
-            let end_date = NaiveDate::parse_from_str(&end, "%Y-%m-%d")?;
-            preview_pattern(&pattern, start_date, end_date)?;
-            return Ok(());
+    };
+    
+    let result = verify::wait_for_calendar(client.as_ref(), expected, cli.verify_wait, VERIFY_POLL_INTERVAL, |attempt, remaining| {
+        if remaining > 0 && attempt > 1 {
+            reporter.info(&format!("   {} day(s) not showing yet, re-checking...", remaining));
         }
-        Some(Commands::Init { name, force, local_dir }) => {
-            init_github_repo(name, force, local_dir)?;
-            return Ok(());
+    });
+    
+    match result {
+        Ok(check) if check.discrepancies.is_empty() => {
+            reporter.info(&format!("✅ All {} days show up on the contribution graph", expected.len()));
+            Some(verify::attributed(expected, &check.discrepancies))
+        }
+        Ok(check) => {
+            report_discrepancies(cli, &check, expected.len(), reporter);
+            Some(verify::attributed(expected, &check.discrepancies))
+        }
+        Err(e) => {
+            reporter.warn(&format!("Could not verify the contribution graph: {}", e));
+            None
         }
-        None => {}
     }
+}
+
+// `compare-users` subcommand: fetches both public graphs over the year (or
+// the last 365 days) and prints them with a per-day diff and stats
+fn compare_users(cli: &Cli, user_a: &str, user_b: &str, year: Option<i32>) -> Result<()> {
+    let today = Local::now().date_naive();
+    let (start, end) = match year {
+        Some(year) => {
+            let start = NaiveDate::from_ymd_opt(year, 1, 1)
+                .filter(|start| *start <= today)
+                .ok_or_else(|| GitHubGridError::Config(format!("Year {} has no contributions yet", year)))?;
+            (start, NaiveDate::from_ymd_opt(year, 12, 31).unwrap().min(today))
+        }
+        None => (today - chrono::Duration::days(364), today),
+    };
     
-    // Use default path if not specified
-    let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let repo_path = match cli.repo {
-        Some(path) => path,
+    let client = connect(cli)?;
+    println!("🔎 Fetching contribution graphs for {} and {} ({} to {})...\n", user_a, user_b, start, end);
+    let days_a = client.user_contribution_calendar(user_a, start, end)?;
+    let days_b = client.user_contribution_calendar(user_b, start, end)?;
+    print!("{}", compare::render((user_a, &days_a), (user_b, &days_b), start, end));
+    Ok(())
+}
+
+// `verify` subcommand: expected counts come from the [AutoGen] commits now
+// on the branch within the run's range. Fails if days are still missing.
+fn verify_run(cli: &Cli, run_id: Option<u32>, wait: StdDuration) -> Result<()> {
+    let record = match run_id {
+        Some(id) => history::find(id)?,
         None => {
-            // Get username dynamically for default path
-            let github = GitHubClient::new()?;
-            let username = github.username();
-            PathBuf::from(format!("{}/github/{}-grid", home_dir, username))
+            let repo = cli.repo.as_ref().map(|path| history::canonical_repo(&path.display().to_string()));
+            history::load()?
+                .into_iter()
+                .rev()
+                .find(|r| !r.dry_run && r.pushed > 0 && (repo.is_none() || r.repo == repo))
+                .ok_or_else(|| GitHubGridError::Config("No pushed runs in history to verify".to_string()))?
         }
     };
+    let (Some(start), Some(end), Some(repo_path)) = (record.start, record.end, &record.repo) else {
+        return Err(GitHubGridError::Config(format!("Run #{} has no recorded range", record.id)));
+    };
     
-    let repo = Repository::open(&repo_path)?;
-    let mut git_ops = GitOperations::new(repo);
+    let git_ops = GitOperations::new(Repository::open(repo_path)?).with_branch(&record.branch);
+    let expected = git_ops.autogen_days(start, end)?;
+    println!("🔎 Verifying run #{}: {} days with generated commits between {} and {}", record.id, expected.len(), start, end);
     
-    let (start_date, end_date) = determine_date_range(&mut git_ops, cli.start, cli.end)?;
+    let reporter = ProgressReporter::new(cli.progress);
+    let client = connect(cli)?;
+    let check = verify::wait_for_calendar(client.as_ref(), &expected, wait, VERIFY_POLL_INTERVAL, |attempt, remaining| {
+        if remaining > 0 && !wait.is_zero() {
+            println!("   Attempt {}: {} day(s) not showing yet", attempt, remaining);
+        }
+    })?;
     
-    println!("Generating commits from {} to {}", start_date, end_date);
+    if check.discrepancies.is_empty() {
+        println!("✅ All {} days show up on the contribution graph", expected.len());
+        return Ok(());
+    }
+    report_discrepancies(cli, &check, expected.len(), &reporter);
+    Err(GitHubGridError::Repository(format!(
+        "{} day(s) are missing from the contribution graph", check.discrepancies.len()
+    )))
+}
+
+// Plans sampled to estimate what a pattern produces on average
+const CHECK_SAMPLES: usize = 20;
+
+// `check`: a monitoring probe for scheduled setups. Counts run through
+// yesterday, since today's commits may not have been made or shown yet.
+fn check_drift(cli: &Cli, threshold: verify::DriftThreshold, since: Option<NaiveDate>) -> Result<()> {
+    let through = Local::now().date_naive() - chrono::Duration::days(1);
+    let since = since.unwrap_or_else(|| NaiveDate::from_ymd_opt(through.year(), 1, 1).unwrap());
+    if since > through {
+        println!("✅ Nothing to check yet: counting starts {}", since);
+        return Ok(());
+    }
+    if since.year() != through.year() {
+        return Err(GitHubGridError::Config(format!("--since must be in {}, the year being checked", through.year())));
+    }
     
-    let (_pattern_name, commits) = if let Some(target_total) = cli.target_total {
-        // Target-based generation
-        let current_year = start_date.year();
-        let existing_commits = count_existing_commits(&git_ops, current_year)?;
-        let commits_needed = target_total.saturating_sub(existing_commits);
-        let days_in_range = (end_date - start_date).num_days() + 1;
-        
-        println!("🎯 Target: {} commits total for {}", target_total, current_year);
-        println!("📊 Existing: {} commits", existing_commits);
-        println!("➕ Generating: ~{} commits over {} days", commits_needed, days_in_range);
-        
-        if commits_needed == 0 {
-            println!("✅ Target already reached!");
-            return Ok(());
+    let (expected, basis) = match target_for_year(cli, through.year())? {
+        Some(target) => {
+            // Spread over the rest of the year from `since`, as catch-up paces it
+            let (_, year_end) = dates::year_range(through.year())?;
+            let elapsed = (through - since).num_days() + 1;
+            let span = (year_end - since).num_days() + 1;
+            let expected = (target as f64 * elapsed as f64 / span as f64).round() as u32;
+            (expected, format!("target {} pro rata", target))
+        }
+        None => {
+            let reporter = ProgressReporter::new(cli.progress);
+            let pattern = run_pattern(cli, &reporter)?;
+            let days_off = days_off(cli, &reporter)?;
+            let days = plan_days(&[(since, through)], &days_off);
+            let mut total = 0;
+            for _ in 0..CHECK_SAMPLES {
+                total += shaped_plan(cli, pattern.generate(since, through), &days_off, &days)?.len();
+            }
+            ((total as f64 / CHECK_SAMPLES as f64).round() as u32, format!("'{}' pattern average", cli.pattern))
         }
-        
-        let config = calibrate_pattern_for_target(commits_needed, days_in_range);
-        let pattern_impl = ConfigurablePattern::new(config);
-        let commits = pattern_impl.generate(start_date, end_date);
-        
-        (format!("target-{}", target_total), commits)
-    } else {
-        // Traditional pattern-based generation
-        println!("Pattern: {}", cli.pattern);
-        let pattern = create_pattern(&cli.pattern)?;
-        let commits = pattern.generate(start_date, end_date);
-        (cli.pattern.clone(), commits)
     };
     
-    println!("Generated {} commits", commits.len());
+    let client = connect(cli)?;
+    let actual: u32 = client.contribution_calendar(since, through)?.values().sum();
+    let behind = expected.saturating_sub(actual);
+    let allowed = threshold.allowed(expected);
     
-    if cli.dry_run {
-        show_commit_summary(&commits);
-        return Ok(());
+    println!("📈 Contributions from {} through {}:", since, through);
+    println!("   Expected  {:>7}   ({})", expected, basis);
+    println!("   On graph  {:>7}", actual);
+    println!("   Behind    {:>7}   ({} allowed)", behind, allowed);
+    if behind > allowed {
+        return Err(GitHubGridError::Drift(format!(
+            "{} of {} expected contributions are missing, over the {} allowed", behind, expected, allowed
+        )));
     }
-    
-    execute_commits(&mut git_ops, commits)?;
-    
+    println!("✅ On track");
     Ok(())
 }
 
-fn show_patterns() {
-    println!("Available patterns:");
-    println!("\nActivity levels (commits/year):");
-    println!("  casual      - Weekend warrior, occasional PRs (~300/year)");
-    println!("  realistic   - Professional developer activity (~1,200/year)");
-    println!("  active      - Multiple projects, good practices (~2,500/year)");
-    println!("  maintainer  - Managing repos, reviewing PRs (~5,000/year)");
-    println!("  hyperactive - Startup pace, heavy open source (~12,000/year)");
-    println!("  extreme     - AI-assisted development (~20,000+/year)");
-    println!("\nLegacy patterns:");
-    println!("  steady      - Consistent daily activity");
-    println!("  sporadic    - Irregular bursts of activity");
-    println!("  contractor  - Mon-Fri focused with occasional weekends");
-}
-
-fn preview_pattern(pattern_name: &str, start: NaiveDate, end: NaiveDate) -> Result<()> {
-    let pattern = create_pattern(pattern_name)?;
-    let commits = pattern.generate(start, end);
-    
-    show_commit_calendar(&commits, start, end);
-    show_commit_summary(&commits);
-    
-    Ok(())
+// A pattern's commits with the run's day filters and shaping applied, as
+// plan_run would, minus the parts that depend on the repo or the clock
+fn shaped_plan(cli: &Cli, commits: Vec<CommitInfo>, days_off: &DaysOff, days: &[NaiveDate]) -> Result<Vec<CommitInfo>> {
+    let mut commits = thin_holiday_eves(cli, apply_exclusions(commits, days_off), days_off);
+    if let Some(ratio) = weekend_ratio(cli)? {
+        commits = patterns::rebalance_weekends(commits, ratio, days, None);
+    }
+    if let Some(share) = cli.active_days {
+        commits = patterns::set_active_days(commits, (days.len() as f64 * share).round() as usize, days, None);
+    }
+    if let Some(min) = min_per_day(cli)? {
+        commits = patterns::clamp_per_day(commits, min, cli.max_per_day, days, &BTreeMap::new(), None).0;
+    }
+    Ok(commits)
 }
 
-fn show_commit_calendar(commits: &[CommitInfo], start: NaiveDate, end: NaiveDate) {
-    println!("\n📅 Commit Calendar:");
+fn report_discrepancies(cli: &Cli, check: &verify::CalendarCheck, days: usize, reporter: &ProgressReporter) {
+    let discrepancies = &check.discrepancies;
+    reporter.warn(&format!("{} of {} pushed days are missing contributions on the graph:", discrepancies.len(), days));
+    for d in discrepancies.iter().take(10) {
+        reporter.info(&format!("   {}: expected at least {}, graph shows {}", d.date, d.expected, d.actual));
+    }
+    if discrepancies.len() > 10 {
+        reporter.info(&format!("   ... and {} more", discrepancies.len() - 10));
+    }
     
-    let mut current = start;
-    while current <= end {
-        let count = commits.iter()
-            .filter(|c| c.date.date_naive() == current)
-            .count();
-            
-        let symbol = match count {
-            0 => "░",
-            1..=3 => "▓",
-            4..=10 => "█",
-            _ => "🔥",
-        };
-        
-        if current.weekday().number_from_monday() == 1 {
-            println!();
-            print!("{:>10} ", current.format("%b %d"));
-        }
-        
-        print!("{}", symbol);
-        current = current.succ_opt().unwrap();
+    // The usual culprits: an unverified commit email or hidden private contributions
+    let email = match &cli.author_email {
+        Some(email) => Ok(email.clone()),
+        None => git_ops::author_identity().map(|(_, email)| email),
+    };
+    if let (Ok(email), Some(emails)) = (email, &check.emails)
+        && !emails.iter().any(|e| e.eq_ignore_ascii_case(&email))
+    {
+        reporter.info(&format!("💡 Commit email {} isn't on your GitHub account, so commits aren't attributed to you", email));
+        reporter.info("💡 Re-credit the pushed commits with `github-grid scrub --new-email <address on your account>`");
     }
-    println!("\n\nLegend: ░=0 ▓=1-3 █=4-10 🔥=10+ commits\n");
+    reporter.info("💡 For private repos, enable \"Private contributions\" in your profile settings");
+    reporter.info("💡 The graph can also lag by several minutes after a push");
 }
 
-fn show_commit_summary(commits: &[CommitInfo]) {
-    let total = commits.len();
-    let avg_per_day = if total > 0 {
-        commits.iter()
-            .map(|c| c.date.date_naive())
-            .collect::<std::collections::HashSet<_>>()
-            .len()
-    } else {
-        0
-    };
-    
-    println!("Summary:");
-    println!("  Total commits: {}", total);
-    println!("  Active days: {}", avg_per_day);
-    if avg_per_day > 0 {
-        println!("  Avg commits/day: {:.1}", total as f64 / avg_per_day as f64);
+fn confirm_prompt(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Err(GitHubGridError::Config(
+            "Refusing to continue without confirmation; pass --yes to run non-interactively".to_string()
+        ));
     }
     
-    let weekend_commits = commits.iter()
-        .filter(|c| {
-            let weekday = c.date.weekday();
-            weekday == chrono::Weekday::Sat || weekday == chrono::Weekday::Sun
-        })
-        .count();
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
     
-    println!("  Weekend commits: {} ({:.1}%)", weekend_commits, 
-             weekend_commits as f64 / total as f64 * 100.0);
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
 
-fn determine_date_range(
-    git_ops: &mut GitOperations,
-    start: Option<String>,
-    end: Option<String>,
-) -> Result<(NaiveDate, NaiveDate)> {
-    let end_date = match end {
-        Some(date_str) => NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?,
-        None => Local::now().date_naive(),
-    };
-    
-    let start_date = match start {
-        Some(date_str) => NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?,
+// Deleting a repo can't be undone, so like GitHub itself this wants the
+// full owner/name typed back (or passed with --confirm); --yes isn't enough
+fn confirm_deletion(slug: &str, confirm: Option<&str>) -> Result<()> {
+    let typed = match confirm {
+        Some(given) => given.to_string(),
+        None if !io::stdin().is_terminal() => {
+            return Err(GitHubGridError::Config(format!(
+                "Refusing to delete {} without confirmation; pass --confirm {} to run non-interactively", slug, slug
+            )));
+        }
         None => {
-            match git_ops.get_latest_autogen_commit()? {
-                Some(last_commit) => last_commit.date_naive() + chrono::Duration::days(1),
-                None => end_date - chrono::Duration::days(365),
-            }
+            eprintln!("⚠️  This permanently deletes {} and its local clone.", slug);
+            eprint!("Type {} to confirm: ", slug);
+            io::stderr().flush()?;
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer)?;
+            answer.trim().to_string()
         }
     };
-    
-    Ok((start_date, end_date))
-}
-
-fn create_pattern(name: &str) -> Result<Box<dyn Pattern>> {
-    match name {
-        // Legacy patterns
-        "realistic" => Ok(Box::new(RealisticPattern::new())),
-        "steady" => Ok(Box::new(SteadyPattern::new())),
-        "sporadic" => Ok(Box::new(SporadicPattern::new())),
-        "contractor" => Ok(Box::new(ContractorPattern::new())),
-        // Activity-level patterns
-        "casual" => Ok(Box::new(CasualPattern::new())),
-        "active" => Ok(Box::new(ActivePattern::new())),
-        "maintainer" => Ok(Box::new(MaintainerPattern::new())),
-        "hyperactive" => Ok(Box::new(HyperactivePattern::new())),
-        "extreme" => Ok(Box::new(ExtremePattern::new())),
-        _ => Err(GitHubGridError::Config(format!("Unknown pattern: {}", name))),
+    if typed != slug {
+        return Err(GitHubGridError::Config(format!(
+            "Confirmation '{}' doesn't match {}; nothing was deleted", typed, slug
+        )));
     }
+    Ok(())
 }
 
 fn execute_commits(
     git_ops: &mut GitOperations,
     commits: Vec<CommitInfo>,
+    cli: &Cli,
+    reporter: &mut ProgressReporter,
 ) -> Result<()> {
-    let pb = ProgressBar::new(commits.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap(),
-    );
+    let total = commits.len();
+    execute_stream(git_ops, commits.into_iter(), Some(total), cli, reporter)
+}
+
+// Creates and pushes commits as the iterator yields them, then reconciles
+// what landed against the plan, even if the run failed partway. `total` is
+// None when a streamed plan's size isn't known in advance.
+fn execute_stream(
+    git_ops: &mut GitOperations,
+    commits: impl Iterator<Item = CommitInfo>,
+    total: Option<usize>,
+    cli: &Cli,
+    reporter: &mut ProgressReporter,
+) -> Result<()> {
+    let mut created = CreatedCommits::default();
+    let result = create_and_push(git_ops, commits, total, cli, reporter, &mut created);
+    let attributed = match result {
+        Ok(()) if !cli.no_push && !cli.no_verify => verify_pushed(cli, &created.days, reporter),
+        _ => None,
+    };
+    if let Some(on_graph) = attributed {
+        reporter.verified(on_graph);
+    }
+    report_reconciliation(git_ops, total, &created, attributed, cli, reporter);
+    result
+}
+
+// The commits a run has made so far
+#[derive(Default)]
+struct CreatedCommits {
+    oids: HashSet<git2::Oid>,
+    days: BTreeMap<NaiveDate, u32>,
+}
+
+fn create_and_push(
+    git_ops: &mut GitOperations,
+    commits: impl Iterator<Item = CommitInfo>,
+    total: Option<usize>,
+    cli: &Cli,
+    reporter: &mut ProgressReporter,
+    created: &mut CreatedCommits,
+) -> Result<()> {
+    reporter.start_commits(total, (!cli.no_push).then_some(cli.batch_size));
     
     let mut batch_count = 0;
-    const BATCH_SIZE: usize = 500;
+    let mut batch_number = 0;
+    let mut pushed_total = 0;
+    let batch_size = cli.batch_size.max(1);
     
-    for commit in commits {
-        pb.set_message(format!("Committing {}", commit.date.format("%Y-%m-%d %H:%M")));
-        
-        git_ops.create_commit(&commit)?;
+    for (index, commit) in commits.enumerate() {
+        let oid = git_ops.create_commit(&commit)?;
+        reporter.committed(&commit, index, total, &oid.to_string());
+        created.oids.insert(oid);
+        *created.days.entry(commit.date.date_naive()).or_insert(0) += 1;
         
         batch_count += 1;
-        if batch_count >= BATCH_SIZE {
-            pb.set_message("Pushing batch...".to_string());
-            git_ops.push_commits()?;
+        if batch_count >= batch_size && !cli.no_push {
+            batch_number += 1;
+            reporter.batch(batch_number, batch_count, false);
+            git_ops.push_commits_with_progress(|progress| reporter.push_progress(progress))?;
+            pushed_total += batch_count;
+            reporter.pushed(batch_number, pushed_total);
             batch_count = 0;
         }
-        
-        pb.inc(1);
+    }
+    
+    git_ops.sync_worktree()?;
+    let readme_updated = cli.readme_stats && update_readme_stats(git_ops, reporter)?;
+    
+    if cli.no_push {
+        reporter.finish();
+        reporter.info(&format!("📦 Created {} commits locally; run `github-grid push` to publish them", created.oids.len()));
+        return Ok(());
     }
     
     if batch_count > 0 {
-        pb.set_message("Final push...".to_string());
+        batch_number += 1;
+        reporter.batch(batch_number, batch_count, true);
+        git_ops.push_commits_with_progress(|progress| reporter.push_progress(progress))?;
+        pushed_total += batch_count;
+        reporter.pushed(batch_number, pushed_total);
+    }
+    if readme_updated {
         git_ops.push_commits()?;
     }
     
-    pb.finish_with_message("✅ All commits created successfully!");
+    reporter.finish();
     Ok(())
 }
 
+// Planned vs created vs on the branch vs pushed vs on the graph, flagging
+// where commits went missing
+fn report_reconciliation(
+    git_ops: &GitOperations,
+    planned: Option<usize>,
+    created: &CreatedCommits,
+    attributed: Option<usize>,
+    cli: &Cli,
+    reporter: &ProgressReporter,
+) {
+    let count = |counted: Result<usize>| counted.unwrap_or_else(|e| {
+        reporter.warn(&format!("Could not count commits for the run report: {}", e));
+        0
+    });
+    let report = verify::Reconciliation {
+        planned: planned.unwrap_or(created.oids.len()),
+        created: created.oids.len(),
+        on_branch: count(git_ops.count_on_branch(&created.oids)),
+        pushed: (!cli.no_push).then(|| count(git_ops.count_on_remote(&created.oids))),
+        attributed,
+    };
+    reporter.info("📊 Run report:");
+    for (stage, count, lost) in report.stages() {
+        let flag = match lost {
+            0 => String::new(),
+            lost => format!("   ⚠️  {} fewer", lost),
+        };
+        reporter.info(&format!("   {:<13}{:>7}{}", stage, count, flag));
+    }
+    let lost: Vec<String> = report.stages().into_iter()
+        .filter(|(_, _, lost)| *lost > 0)
+        .map(|(stage, _, lost)| format!("{} fewer at \"{}\"", lost, stage.to_lowercase()))
+        .collect();
+    if !lost.is_empty() {
+        reporter.warn(&format!("Commits went missing: {}", lost.join(", ")));
+    } else if report.attributed.is_some() {
+        reporter.info("✅ Every planned commit made it onto the graph");
+    }
+}
+
+// Regenerates the README stats section from the branch history and commits
+// it if anything changed. Returns whether a commit was made.
+fn update_readme_stats(git_ops: &mut GitOperations, reporter: &ProgressReporter) -> Result<bool> {
+    let Some(workdir) = git_ops.repo().workdir().map(Path::to_path_buf) else {
+        return Ok(false);
+    };
+    
+    let mut days = BTreeMap::new();
+    for commit in git_ops.history()? {
+        let local = commit.seconds + commit.offset_minutes as i64 * 60;
+        if let Some(date) = chrono::DateTime::from_timestamp(local, 0) {
+            *days.entry(date.date_naive()).or_insert(0u32) += 1;
+        }
+    }
+    
+    let path = workdir.join("README.md");
+    let readme = fs::read_to_string(&path).unwrap_or_default();
+    let updated = readme_stats::update(&readme, &readme_stats::render(&days, Local::now().date_naive()));
+    if updated == readme {
+        return Ok(false);
+    }
+    fs::write(&path, updated)?;
+    git_ops.commit_paths(&[Path::new("README.md")], git_ops::STATS_MESSAGE)?;
+    reporter.info("📊 README stats updated");
+    Ok(true)
+}
+
+// What `init --force` does with a repo that already exists
+enum Recreate {
+    Delete { confirm: Option<String> },
+    Archive,
+}
+
 fn init_github_repo(
+    cli: &Cli,
+    github: &dyn Forge,
     name: Option<String>,
-    force: bool,
+    force: Option<Recreate>,
     local_dir: Option<String>,
-) -> Result<()> {
+    clone: &CloneOptions,
+) -> Result<String> {
     println!("🚀 Initializing GitHub repository for commit patterns...");
     
-    // Create GitHub client
-    let github = GitHubClient::new()?;
     let username = github.username();
     println!("📋 GitHub username: {}", username);
     
@@ -340,7 +3557,26 @@ fn init_github_repo(
     let repo_exists = github.repo_exists(&repo_name)?;
     
     if repo_exists {
-        if force {
+        if let Some(Recreate::Archive) = force {
+            let suffix = format!("archived-{}", Local::now().format("%Y%m%d-%H%M%S"));
+            let archived_name = format!("{}-{}", repo_name, suffix);
+            println!("📦 Repository exists, archiving it as {} due to --archive...", archived_name);
+            github.archive_repo(&repo_name, &archived_name)?;
+            println!("✅ Archived {}", github.repo_url(&archived_name));
+            // Move the old clone aside too, so the new one can take its place
+            if PathBuf::from(&local_path).exists() {
+                let archived_path = format!("{}-{}", local_path, suffix);
+                fs::rename(&local_path, &archived_path)?;
+                println!("📁 Moved local clone to {}", archived_path);
+            }
+        } else if let Some(Recreate::Delete { confirm }) = force {
+            confirm_deletion(&format!("{}/{}", username, repo_name), confirm.as_deref())?;
+            // A mirror first, so forcing against the wrong name is recoverable;
+            // no backup, no deletion
+            let backup_path = format!("{}-backup-{}.git", local_path, Local::now().format("%Y%m%d-%H%M%S"));
+            github.mirror_repo(&repo_name, &backup_path)?;
+            println!("💾 Backed up {}/{} to {}", username, repo_name, backup_path);
+            println!("   Restore with: git -C {} push --mirror <new-remote-url>", backup_path);
             println!("⚠️  Repository exists, deleting due to --force flag...");
             // Remove local directory first to avoid clone conflicts
             if PathBuf::from(&local_path).exists() {
@@ -349,27 +3585,27 @@ fn init_github_repo(
             }
             github.delete_repo(&repo_name)?;
         } else {
-            println!("✅ Repository already exists: https://github.com/{}/{}", username, repo_name);
+            println!("✅ Repository already exists: {}", github.repo_url(&repo_name));
             println!("💡 Use --force to recreate or update the existing repo");
             
             // Check if local clone exists
             if PathBuf::from(&local_path).exists() {
                 println!("📁 Local clone already exists at: {}", local_path);
                 println!("🎯 Ready to use!");
-                return Ok(());
+                return Ok(local_path);
             } else {
                 println!("📥 Cloning existing repository...");
-                github.clone_repo(&repo_name, &local_path)?;
+                github.clone_repo(&repo_name, &local_path, clone)?;
                 
                 // Check if repo needs initialization (empty repo)
                 let repo = Repository::open(&local_path)?;
                 if repo.is_empty()? {
                     println!("🔧 Repository is empty, initializing...");
-                    initialize_repo(&repo, &local_path)?;
+                    initialize_repo(cli, &repo, &local_path)?;
                 }
                 
                 println!("🎯 Ready to use!");
-                return Ok(());
+                return Ok(local_path);
             }
         }
     }
@@ -380,14 +3616,14 @@ fn init_github_repo(
     
     // Clone the repository locally
     println!("📥 Cloning repository...");
-    github.clone_repo(&repo_name, &local_path)?;
+    github.clone_repo(&repo_name, &local_path, clone)?;
     let repo = Repository::open(&local_path)?;
     
     // Initialize with empty commit
-    initialize_repo(&repo, &local_path)?;
+    initialize_repo(cli, &repo, &local_path)?;
     
     println!("✅ Repository setup complete!");
-    println!("🌐 GitHub: https://github.com/{}/{}", username, repo_name);
+    println!("🌐 GitHub: {}", github.repo_url(&repo_name));
     println!("📁 Local: {}", local_path);
     println!();
     println!("🎯 Usage:");
@@ -395,15 +3631,16 @@ fn init_github_repo(
     println!("  ./target/release/github-grid --pattern active");
     println!("  ./target/release/github-grid --dry-run");
     
-    Ok(())
+    Ok(local_path)
 }
 
-fn initialize_repo(repo: &Repository, local_path: &str) -> Result<()> {
+const GRID_README: &str = "# GitHub Contribution Grid\n\nThis repository contains generated commit patterns for GitHub contribution graphs.\n";
+
+fn initialize_repo(cli: &Cli, repo: &Repository, local_path: &str) -> Result<()> {
     let repo_path = PathBuf::from(local_path);
     
     // Create initial README
-    let readme_content = "# GitHub Contribution Grid\n\nThis repository contains generated commit patterns for GitHub contribution graphs.\n";
-    fs::write(repo_path.join("README.md"), readme_content)?;
+    fs::write(repo_path.join("README.md"), GRID_README)?;
     
     // Stage the README
     let mut index = repo.index()?;
@@ -428,80 +3665,26 @@ fn initialize_repo(repo: &Repository, local_path: &str) -> Result<()> {
     )?;
     
     // Push to GitHub using our git operations
-    let mut git_ops = GitOperations::new(Repository::open(local_path)?);
+    println!("🚀 Pushing commits to GitHub...");
+    let mut git_ops = GitOperations::new(Repository::open(local_path)?)
+        .with_proxy(cli.proxy.clone())
+        .with_push_token(push_token(cli));
     git_ops.push_commits()?;
     
     Ok(())
 }
 
-fn count_existing_commits(git_ops: &GitOperations, year: i32) -> Result<u32> {
-    let repo_path = git_ops.repo().workdir().unwrap();
-    let output = std::process::Command::new("git")
-        .current_dir(repo_path)
-        .args(&[
-            "log",
-            "--oneline",
-            &format!("--since={}-01-01", year),
-            &format!("--until={}-12-31", year),
-        ])
-        .output()
-        .map_err(|e| GitHubGridError::Io(e))?;
-    
-    if !output.status.success() {
-        return Ok(0); // Empty repo or no commits in range
-    }
-    
-    let commit_lines = String::from_utf8_lossy(&output.stdout);
-    let count = commit_lines.lines().count() as u32;
-    
-    Ok(count)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TargetScope {
+    /// Everything on the branch, so real work fills part of the target
+    All,
+    /// Only [AutoGen] commits; real activity comes on top of the target
+    Autogen,
 }
 
-
-fn calibrate_pattern_for_target(commits_needed: u32, days_in_range: i64) -> PatternConfig {
-    let avg_per_day = commits_needed as f64 / days_in_range as f64;
-    
-    // Choose intensity level based on required daily average
-    // Calibrated for aggressive spike system: 0.7x accounts for frequent high spikes  
-    let target_avg = avg_per_day * 0.7;  // Conservative: two-tier spikes significantly boost output
-    
-    let intensity = if target_avg < 5.0 {
-        IntensityLevel::Casual
-    } else if target_avg < 15.0 {
-        IntensityLevel::Active  
-    } else if target_avg < 30.0 {
-        IntensityLevel::Maintainer
-    } else if target_avg < 50.0 {
-        IntensityLevel::Hyperactive
-    } else {
-        IntensityLevel::Extreme
-    };
-    
-    // Create pattern config with enhanced variance for target hitting
-    // More realistic vacation frequencies
-    let vacation_freq = match intensity {
-        IntensityLevel::Casual => 0.05,     // More time off
-        IntensityLevel::Active => 0.035,    // Regular breaks
-        IntensityLevel::Maintainer => 0.025, // Still needs breaks
-        IntensityLevel::Hyperactive => 0.02,  // Less but still important
-        IntensityLevel::Extreme => 0.015,    // Rare but necessary
-    };
-    
-    // More aggressive spike probability for dramatic variance
-    let spike_prob = match intensity {
-        IntensityLevel::Casual => 0.25,
-        IntensityLevel::Active => 0.32,
-        IntensityLevel::Maintainer => 0.38,
-        IntensityLevel::Hyperactive => 0.42,
-        IntensityLevel::Extreme => 0.48,
-    };
-    
-    PatternConfig {
-        intensity,
-        use_weekly_rhythm: true,
-        vacation_frequency: vacation_freq,
-        vacation_duration: (2, 8),  // Longer, more realistic breaks
-        spike_probability: spike_prob,
-        spike_multiplier: 3.5,  // Much more dramatic spikes for release/deadline days
+fn counted_toward_target(existing: &CommitCounts, scope: TargetScope) -> u32 {
+    match scope {
+        TargetScope::All => existing.autogen + existing.real,
+        TargetScope::Autogen => existing.autogen,
     }
 }
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDate;
+use rand::{rng, Rng};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use crate::error::{GitHubGridError, Result};
+
+// Every generated message carries this so later runs can find their commits
+pub const AUTOGEN_PREFIX: &str = "[AutoGen]";
+
+// Ticket and issue numbers grow from this date at a steady rate, so runs
+// over different ranges (and daily catch-ups) stay consistent
+const NUMBERING_EPOCH: (i32, u32, u32) = (2015, 1, 1);
+const TICKETS_PER_DAY: f64 = 0.8;
+const FIRST_TICKET: u32 = 100;
+// Commits on one day spread over this many recently opened tickets
+const OPEN_TICKETS: u32 = 25;
+const ISSUES_PER_DAY: f64 = 0.3;
+const OPEN_ISSUES: u32 = 15;
+
+// Built-in categories as (name, weight, messages)
+const BUILTIN_MESSAGES: &[(&str, f64, &[&str])] = &[
+    ("features", 3.0, &[
+        "Add new feature implementation",
+        "Implement user feedback",
+        "Add error handling",
+        "Add logging and monitoring",
+        "Improve user interface",
+        "Add API endpoints",
+        "Add database migrations",
+        "Add configuration options",
+        "Optimize performance bottleneck",
+    ]),
+    ("fixes", 2.0, &[
+        "Fix critical bug in core logic",
+        "Fix merge conflicts",
+        "Fix security vulnerability",
+        "Fix failing tests",
+        "Fix production issue",
+    ]),
+    ("chores", 2.0, &[
+        "Refactor existing codebase",
+        "Add comprehensive tests",
+        "Update documentation",
+        "Update dependencies",
+        "Clean up code structure",
+        "Improve code coverage",
+    ]),
+];
+
+// A group of messages drawn from with relative `weight`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageCategory {
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    pub messages: Vec<String>,
+    // Indices not yet drawn in the current pass, in shuffled order
+    #[serde(skip)]
+    bag: Vec<usize>,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+struct MessageFile {
+    categories: BTreeMap<String, MessageCategory>,
+}
+
+// Commit messages the generator picks from. Load one with `--messages`
+// (plain text, one per line, or TOML with weighted categories) and
+// `install` it to replace the built-in list for the rest of the process.
+// Each category deals its messages like a shuffled deck, so a message only
+// comes round again once the rest of its category has been used.
+#[derive(Debug, Clone)]
+pub struct MessageBank {
+    categories: Vec<(String, MessageCategory)>,
+    last: Option<String>,
+    ticket_prefix: Option<String>,
+    issue_ref_share: f64,
+}
+
+impl MessageBank {
+    pub fn builtin() -> Self {
+        let categories = BUILTIN_MESSAGES.iter()
+            .map(|(name, weight, messages)| (name.to_string(), MessageCategory {
+                weight: *weight,
+                messages: messages.iter().map(|m| m.to_string()).collect(),
+                bag: Vec::new(),
+            }))
+            .collect();
+        Self { categories, last: None, ticket_prefix: None, issue_ref_share: 0.0 }
+    }
+
+    // One message per line; blank lines and `#` comments are skipped
+    pub fn from_lines(content: &str) -> Result<Self> {
+        let messages = content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        Self::from_categories(vec![("general".to_string(), MessageCategory { weight: 1.0, messages, bag: Vec::new() })])
+    }
+
+    // `[categories.<name>]` tables, each with `messages` and an optional `weight`
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: MessageFile = toml::from_str(content)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid message file: {}", e)))?;
+        Self::from_categories(file.categories.into_iter().collect())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            _ => Self::from_lines(&content),
+        }
+    }
+
+    fn from_categories(categories: Vec<(String, MessageCategory)>) -> Result<Self> {
+        let mut kept = Vec::new();
+        for (name, mut category) in categories {
+            if !category.weight.is_finite() || category.weight < 0.0 {
+                return Err(GitHubGridError::Config(format!("Message category '{}' has an invalid weight", name)));
+            }
+            category.messages.retain(|message| !message.trim().is_empty());
+            if category.weight > 0.0 && !category.messages.is_empty() {
+                kept.push((name, category));
+            }
+        }
+        if kept.is_empty() {
+            return Err(GitHubGridError::Config("Message file contains no usable messages".to_string()));
+        }
+        Ok(Self { categories: kept, last: None, ticket_prefix: None, issue_ref_share: 0.0 })
+    }
+
+    // Formats messages as `PROJ-1423: implement retry logic`, with ticket
+    // numbers that increase with the commit date
+    pub fn with_ticket_prefix(mut self, prefix: &str) -> Self {
+        self.ticket_prefix = Some(prefix.to_string());
+        self
+    }
+
+    // Appends `(#123)`, or a `Fixes #456` trailer on fixes, to this share of
+    // messages, with issue numbers that increase with the commit date
+    pub fn with_issue_refs(mut self, share: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&share) {
+            return Err(GitHubGridError::Config(format!("Issue reference share must be between 0 and 1, got {}", share)));
+        }
+        self.issue_ref_share = share;
+        Ok(self)
+    }
+    
+    // Overrides one category's weight; 0 disables it
+    pub fn set_weight(&mut self, name: &str, weight: f64) -> Result<()> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(GitHubGridError::Config(format!("Invalid weight {} for message category '{}'", weight, name)));
+        }
+        let Some(index) = self.categories.iter().position(|(category, _)| category == name) else {
+            return Err(GitHubGridError::Config(format!(
+                "Unknown message category '{}' (available: {})", name, self.category_names().join(", ")
+            )));
+        };
+        
+        let others_positive = self.categories.iter()
+            .enumerate()
+            .any(|(i, (_, category))| i != index && category.weight > 0.0);
+        if weight == 0.0 && !others_positive {
+            return Err(GitHubGridError::Config("At least one message category needs a positive weight".to_string()));
+        }
+        self.categories[index].1.weight = weight;
+        Ok(())
+    }
+    
+    pub fn category_names(&self) -> Vec<&str> {
+        self.categories.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.categories.iter().map(|(_, category)| category.messages.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Next message for a commit on `date`
+    pub fn message_for(&mut self, date: NaiveDate) -> String {
+        let rng = &mut rng();
+        let body = self.pick(rng);
+        let mut message = match &self.ticket_prefix {
+            Some(prefix) => format!("{}-{}: {}", prefix, ticket_number(date, rng), lowercase_first(&body)),
+            None => body.clone(),
+        };
+
+        if rng.random_bool(self.issue_ref_share) {
+            let issue = numbered(date, ISSUES_PER_DAY, 1, OPEN_ISSUES, rng);
+            if body.starts_with("Fix") {
+                message = format!("{}\n\nFixes #{}", message, issue);
+            } else {
+                message = format!("{} (#{})", message, issue);
+            }
+        }
+        format!("{} {}", AUTOGEN_PREFIX, message)
+    }
+
+    // Next message body from a weighted category, without the [AutoGen] prefix
+    fn pick(&mut self, rng: &mut impl Rng) -> String {
+        let total: f64 = self.categories.iter().map(|(_, category)| category.weight).sum();
+        let mut roll = rng.random_range(0.0..total);
+        let index = self.categories.iter()
+            .position(|(_, category)| {
+                roll -= category.weight;
+                roll < 0.0 && category.weight > 0.0
+            })
+            .or_else(|| self.categories.iter().rposition(|(_, category)| category.weight > 0.0))
+            .unwrap();
+        let (_, category) = &mut self.categories[index];
+
+        if category.bag.is_empty() {
+            category.bag = (0..category.messages.len()).collect();
+            category.bag.shuffle(rng);
+            // Don't let a fresh pass open with the message that closed the last one
+            let opens_with_last = category.bag.last()
+                .is_some_and(|&i| self.last.as_deref() == Some(strip_prefix(&category.messages[i])));
+            if opens_with_last && category.bag.len() > 1 {
+                category.bag.swap(0, category.messages.len() - 1);
+            }
+        }
+
+        let message = strip_prefix(&category.messages[category.bag.pop().unwrap()]).to_string();
+        self.last = Some(message.clone());
+        message
+    }
+}
+
+// User files may or may not include the prefix; it's added back on output
+fn strip_prefix(message: &str) -> &str {
+    let message = message.trim();
+    message.strip_prefix(AUTOGEN_PREFIX).map_or(message, str::trim_start)
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn ticket_number(date: NaiveDate, rng: &mut impl Rng) -> u32 {
+    numbered(date, TICKETS_PER_DAY, FIRST_TICKET, OPEN_TICKETS, rng)
+}
+
+// One of the `open` most recent numbers as of `date`, for a tracker that
+// started at `first` and grows by `per_day`
+fn numbered(date: NaiveDate, per_day: f64, first: u32, open: u32, rng: &mut impl Rng) -> u32 {
+    let (year, month, day) = NUMBERING_EPOCH;
+    let days = (date - NaiveDate::from_ymd_opt(year, month, day).unwrap()).num_days().max(0);
+    let newest = first + (days as f64 * per_day) as u32 + open;
+    newest - rng.random_range(0..open)
+}
@@ -0,0 +1,159 @@
+use std::process::Command;
+use crate::progress::{json_str, RunStats};
+
+// Where to report the outcome of a finished run
+#[derive(Debug, Clone, Default)]
+pub struct NotifyOptions {
+    pub desktop: bool,
+    pub webhooks: Vec<String>,
+    // Chat webhooks, sent a readable message instead of the JSON summary
+    pub slack: Vec<String>,
+    pub discord: Vec<String>,
+}
+
+impl NotifyOptions {
+    pub fn is_empty(&self) -> bool {
+        !self.desktop && self.webhooks.is_empty() && self.slack.is_empty() && self.discord.is_empty()
+    }
+}
+
+// Outcome of a run as sent to notification targets
+pub struct RunSummary<'a> {
+    pub command: &'a str,
+    pub stats: RunStats,
+    pub dry_run: bool,
+    pub error: Option<String>,
+}
+
+impl RunSummary<'_> {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn headline(&self) -> String {
+        match &self.error {
+            Some(error) => format!("github-grid {} failed: {}", self.command, error),
+            None if self.dry_run => format!("github-grid {} dry run: {} commits planned", self.command, self.stats.planned),
+            None => format!(
+                "github-grid {}: {} commits created, {} pushed",
+                self.command, self.stats.created, self.stats.pushed
+            ),
+        }
+    }
+
+    // Headline plus range and verification, one fact per line, for chat
+    pub fn chat_message(&self) -> String {
+        let icon = match self.succeeded() {
+            true => "✅",
+            false => "❌",
+        };
+        let mut lines = vec![format!("{} {}", icon, self.headline())];
+        if let Some(repo) = &self.stats.repo {
+            lines.push(format!("Repo: {}", repo));
+        }
+        if let (Some(start), Some(end)) = (self.stats.start, self.stats.end) {
+            lines.push(format!("Range: {} to {}", start, end));
+        }
+        if !self.dry_run && self.stats.created > 0 {
+            lines.push(match self.stats.on_graph {
+                Some(on_graph) if on_graph >= self.stats.created => format!("Verified: all {} commits on the graph", self.stats.created),
+                Some(on_graph) => format!("Verified: {} of {} commits on the graph", on_graph, self.stats.created),
+                None => "Verified: not checked".to_string(),
+            });
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<String>| value.map_or("null".to_string(), |v| json_str(&v));
+        format!(
+            "{{\"status\":{},\"command\":{},\"repo\":{},\"start\":{},\"end\":{},\"planned\":{},\"created\":{},\"pushed\":{},\"on_graph\":{},\"dry_run\":{},\"error\":{}}}",
+            json_str(if self.succeeded() { "success" } else { "failure" }),
+            json_str(self.command),
+            optional(self.stats.repo.clone()),
+            optional(self.stats.start.map(|d| d.to_string())),
+            optional(self.stats.end.map(|d| d.to_string())),
+            self.stats.planned,
+            self.stats.created,
+            self.stats.pushed,
+            self.stats.on_graph.map_or("null".to_string(), |n| n.to_string()),
+            self.dry_run,
+            optional(self.error.clone()),
+        )
+    }
+}
+
+// Sends the summary everywhere configured. Notification problems are
+// reported as warnings and never change the run's own result.
+pub fn send(options: &NotifyOptions, summary: &RunSummary) {
+    if options.desktop && let Err(e) = desktop_notification(&summary.headline()) {
+        eprintln!("⚠️  Desktop notification failed: {}", e);
+    }
+
+    let payload = summary.to_json();
+    // Webhook URLs often carry a secret, so they're named by position
+    for (n, url) in options.webhooks.iter().enumerate() {
+        if let Err(e) = post_json(url, &payload) {
+            eprintln!("⚠️  Webhook #{} failed: {}", n + 1, e);
+        }
+    }
+
+    let message = json_str(&summary.chat_message());
+    let chats = options.slack.iter().map(|url| ("Slack", url, format!("{{\"text\":{}}}", message)))
+        .chain(options.discord.iter().map(|url| ("Discord", url, format!("{{\"content\":{}}}", message))));
+    for (service, url, payload) in chats {
+        if let Err(e) = post_json(url, &payload) {
+            eprintln!("⚠️  {} webhook failed: {}", service, e);
+        }
+    }
+}
+
+fn desktop_notification(message: &str) -> Result<(), String> {
+    let output = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title \"github-grid\"",
+            applescript_str(message)
+        );
+        Command::new("osascript").args(["-e", &script]).output()
+    } else if cfg!(windows) {
+        let script = format!(
+            "[System.Reflection.Assembly]::LoadWithPartialName('System.Windows.Forms') | Out-Null; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; $n.Icon = [System.Drawing.SystemIcons]::Information; \
+             $n.Visible = $true; $n.ShowBalloonTip(10000, 'github-grid', '{}', 'Info'); Start-Sleep -Seconds 5",
+            message.replace('\'', "''")
+        );
+        Command::new("powershell").args(["-NoProfile", "-Command", &script]).output()
+    } else {
+        Command::new("notify-send").args(["github-grid", message]).output()
+    };
+
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn post_json(url: &str, payload: &str) -> Result<(), String> {
+    let output = Command::new("curl")
+        .args([
+            "--silent", "--show-error", "--fail",
+            "--max-time", "30",
+            "-X", "POST",
+            "-H", "Content-Type: application/json",
+            "--data-binary", payload,
+            url,
+        ])
+        .output()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn applescript_str(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
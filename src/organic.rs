@@ -0,0 +1,120 @@
+use std::f64::consts::PI;
+use chrono::{Datelike, NaiveDate};
+use rand::seq::SliceRandom;
+use rand::{rng, Rng};
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{create_commit_at_time, separate_timestamps, CommitInfo, Pattern};
+
+// A detail octave at roughly a third of the base period breaks up the
+// long swells without turning back into daily noise
+const DETAIL_SCALE: f64 = 2.7;
+const DETAIL_WEIGHT: f64 = 0.35;
+
+// One-dimensional Perlin noise over days: smooth, seeded per run, and
+// roughly within -1..=1
+#[derive(Debug, Clone, Copy)]
+pub struct DayNoise {
+    seed: u64,
+    period: f64,
+}
+
+impl DayNoise {
+    // `period` is the typical length of a hot or cold stretch, in days
+    pub fn new(seed: u64, period: u32) -> Result<Self> {
+        if !(7..=365).contains(&period) {
+            return Err(GitHubGridError::Config(format!("Organic period must be 7 to 365 days, got {}", period)));
+        }
+        Ok(Self { seed, period: period as f64 })
+    }
+
+    pub fn at(&self, day: NaiveDate) -> f64 {
+        let x = day.num_days_from_ce() as f64 / self.period;
+        let base = perlin(self.seed, x);
+        let detail = perlin(self.seed ^ 0x9e37_79b9_7f4a_7c15, x * DETAIL_SCALE);
+        // 1-D Perlin peaks at ±0.5
+        ((base + DETAIL_WEIGHT * detail) * 2.0 / (1.0 + DETAIL_WEIGHT)).clamp(-1.0, 1.0)
+    }
+}
+
+fn perlin(seed: u64, x: f64) -> f64 {
+    let cell = x.floor();
+    let t = x - cell;
+    let left = gradient(seed, cell as i64) * t;
+    let right = gradient(seed, cell as i64 + 1) * (t - 1.0);
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    left + fade * (right - left)
+}
+
+// Slope at a lattice point, from a splitmix64 hash so no table is kept
+fn gradient(seed: u64, cell: i64) -> f64 {
+    let mut z = seed.wrapping_add((cell as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z as f64 / u64::MAX as f64 * 2.0 * PI).cos()
+}
+
+// Modulates another pattern with a noise field over the year, so busy and
+// quiet stretches cluster the way real projects do instead of every day
+// being drawn on its own. Each day's commits are scaled by 1 + noise:
+// cold stretches thin out to nothing, hot ones double up and pull in days
+// that would have been empty. The average stays close to the inner
+// pattern's.
+pub struct OrganicPattern {
+    inner: Box<dyn Pattern>,
+    noise: DayNoise,
+}
+
+impl OrganicPattern {
+    pub fn new(inner: Box<dyn Pattern>, period: u32) -> Result<Self> {
+        Ok(Self { inner, noise: DayNoise::new(rng().random(), period)? })
+    }
+
+    pub fn with_noise(inner: Box<dyn Pattern>, noise: DayNoise) -> Self {
+        Self { inner, noise }
+    }
+}
+
+impl Pattern for OrganicPattern {
+    fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
+        self.stream(start, end).collect()
+    }
+
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        let mut inner = self.inner.stream(start, end).peekable();
+        let mut current = start;
+        Box::new(std::iter::from_fn(move || {
+            if current > end {
+                // Anything the inner pattern put past the end passes through
+                return inner.next().map(|commit| vec![commit]);
+            }
+            let day = current;
+            current = day.succ_opt().unwrap();
+            let mut commits = Vec::new();
+            while let Some(commit) = inner.next_if(|commit| commit.date.date_naive() <= day) {
+                commits.push(commit);
+            }
+            modulate(day, &mut commits, 1.0 + self.noise.at(day));
+            Some(commits)
+        }).flatten())
+    }
+}
+
+fn modulate(day: NaiveDate, commits: &mut Vec<CommitInfo>, factor: f64) {
+    let mut rng = rng();
+    let base = match commits.len() {
+        // Hot stretches fill in days off at the rate they add work
+        0 if factor > 1.0 && rng.random_bool((factor - 1.0) / 2.0) => rng.random_range(1..=3),
+        count => count,
+    };
+    let target = (base as f64 * factor).round() as usize;
+    if target < commits.len() {
+        commits.shuffle(&mut rng);
+        commits.truncate(target);
+    }
+    while commits.len() < target {
+        commits.push(create_commit_at_time(day, rng.random_range(9..=22), rng.random_range(0..60)));
+    }
+    commits.sort_by_key(|commit| commit.date);
+    separate_timestamps(commits);
+}
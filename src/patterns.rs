@@ -1,15 +1,32 @@
-use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Weekday, Datelike};
+use std::collections::BTreeMap;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Timelike, Weekday, Datelike};
 use rand::{rng, Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use crate::error::{GitHubGridError, Result};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitInfo {
     pub date: DateTime<Local>,
+    // Left empty by the patterns; GitOperations draws one from its message
+    // bank when the commit is made
+    #[serde(default)]
     pub message: String,
+    // Seconds east of UTC to record instead of the local offset (set while travelling)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc_offset: Option<i32>,
 }
 
 pub trait Pattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo>;
+    
+    // The same commits in date order, produced lazily so callers can start
+    // on the first ones before the range is done and huge ranges never sit
+    // in memory at once. Patterns that can't generate incrementally keep
+    // this default, which builds the whole plan first.
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        Box::new(self.generate(start, end).into_iter())
+    }
 }
 
 // Deterministic RNG seeded by date for consistent results
@@ -27,7 +44,8 @@ fn date_rng(date: NaiveDate) -> ChaCha8Rng {
 }
 
 // Base intensity levels with ranges
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IntensityLevel {
     Casual,      // ~300/year
     Active,      // ~2,500/year  
@@ -88,16 +106,14 @@ impl IntensityLevel {
     }
 }
 
-// Weekly rhythm multipliers (realistic work patterns with slight randomization)
-fn get_weekly_multiplier(weekday: Weekday, rng: &mut ChaCha8Rng) -> f64 {
-    let base = match weekday {
-        Weekday::Mon => 0.7,  // Monday blues
-        Weekday::Tue => 1.1,  // Peak productivity
-        Weekday::Wed => 1.1,  // Peak productivity
-        Weekday::Thu => 1.1,  // Peak productivity
-        Weekday::Fri => 0.8,  // Winding down
-        Weekday::Sat => 0.6,  // Lighter weekends
-        Weekday::Sun => 0.6,  // Lighter weekends
+// Weekly rhythm multipliers (realistic work patterns with slight randomization),
+// by where the day falls in the workweek
+fn get_weekly_multiplier(weekday: Weekday, workweek: Workweek, rng: &mut ChaCha8Rng) -> f64 {
+    let base = match workweek.position(weekday) {
+        None => 0.6,                            // Lighter weekends
+        Some((0, _)) => 0.7,                    // Monday blues
+        Some((n, len)) if n + 1 == len => 0.8,  // Winding down
+        Some(_) => 1.1,                         // Peak productivity
     };
     
     // Add ±5% randomization to avoid exact patterns
@@ -105,18 +121,228 @@ fn get_weekly_multiplier(weekday: Weekday, rng: &mut ChaCha8Rng) -> f64 {
     f64::max(base + variation, 0.1) // Ensure positive multiplier
 }
 
-// Configuration for pattern generation
-#[derive(Debug, Clone)]
+// Which days of the week count as working days, e.g. "Mon-Fri", "Sun-Thu"
+// or "Mon-Wed,Fri". The others get the weekend tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Workweek {
+    // One bit per day, Monday first
+    days: u8,
+}
+
+impl Workweek {
+    pub const MON_FRI: Workweek = Workweek { days: 0b0011111 };
+    
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GitHubGridError::Parse(format!("Invalid workweek '{}', expected e.g. Mon-Fri or Sun-Thu", value));
+        let day = |name: &str| name.trim().parse::<Weekday>().map_err(|_| invalid());
+        let mut days = 0u8;
+        for part in value.split(',') {
+            let (first, last) = match part.split_once('-') {
+                Some((first, last)) => (day(first)?, day(last)?),
+                None => (day(part)?, day(part)?),
+            };
+            // Ranges wrap around the week, so Sat-Tue is four days
+            let mut current = first;
+            loop {
+                days |= 1 << current.num_days_from_monday();
+                if current == last {
+                    break;
+                }
+                current = current.succ();
+            }
+        }
+        Ok(Self { days })
+    }
+    
+    pub fn is_workday(&self, weekday: Weekday) -> bool {
+        self.days & (1 << weekday.num_days_from_monday()) != 0
+    }
+    
+    pub fn workdays(&self) -> usize {
+        self.days.count_ones() as usize
+    }
+    
+    fn without(&self, weekday: Weekday) -> Self {
+        Self { days: self.days & !(1 << weekday.num_days_from_monday()) }
+    }
+    
+    // Index of a workday within its run of consecutive workdays, and the
+    // run's length; None on days off. A seven-day week starts on Monday.
+    fn position(&self, weekday: Weekday) -> Option<(usize, usize)> {
+        if !self.is_workday(weekday) {
+            return None;
+        }
+        let mut start = weekday;
+        if self.workdays() == 7 {
+            start = Weekday::Mon;
+        } else {
+            while self.is_workday(start.pred()) {
+                start = start.pred();
+            }
+        }
+        let mut len = 1;
+        while len < 7 && self.is_workday(days_after(start, len)) {
+            len += 1;
+        }
+        let index = (weekday.num_days_from_monday() + 7 - start.num_days_from_monday()) as usize % 7;
+        Some((index, len))
+    }
+}
+
+fn days_after(weekday: Weekday, days: usize) -> Weekday {
+    Weekday::try_from(((weekday.num_days_from_monday() as usize + days) % 7) as u8).unwrap()
+}
+
+impl Default for Workweek {
+    fn default() -> Self {
+        Self::MON_FRI
+    }
+}
+
+impl std::fmt::Display for Workweek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let runs: Vec<String> = (0..7)
+            .map(|n| days_after(Weekday::Mon, n))
+            .filter_map(|weekday| match self.position(weekday) {
+                Some((0, 1)) => Some(weekday.to_string()),
+                Some((0, len)) => Some(format!("{}-{}", weekday, days_after(weekday, len - 1))),
+                _ => None,
+            })
+            .collect();
+        write!(f, "{}", runs.join(","))
+    }
+}
+
+impl TryFrom<String> for Workweek {
+    type Error = GitHubGridError;
+    
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Workweek> for String {
+    fn from(workweek: Workweek) -> String {
+        workweek.to_string()
+    }
+}
+
+// Configuration for pattern generation. Start from a preset or
+// `PatternConfig::builder()`; the builder rejects out-of-range values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternConfig {
-    pub intensity: IntensityLevel,
-    pub use_weekly_rhythm: bool,
-    pub vacation_frequency: f64,    // Probability per day of starting vacation
-    pub vacation_duration: (u32, u32), // Min/max vacation days
-    pub spike_probability: f64,     // Chance of high-activity days
-    pub spike_multiplier: f64,      // Multiplier for spike days
+    intensity: IntensityLevel,
+    use_weekly_rhythm: bool,
+    vacation_frequency: f64,    // Probability per day of starting vacation
+    vacation_duration: (u32, u32), // Min/max vacation days
+    spike_probability: f64,     // Chance of high-activity days
+    spike_multiplier: f64,      // Multiplier for spike days
+    #[serde(default = "default_volume")]
+    volume: f64,                // Scale on daily base commits, tuned by calibration
+    #[serde(default)]
+    workweek: Workweek,         // Days that get the weekday tables
+    // A workday taken off every week (four-day weeks); the other workdays
+    // carry its share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    day_off: Option<Weekday>,
+}
+
+fn default_volume() -> f64 {
+    1.0
 }
 
 impl PatternConfig {
+    // Builder seeded with the `active` preset
+    pub fn builder() -> PatternConfigBuilder {
+        PatternConfigBuilder { config: Self::active() }
+    }
+    
+    // Builder seeded with this config, for tweaking a preset
+    pub fn to_builder(&self) -> PatternConfigBuilder {
+        PatternConfigBuilder { config: self.clone() }
+    }
+    
+    pub fn intensity(&self) -> &IntensityLevel {
+        &self.intensity
+    }
+    
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+    
+    pub fn vacation_frequency(&self) -> f64 {
+        self.vacation_frequency
+    }
+    
+    pub fn vacation_duration(&self) -> (u32, u32) {
+        self.vacation_duration
+    }
+    
+    pub fn spike_probability(&self) -> f64 {
+        self.spike_probability
+    }
+    
+    pub fn spike_multiplier(&self) -> f64 {
+        self.spike_multiplier
+    }
+    
+    pub fn workweek(&self) -> Workweek {
+        self.workweek
+    }
+    
+    pub fn day_off(&self) -> Option<Weekday> {
+        self.day_off
+    }
+    
+    // Pattern file as written by `fit`, usable with `--pattern FILE.toml`
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid pattern file: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| GitHubGridError::Parse(format!("Failed to serialize pattern: {}", e)))
+    }
+    
+    // Checks the invariants the generator relies on; configs loaded from
+    // files should go through this before use.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(GitHubGridError::Config(msg));
+        let probability = |value: f64| value.is_finite() && (0.0..=1.0).contains(&value);
+        
+        if !probability(self.vacation_frequency) {
+            return invalid(format!("vacation_frequency must be between 0 and 1, got {}", self.vacation_frequency));
+        }
+        if !probability(self.spike_probability) {
+            return invalid(format!("spike_probability must be between 0 and 1, got {}", self.spike_probability));
+        }
+        if !self.volume.is_finite() || self.volume <= 0.0 || self.volume > 20.0 {
+            return invalid(format!("volume must be greater than 0 and at most 20, got {}", self.volume));
+        }
+        if !self.spike_multiplier.is_finite() || self.spike_multiplier < 1.0 {
+            return invalid(format!("spike_multiplier must be at least 1.0, got {}", self.spike_multiplier));
+        }
+        let (min_days, max_days) = self.vacation_duration;
+        if min_days > max_days {
+            return invalid(format!("vacation_duration minimum ({}) exceeds maximum ({})", min_days, max_days));
+        }
+        if max_days > 60 {
+            return invalid(format!("vacation_duration maximum must be at most 60 days, got {}", max_days));
+        }
+        if let Some(day) = self.day_off {
+            if !self.workweek.is_workday(day) {
+                return invalid(format!("day_off {} is not in the {} workweek", day, self.workweek));
+            }
+            if self.workweek.workdays() < 2 {
+                return invalid(format!("day_off needs a workweek of at least two days, got {}", self.workweek));
+            }
+        }
+        Ok(())
+    }
+    
     pub fn casual() -> Self {
         Self {
             intensity: IntensityLevel::Casual,
@@ -125,6 +351,9 @@ impl PatternConfig {
             vacation_duration: (0, 0),
             spike_probability: 0.15,  // Regular burst days
             spike_multiplier: 3.0,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         }
     }
     
@@ -136,6 +365,9 @@ impl PatternConfig {
             vacation_duration: (2, 7),
             spike_probability: 0.20,  // Frequent feature days
             spike_multiplier: 2.5,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         }
     }
     
@@ -147,6 +379,9 @@ impl PatternConfig {
             vacation_duration: (3, 10),
             spike_probability: 0.25,   // Many busy days
             spike_multiplier: 2.2,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         }
     }
     
@@ -158,9 +393,18 @@ impl PatternConfig {
             vacation_duration: (2, 5),
             spike_probability: 0.30,   // Constant marathon sessions
             spike_multiplier: 2.8,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         }
     }
     
+    // Active developer on a four-day week: `day_off` (usually Friday) stays
+    // near-empty and the other four days make up for it
+    pub fn four_day(day_off: Weekday) -> Self {
+        Self { day_off: Some(day_off), ..Self::active() }
+    }
+    
     pub fn extreme() -> Self {
         Self {
             intensity: IntensityLevel::Extreme,
@@ -169,46 +413,381 @@ impl PatternConfig {
             vacation_duration: (1, 4),
             spike_probability: 0.35,   // Always in sprint mode
             spike_multiplier: 3.2,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         }
     }
 }
 
-const COMMIT_MESSAGES: &[&str] = &[
-    "[AutoGen] Add new feature implementation",
-    "[AutoGen] Fix critical bug in core logic", 
-    "[AutoGen] Refactor existing codebase",
-    "[AutoGen] Add comprehensive tests",
-    "[AutoGen] Update documentation",
-    "[AutoGen] Optimize performance bottleneck",
-    "[AutoGen] Implement user feedback",
-    "[AutoGen] Fix merge conflicts",
-    "[AutoGen] Add error handling",
-    "[AutoGen] Update dependencies",
-    "[AutoGen] Clean up code structure",
-    "[AutoGen] Add logging and monitoring",
-    "[AutoGen] Fix security vulnerability",
-    "[AutoGen] Improve user interface",
-    "[AutoGen] Add API endpoints",
-    "[AutoGen] Fix failing tests",
-    "[AutoGen] Add database migrations",
-    "[AutoGen] Improve code coverage",
-    "[AutoGen] Add configuration options",
-    "[AutoGen] Fix production issue",
-];
-
-fn get_random_message() -> String {
-    let mut rng = rng();
-    COMMIT_MESSAGES[rng.random_range(0..COMMIT_MESSAGES.len())].to_string()
+pub struct PatternConfigBuilder {
+    config: PatternConfig,
 }
 
-fn create_commit_at_time(date: NaiveDate, hour: u32, minute: u32) -> CommitInfo {
-    let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
-    let datetime = Local.from_local_datetime(&date.and_time(time)).unwrap();
+impl PatternConfigBuilder {
+    pub fn intensity(mut self, intensity: IntensityLevel) -> Self {
+        self.config.intensity = intensity;
+        self
+    }
+    
+    pub fn weekly_rhythm(mut self, enabled: bool) -> Self {
+        self.config.use_weekly_rhythm = enabled;
+        self
+    }
+    
+    pub fn vacation_frequency(mut self, probability: f64) -> Self {
+        self.config.vacation_frequency = probability;
+        self
+    }
+    
+    pub fn vacation_duration(mut self, min_days: u32, max_days: u32) -> Self {
+        self.config.vacation_duration = (min_days, max_days);
+        self
+    }
+    
+    pub fn spike_probability(mut self, probability: f64) -> Self {
+        self.config.spike_probability = probability;
+        self
+    }
+    
+    pub fn spike_multiplier(mut self, multiplier: f64) -> Self {
+        self.config.spike_multiplier = multiplier;
+        self
+    }
+    
+    pub fn volume(mut self, volume: f64) -> Self {
+        self.config.volume = volume;
+        self
+    }
+    
+    pub fn workweek(mut self, workweek: Workweek) -> Self {
+        self.config.workweek = workweek;
+        self
+    }
+    
+    pub fn day_off(mut self, day_off: Option<Weekday>) -> Self {
+        self.config.day_off = day_off;
+        self
+    }
+    
+    pub fn build(self) -> Result<PatternConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+// Seconds are random so timestamps don't all end in :00
+pub fn create_commit_at_time(date: NaiveDate, hour: u32, minute: u32) -> CommitInfo {
+    let time = NaiveTime::from_hms_opt(hour, minute, rng().random_range(0..60)).unwrap();
+    let datetime = crate::dates::local_datetime(date.and_time(time));
     
     CommitInfo {
         date: datetime,
-        message: get_random_message(),
+        message: String::new(),
+        utc_offset: None,
+    }
+}
+
+// A commit at hour:minute on `day`, or at a random earlier time that day
+// when that would be after `not_after`
+fn commit_by(day: NaiveDate, hour: u32, minute: u32, not_after: Option<DateTime<Local>>) -> CommitInfo {
+    let mut commit = create_commit_at_time(day, hour, minute);
+    if let Some(limit) = not_after && commit.date > limit {
+        let elapsed = (limit.naive_local() - day.and_time(NaiveTime::MIN)).num_seconds().max(0);
+        commit.date = limit - chrono::Duration::seconds(rng().random_range(0..=elapsed));
     }
+    commit
+}
+
+// Nudges commits that share a timestamp forward a second at a time so no
+// two are identical, without pushing any onto the next day. `commits` must
+// be sorted by date.
+pub fn separate_timestamps(commits: &mut [CommitInfo]) {
+    for i in 1..commits.len() {
+        let previous = commits[i - 1].date;
+        if commits[i].date <= previous {
+            let nudged = previous + chrono::Duration::seconds(1);
+            if nudged.date_naive() == commits[i].date.date_naive() {
+                commits[i].date = nudged;
+            }
+        }
+    }
+}
+
+// Adds or removes commits until the plan holds exactly `target`. Extra
+// commits go to days that already have activity and removals never empty a
+// day while another day has more than one, so the grid keeps its shape.
+// `days` are the dates allowed to receive commits if the plan is empty.
+pub fn fit_to_total(commits: Vec<CommitInfo>, target: usize, days: &[NaiveDate]) -> Vec<CommitInfo> {
+    let mut rng = rng();
+    let mut by_day: BTreeMap<NaiveDate, Vec<CommitInfo>> = BTreeMap::new();
+    for commit in commits {
+        by_day.entry(commit.date.date_naive()).or_default().push(commit);
+    }
+    let mut total: usize = by_day.values().map(|c| c.len()).sum();
+    
+    // Thin random multi-commit days, falling back to emptying single days
+    let mut crowded: Vec<NaiveDate> = by_day.iter()
+        .filter(|(_, c)| c.len() > 1)
+        .map(|(day, _)| *day)
+        .collect();
+    while total > target && !crowded.is_empty() {
+        let index = rng.random_range(0..crowded.len());
+        let day_commits = by_day.get_mut(&crowded[index]).unwrap();
+        day_commits.remove(rng.random_range(0..day_commits.len()));
+        if day_commits.len() == 1 {
+            crowded.swap_remove(index);
+        }
+        total -= 1;
+    }
+    let mut active: Vec<NaiveDate> = by_day.keys().copied().collect();
+    while total > target {
+        by_day.remove(&active.swap_remove(rng.random_range(0..active.len())));
+        total -= 1;
+    }
+    
+    while total < target {
+        let day = if active.is_empty() {
+            // Nothing to build on: seed weekdays first, like a real schedule would
+            let weekdays: Vec<NaiveDate> = days.iter()
+                .copied()
+                .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+                .collect();
+            let pool = if weekdays.is_empty() { days } else { &weekdays };
+            if pool.is_empty() {
+                break;
+            }
+            let day = pool[rng.random_range(0..pool.len())];
+            active.push(day);
+            day
+        } else {
+            active[rng.random_range(0..active.len())]
+        };
+        
+        let commit = create_commit_at_time(day, rng.random_range(6..=23), rng.random_range(0..60));
+        by_day.entry(day).or_default().push(commit);
+        total += 1;
+    }
+    
+    let mut commits: Vec<CommitInfo> = by_day.into_values().flatten().collect();
+    commits.sort_by_key(|c| c.date);
+    separate_timestamps(&mut commits);
+    commits
+}
+
+// Clamps every active day to min..=max commits while keeping the total.
+// Commits already in the repo (`existing`) count toward both limits but
+// are never moved. Commits clipped from busy days go to active days with
+// room (or open new days from `days` once none has any); thin days are
+// topped up from that pool, then from days above the minimum, and merged
+// away if neither has enough. Added commits never land after `not_after`.
+// Returns the plan and how many commits had nowhere to go.
+pub fn clamp_per_day(
+    commits: Vec<CommitInfo>,
+    min: u32,
+    max: Option<u32>,
+    days: &[NaiveDate],
+    existing: &BTreeMap<NaiveDate, u32>,
+    not_after: Option<DateTime<Local>>,
+) -> (Vec<CommitInfo>, usize) {
+    let mut rng = rng();
+    let min = min.max(1) as usize;
+    let max = max.map_or(usize::MAX, |max| max as usize);
+    let already = |day: &NaiveDate| existing.get(day).copied().unwrap_or(0) as usize;
+    let mut by_day = group_by_day(commits);
+    let add = |day_commits: &mut Vec<CommitInfo>, day: NaiveDate, rng: &mut rand::rngs::ThreadRng| {
+        day_commits.push(commit_by(day, rng.random_range(6..=23), rng.random_range(0..60), not_after));
+    };
+    
+    let mut pool = 0;
+    for (day, day_commits) in by_day.iter_mut() {
+        while !day_commits.is_empty() && already(day) + day_commits.len() > max {
+            day_commits.remove(rng.random_range(0..day_commits.len()));
+            pool += 1;
+        }
+    }
+    by_day.retain(|_, day_commits| !day_commits.is_empty());
+    
+    // Closest to the minimum first, so the fewest days get merged away
+    let mut thin: Vec<NaiveDate> = by_day.iter()
+        .filter(|(day, c)| already(day) + c.len() < min)
+        .map(|(day, _)| *day)
+        .collect();
+    thin.sort_by_key(|day| std::cmp::Reverse(already(day) + by_day[day].len()));
+    for day in thin {
+        let need = min - already(&day) - by_day[&day].len();
+        let mut taken = pool.min(need);
+        pool -= taken;
+        while taken < need {
+            let donors: Vec<NaiveDate> = by_day.iter()
+                .filter(|(d, c)| **d != day && already(d) + c.len() > min)
+                .map(|(d, _)| *d)
+                .collect();
+            if donors.is_empty() {
+                break;
+            }
+            let donor = by_day.get_mut(&donors[rng.random_range(0..donors.len())]).unwrap();
+            donor.remove(rng.random_range(0..donor.len()));
+            taken += 1;
+        }
+        if taken < need {
+            pool += taken + by_day.remove(&day).unwrap().len();
+        } else {
+            let day_commits = by_day.get_mut(&day).unwrap();
+            for _ in 0..taken {
+                add(day_commits, day, &mut rng);
+            }
+        }
+    }
+    
+    while pool > 0 {
+        let roomy: Vec<NaiveDate> = by_day.iter().filter(|(day, c)| already(day) + c.len() < max).map(|(day, _)| *day).collect();
+        if !roomy.is_empty() {
+            let day = roomy[rng.random_range(0..roomy.len())];
+            add(by_day.get_mut(&day).unwrap(), day, &mut rng);
+            pool -= 1;
+            continue;
+        }
+        let empty: Vec<NaiveDate> = days.iter().copied()
+            .filter(|day| !by_day.contains_key(day) && already(day) == 0)
+            .collect();
+        if empty.is_empty() || pool < min {
+            break;
+        }
+        let day = empty[rng.random_range(0..empty.len())];
+        let day_commits = by_day.entry(day).or_default();
+        for _ in 0..min {
+            add(day_commits, day, &mut rng);
+        }
+        pool -= min;
+    }
+    
+    (into_plan(by_day), pool)
+}
+
+// Moves commits between weekdays and weekends until `ratio` of the total
+// falls on Saturdays and Sundays. Commits leave busy days more often than
+// quiet ones and mostly land on days that already have activity, with some
+// opening new days from `days`. None land after `not_after`.
+pub fn rebalance_weekends(commits: Vec<CommitInfo>, ratio: f64, days: &[NaiveDate], not_after: Option<DateTime<Local>>) -> Vec<CommitInfo> {
+    let mut rng = rng();
+    let is_weekend = |day: &NaiveDate| matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+    let target = (commits.len() as f64 * ratio.clamp(0.0, 1.0)).round() as usize;
+    let current = commits.iter().filter(|c| is_weekend(&c.date.date_naive())).count();
+    // Move commits off weekends when there are too many, onto them otherwise
+    let from_weekend = current > target;
+    let (moves, mut donor_total) = if from_weekend {
+        (current - target, current)
+    } else {
+        (target - current, commits.len() - current)
+    };
+    let recipients: Vec<NaiveDate> = days.iter().copied().filter(|day| is_weekend(day) != from_weekend).collect();
+    let mut by_day = group_by_day(commits);
+    
+    for _ in 0..moves {
+        if donor_total == 0 || recipients.is_empty() {
+            break;
+        }
+        let mut pick = rng.random_range(0..donor_total);
+        let donor = *by_day.iter()
+            .filter(|(day, _)| is_weekend(day) == from_weekend)
+            .find(|(_, c)| {
+                if pick < c.len() {
+                    return true;
+                }
+                pick -= c.len();
+                false
+            })
+            .unwrap()
+            .0;
+        let donor_commits = by_day.get_mut(&donor).unwrap();
+        donor_commits.remove(rng.random_range(0..donor_commits.len()));
+        if donor_commits.is_empty() {
+            by_day.remove(&donor);
+        }
+        donor_total -= 1;
+        
+        let active: Vec<NaiveDate> = by_day.keys().copied().filter(|day| is_weekend(day) != from_weekend).collect();
+        let day = if !active.is_empty() && rng.random_bool(0.75) {
+            active[rng.random_range(0..active.len())]
+        } else {
+            recipients[rng.random_range(0..recipients.len())]
+        };
+        by_day.entry(day).or_default().push(commit_by(day, rng.random_range(9..=22), rng.random_range(0..60), not_after));
+    }
+    
+    into_plan(by_day)
+}
+
+// Merges or splits days until `target` of them have commits, keeping the
+// total and each commit's time of day. The quietest days are folded into
+// other active days; the busiest days give single commits to new ones.
+// Both prefer a day of the same kind (weekday or weekend), so the weekend
+// share barely moves. A commit moved onto the day of `not_after` is pulled
+// back before it if needed.
+pub fn set_active_days(commits: Vec<CommitInfo>, target: usize, days: &[NaiveDate], not_after: Option<DateTime<Local>>) -> Vec<CommitInfo> {
+    let mut rng = rng();
+    let is_weekend = |day: &NaiveDate| matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+    let mut by_day = group_by_day(commits);
+    let pick_like = |candidates: Vec<NaiveDate>, like: NaiveDate, rng: &mut rand::rngs::ThreadRng| {
+        let same: Vec<NaiveDate> = candidates.iter().copied().filter(|day| is_weekend(day) == is_weekend(&like)).collect();
+        let pool = if same.is_empty() { &candidates } else { &same };
+        pool[rng.random_range(0..pool.len())]
+    };
+    
+    while by_day.len() > target.max(1) {
+        let quietest = by_day.values().map(Vec::len).min().unwrap();
+        let candidates: Vec<NaiveDate> = by_day.iter().filter(|(_, c)| c.len() == quietest).map(|(day, _)| *day).collect();
+        let day = candidates[rng.random_range(0..candidates.len())];
+        for commit in by_day.remove(&day).unwrap() {
+            let to = pick_like(by_day.keys().copied().collect(), day, &mut rng);
+            by_day.get_mut(&to).unwrap().push(commit_by(to, commit.date.hour(), commit.date.minute(), not_after));
+        }
+    }
+    
+    while by_day.len() < target {
+        let busiest = by_day.values().map(Vec::len).max().unwrap_or(0);
+        let empty: Vec<NaiveDate> = days.iter().copied().filter(|day| !by_day.contains_key(day)).collect();
+        if busiest < 2 || empty.is_empty() {
+            break;
+        }
+        let candidates: Vec<NaiveDate> = by_day.iter().filter(|(_, c)| c.len() == busiest).map(|(day, _)| *day).collect();
+        let day = candidates[rng.random_range(0..candidates.len())];
+        let day_commits = by_day.get_mut(&day).unwrap();
+        let commit = day_commits.remove(rng.random_range(0..day_commits.len()));
+        let to = pick_like(empty, day, &mut rng);
+        by_day.insert(to, vec![commit_by(to, commit.date.hour(), commit.date.minute(), not_after)]);
+    }
+    
+    into_plan(by_day)
+}
+
+// A share given as a fraction (0.7) or a percentage (70%)
+pub fn parse_share(value: &str) -> Result<f64> {
+    let invalid = || GitHubGridError::Parse(format!("Invalid share '{}', expected e.g. 0.7 or 70%", value));
+    let share = match value.trim().strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().map_err(|_| invalid())? / 100.0,
+        None => value.trim().parse::<f64>().map_err(|_| invalid())?,
+    };
+    if !(0.0..=1.0).contains(&share) {
+        return Err(invalid());
+    }
+    Ok(share)
+}
+
+fn group_by_day(commits: Vec<CommitInfo>) -> BTreeMap<NaiveDate, Vec<CommitInfo>> {
+    let mut by_day: BTreeMap<NaiveDate, Vec<CommitInfo>> = BTreeMap::new();
+    for commit in commits {
+        by_day.entry(commit.date.date_naive()).or_default().push(commit);
+    }
+    by_day
+}
+
+fn into_plan(by_day: BTreeMap<NaiveDate, Vec<CommitInfo>>) -> Vec<CommitInfo> {
+    let mut commits: Vec<CommitInfo> = by_day.into_values().flatten().collect();
+    commits.sort_by_key(|c| c.date);
+    separate_timestamps(&mut commits);
+    commits
 }
 
 // Generic pattern generator using configuration
@@ -250,9 +829,21 @@ impl ConfigurablePattern {
         }
     }
     
+    // The workdays that get regular activity
+    fn working_week(&self) -> Workweek {
+        match self.config.day_off {
+            Some(day) => self.config.workweek.without(day),
+            None => self.config.workweek,
+        }
+    }
+    
     fn should_work_today(&self, date: NaiveDate, rng: &mut ChaCha8Rng, worked_yesterday: bool, days_since_work: u32) -> bool {
+        // The weekly day off only sees the odd commit
+        if self.config.day_off == Some(date.weekday()) {
+            return rng.random::<f64>() < 0.03;
+        }
         let base_probability = self.config.intensity.get_work_probability();
-        let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let is_weekend = !self.config.workweek.is_workday(date.weekday());
         let is_holiday = self.is_holiday_period(date);
         
         // Base weekend/weekday probability
@@ -332,7 +923,7 @@ impl ConfigurablePattern {
     }
     
     fn get_base_commits(&self, date: NaiveDate, rng: &mut ChaCha8Rng) -> u32 {
-        let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let is_weekend = !self.working_week().is_workday(date.weekday());
         
         let range = if is_weekend {
             self.config.intensity.get_weekend_range()
@@ -341,10 +932,18 @@ impl ConfigurablePattern {
         };
         
         let mut commits = rng.random_range(range.0..=range.1);
+        let mut volume = self.config.volume;
+        if self.config.day_off.is_some() && !is_weekend {
+            // The remaining workdays absorb the day off's share
+            volume *= self.config.workweek.workdays() as f64 / self.working_week().workdays() as f64;
+        }
+        if volume != 1.0 {
+            commits = (commits as f64 * volume).round() as u32;
+        }
         
         // Apply weekly rhythm if enabled
         if self.config.use_weekly_rhythm {
-            let multiplier = get_weekly_multiplier(date.weekday(), rng);
+            let multiplier = get_weekly_multiplier(date.weekday(), self.working_week(), rng);
             commits = (commits as f64 * multiplier) as u32;
         }
         
@@ -362,63 +961,101 @@ impl ConfigurablePattern {
 
 impl Pattern for ConfigurablePattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
-        let mut commits = Vec::new();
-        let mut in_vacation = false;
-        let mut vacation_end = start;
-        let mut worked_yesterday = false;
-        let mut days_since_work = 0u32;
+        self.stream(start, end).collect()
+    }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        Box::new(PatternStream {
+            pattern: self,
+            current: start,
+            end,
+            in_vacation: false,
+            vacation_end: start,
+            worked_yesterday: false,
+            days_since_work: 0,
+            day: Vec::new().into_iter(),
+        })
+    }
+}
+
+// Generates a ConfigurablePattern one day at a time, carrying the vacation
+// and streak state from day to day
+struct PatternStream<'a> {
+    pattern: &'a ConfigurablePattern,
+    current: NaiveDate,
+    end: NaiveDate,
+    in_vacation: bool,
+    vacation_end: NaiveDate,
+    worked_yesterday: bool,
+    days_since_work: u32,
+    // The rest of the current day's commits
+    day: std::vec::IntoIter<CommitInfo>,
+}
+
+impl PatternStream<'_> {
+    // Commits for the next day in time order, or None past the end
+    fn next_day(&mut self) -> Option<Vec<CommitInfo>> {
+        if self.current > self.end {
+            return None;
+        }
+        let current = self.current;
+        self.current = current.succ_opt().unwrap();
+        let config = &self.pattern.config;
+        let mut rng = date_rng(current);
         
-        let mut current = start;
-        while current <= end {
-            let mut rng = date_rng(current);
-            
-            // Check for vacation start
-            if !in_vacation && rng.random::<f64>() < self.config.vacation_frequency {
-                let vacation_days = rng.random_range(
-                    self.config.vacation_duration.0..=self.config.vacation_duration.1
-                );
-                vacation_end = current + chrono::Duration::days(vacation_days as i64);
-                in_vacation = true;
-            }
-            
-            // Skip vacation days
-            if in_vacation {
-                if current >= vacation_end {
-                    in_vacation = false;
-                }
-                worked_yesterday = false;
-                days_since_work += 1;
-                current = current.succ_opt().unwrap();
-                continue;
-            }
-            
-            // Check if working today (with streak tracking)
-            let working_today = self.should_work_today(current, &mut rng, worked_yesterday, days_since_work);
-            
-            if !working_today {
-                worked_yesterday = false;
-                days_since_work += 1;
-                current = current.succ_opt().unwrap();
-                continue;
+        // Check for vacation start
+        if !self.in_vacation && rng.random::<f64>() < config.vacation_frequency {
+            let vacation_days = rng.random_range(config.vacation_duration.0..=config.vacation_duration.1);
+            self.vacation_end = current + chrono::Duration::days(vacation_days as i64);
+            self.in_vacation = true;
+        }
+        
+        // Skip vacation days
+        if self.in_vacation {
+            if current >= self.vacation_end {
+                self.in_vacation = false;
             }
-            
-            // Generate commits for the day
-            let day_commits = self.get_base_commits(current, &mut rng);
-            
-            for _ in 0..day_commits {
+            self.worked_yesterday = false;
+            self.days_since_work += 1;
+            return Some(Vec::new());
+        }
+        
+        // Check if working today (with streak tracking)
+        if !self.pattern.should_work_today(current, &mut rng, self.worked_yesterday, self.days_since_work) {
+            self.worked_yesterday = false;
+            self.days_since_work += 1;
+            return Some(Vec::new());
+        }
+        
+        // Generate commits for the day
+        let day_commits = self.pattern.get_base_commits(current, &mut rng);
+        let mut commits: Vec<CommitInfo> = (0..day_commits)
+            .map(|_| {
                 let hour = rng.random_range(6..=23);
                 let minute = rng.random_range(0..60);
-                commits.push(create_commit_at_time(current, hour, minute));
+                create_commit_at_time(current, hour, minute)
+            })
+            .collect();
+        commits.sort_by_key(|c| c.date);
+        separate_timestamps(&mut commits);
+        
+        // Update streak tracking
+        self.worked_yesterday = true;
+        self.days_since_work = 0;
+        Some(commits)
+    }
+}
+
+impl Iterator for PatternStream<'_> {
+    type Item = CommitInfo;
+    
+    fn next(&mut self) -> Option<CommitInfo> {
+        loop {
+            if let Some(commit) = self.day.next() {
+                return Some(commit);
             }
-            
-            // Update streak tracking
-            worked_yesterday = true;
-            days_since_work = 0;
-            current = current.succ_opt().unwrap();
+            self.day = self.next_day()?.into_iter();
         }
-        
-        commits.sort_by_key(|c| c.date);
-        commits
     }
 }
 
@@ -459,6 +1096,10 @@ pub struct ExtremePattern {
     inner: ConfigurablePattern,
 }
 
+pub struct FourDayPattern {
+    inner: ConfigurablePattern,
+}
+
 // Implementation of all patterns using the new configurable system
 
 impl RealisticPattern {
@@ -469,10 +1110,20 @@ impl RealisticPattern {
     }
 }
 
+impl Default for RealisticPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for RealisticPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl SteadyPattern {
@@ -485,6 +1136,9 @@ impl SteadyPattern {
             vacation_duration: (1, 2),
             spike_probability: 0.02,   // Minimal spikes
             spike_multiplier: 1.2,     // Small spikes
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         };
         Self {
             inner: ConfigurablePattern::new(config),
@@ -492,10 +1146,20 @@ impl SteadyPattern {
     }
 }
 
+impl Default for SteadyPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for SteadyPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl SporadicPattern {
@@ -508,6 +1172,9 @@ impl SporadicPattern {
             vacation_duration: (1, 5),
             spike_probability: 0.15,   // High spike chance
             spike_multiplier: 3.0,     // Big spikes
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         };
         Self {
             inner: ConfigurablePattern::new(config),
@@ -515,10 +1182,20 @@ impl SporadicPattern {
     }
 }
 
+impl Default for SporadicPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for SporadicPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl ContractorPattern {
@@ -531,6 +1208,9 @@ impl ContractorPattern {
             vacation_duration: (2, 4),
             spike_probability: 0.08,
             spike_multiplier: 1.4,
+            volume: 1.0,
+            workweek: Workweek::MON_FRI,
+            day_off: None,
         };
         Self {
             inner: ConfigurablePattern::new(config),
@@ -538,10 +1218,20 @@ impl ContractorPattern {
     }
 }
 
+impl Default for ContractorPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for ContractorPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl CasualPattern {
@@ -552,10 +1242,20 @@ impl CasualPattern {
     }
 }
 
+impl Default for CasualPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for CasualPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl ActivePattern {
@@ -566,10 +1266,20 @@ impl ActivePattern {
     }
 }
 
+impl Default for ActivePattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for ActivePattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl MaintainerPattern {
@@ -580,10 +1290,20 @@ impl MaintainerPattern {
     }
 }
 
+impl Default for MaintainerPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for MaintainerPattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl HyperactivePattern {
@@ -594,10 +1314,20 @@ impl HyperactivePattern {
     }
 }
 
+impl Default for HyperactivePattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for HyperactivePattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
 }
 
 impl ExtremePattern {
@@ -608,8 +1338,236 @@ impl ExtremePattern {
     }
 }
 
+impl Default for ExtremePattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pattern for ExtremePattern {
     fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
         self.inner.generate(start, end)
     }
-}
\ No newline at end of file
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
+}
+
+impl FourDayPattern {
+    // Fridays off
+    pub fn new() -> Self {
+        Self::with_day_off(Weekday::Fri)
+    }
+    
+    pub fn with_day_off(day_off: Weekday) -> Self {
+        Self {
+            inner: ConfigurablePattern::new(PatternConfig::four_day(day_off)),
+        }
+    }
+}
+
+impl Default for FourDayPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pattern for FourDayPattern {
+    fn generate(&self, start: NaiveDate, end: NaiveDate) -> Vec<CommitInfo> {
+        self.inner.generate(start, end)
+    }
+    
+    fn stream<'a>(&'a self, start: NaiveDate, end: NaiveDate) -> Box<dyn Iterator<Item = CommitInfo> + 'a> {
+        self.inner.stream(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    // 2024-01-01 is a Monday
+    fn day(n: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(n)
+    }
+
+    fn days(count: i64) -> Vec<NaiveDate> {
+        (0..count).map(day).collect()
+    }
+
+    // `count` commits on each listed day, spread through the afternoon
+    fn plan(counts: &[(i64, usize)]) -> Vec<CommitInfo> {
+        counts.iter()
+            .flat_map(|&(n, count)| (0..count).map(move |i| create_commit_at_time(day(n), 12 + (i % 10) as u32, (i % 60) as u32)))
+            .collect()
+    }
+
+    fn per_day(commits: &[CommitInfo]) -> BTreeMap<NaiveDate, usize> {
+        let mut counts = BTreeMap::new();
+        for commit in commits {
+            *counts.entry(commit.date.date_naive()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn weekend_commits(commits: &[CommitInfo]) -> usize {
+        commits.iter().filter(|c| matches!(c.date.weekday(), Weekday::Sat | Weekday::Sun)).count()
+    }
+
+    #[test]
+    fn workweek_parses_ranges_and_lists() {
+        assert_eq!(Workweek::parse("Mon-Fri").unwrap(), Workweek::MON_FRI);
+        let gulf = Workweek::parse("Sun-Thu").unwrap();
+        assert_eq!(gulf.workdays(), 5);
+        assert!(gulf.is_workday(Weekday::Sun) && !gulf.is_workday(Weekday::Fri));
+        let split = Workweek::parse("Mon-Wed, Fri").unwrap();
+        assert_eq!(split.workdays(), 4);
+        assert!(!split.is_workday(Weekday::Thu));
+        assert_eq!(Workweek::parse("Sat").unwrap().workdays(), 1);
+        assert!(Workweek::parse("Mon-Funday").is_err());
+        assert!(Workweek::parse("").is_err());
+    }
+
+    #[test]
+    fn workweek_ranges_wrap_around_the_week() {
+        let wrapped = Workweek::parse("Sat-Tue").unwrap();
+        assert_eq!(wrapped.workdays(), 4);
+        assert!(wrapped.is_workday(Weekday::Sun) && !wrapped.is_workday(Weekday::Wed));
+        assert_eq!(Workweek::parse("Tue-Mon").unwrap().workdays(), 7);
+    }
+
+    #[test]
+    fn workweek_position_follows_the_run_of_workdays() {
+        let gulf = Workweek::parse("Sun-Thu").unwrap();
+        assert_eq!(gulf.position(Weekday::Sun), Some((0, 5)));
+        assert_eq!(gulf.position(Weekday::Thu), Some((4, 5)));
+        assert_eq!(gulf.position(Weekday::Fri), None);
+
+        let wrapped = Workweek::parse("Sat-Tue").unwrap();
+        assert_eq!(wrapped.position(Weekday::Sat), Some((0, 4)));
+        assert_eq!(wrapped.position(Weekday::Tue), Some((3, 4)));
+
+        let split = Workweek::parse("Mon-Wed,Fri").unwrap();
+        assert_eq!(split.position(Weekday::Wed), Some((2, 3)));
+        assert_eq!(split.position(Weekday::Fri), Some((0, 1)));
+    }
+
+    #[test]
+    fn seven_day_workweek_starts_on_monday() {
+        let always = Workweek::parse("Mon-Sun").unwrap();
+        assert_eq!(always.position(Weekday::Mon), Some((0, 7)));
+        assert_eq!(always.position(Weekday::Sun), Some((6, 7)));
+        assert_eq!(Workweek::parse("Thu-Wed").unwrap().position(Weekday::Thu), Some((3, 7)));
+    }
+
+    #[test]
+    fn parse_share_accepts_fractions_and_percentages() {
+        assert_eq!(parse_share("0.7").unwrap(), 0.7);
+        assert_eq!(parse_share("70%").unwrap(), 0.7);
+        assert_eq!(parse_share(" 25 % ").unwrap(), 0.25);
+        assert_eq!(parse_share("0").unwrap(), 0.0);
+        assert_eq!(parse_share("100%").unwrap(), 1.0);
+        for invalid in ["1.5", "120%", "-10%", "seventy", "%", ""] {
+            assert!(parse_share(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn fit_to_total_adds_only_to_active_days() {
+        let commits = plan(&[(0, 2), (3, 1), (9, 4)]);
+        let fitted = fit_to_total(commits, 20, &days(30));
+        assert_eq!(fitted.len(), 20);
+        let counts = per_day(&fitted);
+        assert_eq!(counts.keys().copied().collect::<Vec<_>>(), vec![day(0), day(3), day(9)]);
+        assert!(fitted.windows(2).all(|pair| pair[0].date <= pair[1].date));
+    }
+
+    #[test]
+    fn fit_to_total_thins_busy_days_before_emptying_any() {
+        let commits = plan(&[(0, 5), (1, 1), (2, 3), (5, 1)]);
+        let fitted = fit_to_total(commits, 4, &days(30));
+        assert_eq!(fitted.len(), 4);
+        assert_eq!(per_day(&fitted).values().copied().collect::<Vec<_>>(), vec![1, 1, 1, 1]);
+
+        let emptied = fit_to_total(plan(&[(0, 2), (1, 1), (2, 1)]), 2, &days(30));
+        assert_eq!(per_day(&emptied).values().copied().collect::<Vec<_>>(), vec![1, 1]);
+        assert!(fit_to_total(plan(&[(0, 3)]), 0, &days(30)).is_empty());
+    }
+
+    #[test]
+    fn fit_to_total_seeds_weekdays_of_an_empty_plan() {
+        let fitted = fit_to_total(Vec::new(), 12, &days(14));
+        assert_eq!(fitted.len(), 12);
+        assert_eq!(weekend_commits(&fitted), 0);
+        assert!(fit_to_total(Vec::new(), 5, &[]).is_empty());
+    }
+
+    #[test]
+    fn clamp_per_day_keeps_the_total_within_limits() {
+        let commits = plan(&[(0, 10), (2, 1), (3, 1), (4, 3), (7, 1), (8, 6)]);
+        let (clamped, leftover) = clamp_per_day(commits, 2, Some(4), &days(28), &BTreeMap::new(), None);
+        assert_eq!(clamped.len() + leftover, 22);
+        assert_eq!(leftover, 0);
+        assert!(per_day(&clamped).values().all(|&count| (2..=4).contains(&count)));
+    }
+
+    #[test]
+    fn clamp_per_day_counts_existing_commits() {
+        let existing = BTreeMap::from([(day(0), 3), (day(1), 1)]);
+        let commits = plan(&[(0, 4), (1, 1), (2, 2)]);
+        let (clamped, leftover) = clamp_per_day(commits, 2, Some(4), &days(10), &existing, None);
+        assert_eq!(clamped.len() + leftover, 7);
+        for (date, count) in per_day(&clamped) {
+            let total = count + existing.get(&date).copied().unwrap_or(0) as usize;
+            assert!((2..=4).contains(&total), "{}: {}", date, total);
+        }
+    }
+
+    #[test]
+    fn clamp_per_day_reports_commits_with_nowhere_to_go() {
+        let (clamped, leftover) = clamp_per_day(plan(&[(0, 9)]), 1, Some(2), &days(3), &BTreeMap::new(), None);
+        assert_eq!(clamped.len(), 6);
+        assert_eq!(leftover, 3);
+    }
+
+    #[test]
+    fn rebalance_weekends_hits_the_ratio_and_keeps_the_total() {
+        let commits = plan(&[(0, 6), (1, 4), (2, 5), (7, 3), (8, 2)]);
+        let balanced = rebalance_weekends(commits.clone(), 0.3, &days(14), None);
+        assert_eq!(balanced.len(), 20);
+        assert_eq!(weekend_commits(&balanced), 6);
+
+        let weekday_only = rebalance_weekends(balanced, 0.0, &days(14), None);
+        assert_eq!(weekday_only.len(), 20);
+        assert_eq!(weekend_commits(&weekday_only), 0);
+    }
+
+    #[test]
+    fn set_active_days_merges_and_splits_keeping_the_total() {
+        let commits = plan(&[(0, 1), (1, 1), (2, 2), (3, 1), (4, 5), (7, 1)]);
+        let fewer = set_active_days(commits.clone(), 3, &days(14), None);
+        assert_eq!(fewer.len(), 11);
+        assert_eq!(per_day(&fewer).len(), 3);
+
+        let more = set_active_days(commits, 9, &days(14), None);
+        assert_eq!(more.len(), 11);
+        assert_eq!(per_day(&more).len(), 9);
+    }
+
+    #[test]
+    fn reshaping_never_moves_commits_past_not_after() {
+        let limit = day(9).and_hms_opt(12, 0, 0).unwrap().and_local_timezone(Local).unwrap();
+        let allowed = days(10);
+        let commits = plan(&[(0, 8), (2, 1), (5, 4), (6, 2)]);
+        let within = |commits: &[CommitInfo]| commits.iter().all(|c| c.date <= limit);
+
+        for _ in 0..20 {
+            let (clamped, _) = clamp_per_day(commits.clone(), 3, Some(3), &allowed, &BTreeMap::new(), Some(limit));
+            assert!(within(&clamped));
+            assert!(within(&rebalance_weekends(commits.clone(), 0.0, &allowed, Some(limit))));
+            assert!(within(&set_active_days(commits.clone(), 10, &allowed, Some(limit))));
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDate;
+use rand::{rng, Rng};
+use serde::{Deserialize, Serialize};
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::{create_commit_at_time, separate_timestamps, CommitInfo, PatternConfig};
+
+// Hours that commits built from bare per-day counts are spread over
+const COUNT_HOURS: (u32, u32) = (9, 22);
+
+// A generated commit schedule together with what produced it, so it can be
+// saved, reviewed or edited and executed later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Plan {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<PatternConfig>,
+    #[serde(default)]
+    pub commits: Vec<CommitInfo>,
+}
+
+impl Plan {
+    pub fn new(start: NaiveDate, end: NaiveDate, commits: Vec<CommitInfo>) -> Self {
+        Self { start, end, pattern: None, config: None, commits }
+    }
+
+    // One commit per count on each day, at random working-hours times
+    pub fn from_counts(counts: &BTreeMap<NaiveDate, u32>) -> Result<Self> {
+        let (Some(&start), Some(&end)) = (counts.keys().next(), counts.keys().next_back()) else {
+            return Err(GitHubGridError::Config("No days to plan".to_string()));
+        };
+        let mut rng = rng();
+        let mut commits = Vec::new();
+        for (&day, &count) in counts {
+            let mut day_commits: Vec<CommitInfo> = (0..count)
+                .map(|_| create_commit_at_time(day, rng.random_range(COUNT_HOURS.0..=COUNT_HOURS.1), rng.random_range(0..60)))
+                .collect();
+            day_commits.sort_by_key(|c| c.date);
+            separate_timestamps(&mut day_commits);
+            commits.extend(day_commits);
+        }
+        Ok(Self::new(start, end, commits))
+    }
+    
+    pub fn with_pattern(mut self, name: &str) -> Self {
+        self.pattern = Some(name.to_string());
+        self
+    }
+
+    pub fn with_config(mut self, config: PatternConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn total(&self) -> usize {
+        self.commits.len()
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| GitHubGridError::Parse(format!("Failed to serialize plan: {}", e)))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let plan: Self = toml::from_str(content)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid plan: {}", e)))?;
+        if let Some(config) = &plan.config {
+            config.validate()?;
+        }
+        Ok(plan)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+}
+
+// `date,count` lines, with an optional header row. Repeated dates add up.
+pub fn parse_counts_csv(content: &str) -> Result<BTreeMap<NaiveDate, u32>> {
+    let mut counts = BTreeMap::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || GitHubGridError::Parse(format!("Line {}: expected `YYYY-MM-DD,count`, got '{}'", index + 1, line));
+        let (date, count) = line.split_once(',').ok_or_else(invalid)?;
+        let Ok(date) = NaiveDate::parse_from_str(date.trim().trim_matches('"'), "%Y-%m-%d") else {
+            if index == 0 {
+                continue; // Header
+            }
+            return Err(invalid());
+        };
+        let count: u32 = count.trim().trim_matches('"').parse().map_err(|_| invalid())?;
+        *counts.entry(date).or_insert(0) += count;
+    }
+    if counts.is_empty() {
+        return Err(GitHubGridError::Config("Counts file has no rows".to_string()));
+    }
+    Ok(counts)
+}
+
+// Mixes a pattern's day-to-day shape into the counts: each day becomes
+// `(1 - share) * count + share * pattern`, with the pattern scaled to the
+// same total so the overall volume is kept
+pub fn blend_counts(counts: &BTreeMap<NaiveDate, u32>, pattern: &[CommitInfo], share: f64) -> BTreeMap<NaiveDate, u32> {
+    let mut pattern_days: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for commit in pattern {
+        *pattern_days.entry(commit.date.date_naive()).or_insert(0) += 1;
+    }
+    let (Some(&start), Some(&end)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return counts.clone();
+    };
+    let total: u32 = counts.values().sum();
+    let pattern_total: u32 = pattern_days.range(start..=end).map(|(_, c)| c).sum();
+    let scale = if pattern_total > 0 { total as f64 / pattern_total as f64 } else { 0.0 };
+
+    start.iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| {
+            let own = counts.get(&day).copied().unwrap_or(0) as f64;
+            let shaped = pattern_days.get(&day).copied().unwrap_or(0) as f64 * scale;
+            (day, ((1.0 - share) * own + share * shaped).round() as u32)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration as StdDuration;
+use chrono::{Duration, NaiveDate};
+use ratatui::crossterm::{cursor, queue, terminal};
+use crate::readme_stats::{quartile_levels, week_rows, LEVELS};
+
+const WEEKDAY_LABELS: [&str; 7] = ["", "Mon", "", "Wed", "", "Fri", ""];
+// Seven grid rows, a blank line and the status line
+const FRAME_LINES: u16 = 9;
+// Days without a commit before the status calls it a break
+const QUIET_DAYS: u32 = 5;
+
+// Replays start..=end a day at a time, redrawing the grid in place with
+// the day's count, running total and streak underneath. Shades are cut on
+// the whole range so days keep their colour as the plan fills in.
+pub fn play(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate, days_per_second: f64) -> io::Result<()> {
+    let level = quartile_levels(days, start, end);
+    let delay = StdDuration::from_secs_f64(1.0 / days_per_second);
+    let mut out = io::stdout();
+    let (mut total, mut streak, mut longest, mut quiet) = (0u32, 0u32, 0u32, 0u32);
+
+    let mut current = start;
+    while current <= end {
+        let count = days.get(&current).copied().unwrap_or(0);
+        total += count;
+        (streak, quiet) = if count > 0 { (streak + 1, 0) } else { (0, quiet + 1) };
+        longest = longest.max(streak);
+
+        if current > start {
+            queue!(out, cursor::MoveUp(FRAME_LINES), terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        }
+        let rows = week_rows(start, end, |day| match day {
+            day if day > current => ' ',
+            day => LEVELS[level(days.get(&day).copied().unwrap_or(0))],
+        });
+        for (label, row) in WEEKDAY_LABELS.iter().zip(rows) {
+            writeln!(out, "  {:<4}{}", label, row.trim_end())?;
+        }
+        let note = match (level(count), quiet) {
+            (4, _) => " 🔥 spike".to_string(),
+            (_, quiet) if quiet >= QUIET_DAYS => format!(" 🏖️  quiet for {} days", quiet),
+            _ => String::new(),
+        };
+        writeln!(out)?;
+        writeln!(out, "  {}  {:>3} commits   total {:>5}   streak {:>3} (longest {}){}",
+            current.format("%a %b %d %Y"), count, total, streak, longest, note)?;
+        out.flush()?;
+
+        thread::sleep(delay);
+        current += Duration::days(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,416 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use indicatif::{BinaryBytes, HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
+use github_grid::git_ops::PushProgress;
+use github_grid::patterns::CommitInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Interactive indicatif progress bar
+    Bar,
+    /// Machine-readable JSON-lines events on stdout
+    Json,
+}
+
+// Running totals for the current run, used for end-of-run summaries
+#[derive(Debug, Clone, Default)]
+pub struct RunStats {
+    pub repo: Option<String>,
+    pub pattern: Option<String>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub planned: usize,
+    pub created: usize,
+    pub pushed: usize,
+    // Branch tip before the run and the first/last commits it created
+    pub base_commit: Option<String>,
+    pub first_commit: Option<String>,
+    pub last_commit: Option<String>,
+    // Noise seed of an --organic run, for reproducing its shape
+    pub seed: Option<u64>,
+    // Commits per day as planned and as actually created
+    pub planned_days: BTreeMap<NaiveDate, u32>,
+    pub created_days: BTreeMap<NaiveDate, u32>,
+    pub warnings: Vec<String>,
+    // Created commits the contribution graph accounts for, when verified
+    pub on_graph: Option<usize>,
+}
+
+// Push durations so far, for estimating the time left in a run
+#[derive(Debug, Default)]
+struct PushTiming {
+    batch_size: Option<usize>,
+    started: Option<Instant>,
+    label: String,
+    elapsed: Duration,
+    batches: u32,
+}
+
+// Reports run progress either as terminal progress bars or as JSON-lines
+// events, so wrappers and GUIs don't have to scrape indicatif output. The
+// bars are one line per phase: plan generation (a spinner until the plan
+// is ready), commit creation, the current push, and an overall ETA.
+pub struct ProgressReporter {
+    mode: ProgressMode,
+    multi: MultiProgress,
+    generation: Option<ProgressBar>,
+    bar: Option<ProgressBar>,
+    push: RefCell<Option<ProgressBar>>,
+    overall: Option<ProgressBar>,
+    timing: RefCell<PushTiming>,
+    stats: RefCell<RunStats>,
+}
+
+fn phase_style(template: &str) -> ProgressStyle {
+    ProgressStyle::default_bar().template(template).unwrap()
+}
+
+impl ProgressReporter {
+    pub fn new(mode: ProgressMode) -> Self {
+        Self {
+            mode,
+            multi: MultiProgress::new(),
+            generation: None,
+            bar: None,
+            push: RefCell::new(None),
+            overall: None,
+            timing: RefCell::new(PushTiming::default()),
+            stats: RefCell::new(RunStats::default()),
+        }
+    }
+
+    pub fn stats(&self) -> RunStats {
+        self.stats.borrow().clone()
+    }
+
+    pub fn target(&self, repo: &str) {
+        self.stats.borrow_mut().repo = Some(repo.to_string());
+    }
+
+    pub fn pattern(&self, name: &str) {
+        self.stats.borrow_mut().pattern = Some(name.to_string());
+    }
+
+    pub fn base_commit(&self, oid: Option<String>) {
+        self.stats.borrow_mut().base_commit = oid;
+    }
+
+    pub fn seed(&self, seed: u64) {
+        self.stats.borrow_mut().seed = Some(seed);
+    }
+
+    pub fn verified(&self, on_graph: usize) {
+        self.stats.borrow_mut().on_graph = Some(on_graph);
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.mode == ProgressMode::Json
+    }
+
+    // Human-readable status line; kept off stdout in JSON mode, and printed
+    // above the bars rather than through them
+    pub fn info(&self, message: &str) {
+        match self.mode {
+            ProgressMode::Bar => self.multi.suspend(|| println!("{}", message)),
+            ProgressMode::Json => eprintln!("{}", message),
+        }
+    }
+
+    // Like `info`, and kept for the run report
+    pub fn warn(&self, message: &str) {
+        self.stats.borrow_mut().warnings.push(message.to_string());
+        self.info(&format!("⚠️  {}", message));
+    }
+
+    // Spinner shown while the plan is generated; cleared by `generated`
+    pub fn start_generation(&mut self) {
+        if self.is_json() {
+            return;
+        }
+        let pb = self.multi.add(ProgressBar::new_spinner());
+        pb.set_style(phase_style("{prefix:>8.bold} {spinner:.green} {msg} [{elapsed}]"));
+        pb.set_prefix("Plan");
+        pb.set_message("generating...");
+        pb.enable_steady_tick(Duration::from_millis(100));
+        self.generation = Some(pb);
+    }
+
+    fn clear_generation(&self) {
+        if let Some(pb) = &self.generation && !pb.is_finished() {
+            pb.finish_and_clear();
+            self.multi.remove(pb);
+        }
+    }
+
+    pub fn generated(&self, commits: &[CommitInfo], start: NaiveDate, end: NaiveDate) {
+        self.clear_generation();
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.planned = commits.len();
+            stats.planned_days.clear();
+            for commit in commits {
+                *stats.planned_days.entry(commit.date.date_naive()).or_insert(0) += 1;
+            }
+            stats.start = Some(start);
+            stats.end = Some(end);
+        }
+
+        if self.is_json() {
+            emit(&[
+                ("event", json_str("generated")),
+                ("total", commits.len().to_string()),
+                ("start", json_str(&start.to_string())),
+                ("end", json_str(&end.to_string())),
+            ]);
+        }
+    }
+
+    // Like `generated` for a streamed run, whose total isn't known up front
+    pub fn streaming(&self, start: NaiveDate, end: NaiveDate) {
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.start = Some(start);
+            stats.end = Some(end);
+        }
+
+        if self.is_json() {
+            emit(&[
+                ("event", json_str("streaming")),
+                ("start", json_str(&start.to_string())),
+                ("end", json_str(&end.to_string())),
+            ]);
+        }
+    }
+
+    // A bar when the total is known, otherwise a spinner with a running
+    // count, plus the overall line. `batch_size` is how many commits go in
+    // each push (None when not pushing), for the ETA.
+    pub fn start_commits(&mut self, total: Option<usize>, batch_size: Option<usize>) {
+        self.timing.borrow_mut().batch_size = batch_size.map(|size| size.max(1));
+        if self.is_json() {
+            return;
+        }
+
+        let (pb, template) = match total {
+            Some(total) => (
+                ProgressBar::new(total as u64),
+                "{prefix:>8.bold} {spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}",
+            ),
+            None => (ProgressBar::new_spinner(), "{prefix:>8.bold} {spinner:.green} {pos} commits {msg}"),
+        };
+        let pb = self.multi.add(pb);
+        pb.set_style(phase_style(template));
+        pb.set_prefix("Commits");
+        self.bar = Some(pb);
+
+        let overall = self.multi.add(ProgressBar::new_spinner());
+        overall.set_style(phase_style("{prefix:>8.bold} [{elapsed_precise}] {msg}"));
+        overall.set_prefix("Overall");
+        overall.enable_steady_tick(Duration::from_millis(500));
+        self.overall = Some(overall);
+        self.update_eta();
+    }
+
+    // Time left: the commits still to create at the current rate, plus
+    // their pushes at the average push time so far
+    fn update_eta(&self) {
+        let (Some(overall), Some(pb)) = (&self.overall, &self.bar) else {
+            return;
+        };
+        let timing = self.timing.borrow();
+        let Some(total) = pb.length() else {
+            overall.set_message("streaming, total unknown".to_string());
+            return;
+        };
+        let created = pb.position();
+        let remaining = total.saturating_sub(created);
+        let commit_secs = if created == 0 { 0.0 } else { remaining as f64 / pb.per_sec().max(f64::EPSILON) };
+        let (push_secs, waiting_for_push) = match timing.batch_size {
+            None => (0.0, false),
+            Some(batch_size) => {
+                let pushed = self.stats.borrow().pushed as u64;
+                let batches_left = total.saturating_sub(pushed).div_ceil(batch_size as u64);
+                match timing.batches {
+                    0 => (0.0, created >= batch_size as u64),
+                    n => (timing.elapsed.as_secs_f64() / n as f64 * batches_left as f64, false),
+                }
+            }
+        };
+        let suffix = if timing.batch_size.is_some() { " including pushes" } else { "" };
+        if created == 0 || waiting_for_push {
+            overall.set_message("estimating time left...".to_string());
+        } else {
+            overall.set_message(format!("~{} left{}", HumanDuration(Duration::from_secs_f64(commit_secs + push_secs)), suffix));
+        }
+    }
+
+    pub fn committed(&self, commit: &CommitInfo, index: usize, total: Option<usize>, oid: &str) {
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.created += 1;
+            stats.first_commit.get_or_insert_with(|| oid.to_string());
+            stats.last_commit = Some(oid.to_string());
+            *stats.created_days.entry(commit.date.date_naive()).or_insert(0) += 1;
+        }
+
+        match &self.bar {
+            Some(pb) => {
+                pb.set_message(format!("Committing {}", commit.date.format("%Y-%m-%d %H:%M")));
+                pb.inc(1);
+                self.update_eta();
+            }
+            None if self.is_json() => emit(&[
+                ("event", json_str("committed")),
+                ("index", (index + 1).to_string()),
+                ("total", total.map_or("null".to_string(), |total| total.to_string())),
+                ("date", json_str(&commit.date.to_rfc3339())),
+                ("oid", json_str(oid)),
+            ]),
+            None => {}
+        }
+    }
+
+    pub fn batch(&self, batch: usize, size: usize, is_final: bool) {
+        self.timing.borrow_mut().started = Some(Instant::now());
+        match &self.bar {
+            Some(_) => {
+                let mut push = self.push.borrow_mut();
+                let pb = push.get_or_insert_with(|| {
+                    let pb = match &self.overall {
+                        Some(overall) => self.multi.insert_before(overall, ProgressBar::new_spinner()),
+                        None => self.multi.add(ProgressBar::new_spinner()),
+                    };
+                    pb.set_style(phase_style("{prefix:>8.bold} {spinner:.green} {msg}"));
+                    pb.set_prefix("Push");
+                    pb.enable_steady_tick(Duration::from_millis(100));
+                    pb
+                });
+                let kind = if is_final { "final batch" } else { "batch" };
+                let label = format!("{} {}", kind, batch);
+                pb.set_message(format!("{}: pushing {} commits...", label, size));
+                self.timing.borrow_mut().label = label;
+            }
+            None if self.is_json() => emit(&[
+                ("event", json_str("batch")),
+                ("batch", batch.to_string()),
+                ("size", size.to_string()),
+                ("final", is_final.to_string()),
+            ]),
+            None => {}
+        }
+    }
+
+    // One of git's progress lines for the push in flight
+    pub fn push_progress(&self, progress: &PushProgress) {
+        if self.is_json() {
+            emit(&[
+                ("event", json_str("push_progress")),
+                ("stage", json_str(&progress.stage)),
+                ("current", progress.current.to_string()),
+                ("total", progress.total.to_string()),
+                ("bytes", progress.bytes.map_or("null".to_string(), |bytes| bytes.to_string())),
+            ]);
+            return;
+        }
+        if let Some(pb) = self.push.borrow().as_ref() {
+            let bytes = progress.bytes.map_or(String::new(), |bytes| format!(", {}", BinaryBytes(bytes)));
+            pb.set_message(format!(
+                "{}: {} {}/{}{}",
+                self.timing.borrow().label, progress.stage.to_lowercase(), progress.current, progress.total, bytes
+            ));
+        }
+    }
+
+    pub fn pushed(&self, batch: usize, pushed_total: usize) {
+        self.stats.borrow_mut().pushed = pushed_total;
+        let took = {
+            let mut timing = self.timing.borrow_mut();
+            let took = timing.started.take().map(|started| started.elapsed()).unwrap_or_default();
+            timing.elapsed += took;
+            timing.batches += 1;
+            took
+        };
+        if let Some(pb) = self.push.borrow().as_ref() {
+            pb.set_message(format!("batch {} pushed in {:.1}s, {} commits on the remote", batch, took.as_secs_f64(), pushed_total));
+        }
+        self.update_eta();
+
+        if self.is_json() {
+            emit(&[
+                ("event", json_str("pushed")),
+                ("batch", batch.to_string()),
+                ("pushed_total", pushed_total.to_string()),
+            ]);
+        }
+    }
+
+    pub fn error(&self, error: &dyn std::error::Error) {
+        self.clear_generation();
+        for pb in [&*self.push.borrow(), &self.overall].into_iter().flatten() {
+            pb.abandon();
+        }
+        match &self.bar {
+            Some(pb) => pb.abandon_with_message(format!("❌ {}", error)),
+            None if self.is_json() => emit(&[
+                ("event", json_str("error")),
+                ("message", json_str(&error.to_string())),
+            ]),
+            None => {}
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(pb) = &self.bar {
+            pb.finish_with_message("✅ All commits created successfully!");
+        }
+        if let Some(pb) = self.push.borrow().as_ref() {
+            let timing = self.timing.borrow();
+            pb.finish_with_message(format!(
+                "✅ {} commits pushed in {} batch(es), {:.1}s total",
+                self.stats.borrow().pushed, timing.batches, timing.elapsed.as_secs_f64()
+            ));
+        }
+        if let Some(pb) = &self.overall {
+            pb.finish_with_message("done".to_string());
+        }
+    }
+}
+
+// Write one JSON object per line; values must already be JSON-encoded
+fn emit(fields: &[(&str, String)]) {
+    let body = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_str(key), value))
+        .collect::<Vec<_>>()
+        .join(",");
+    println!("{{{}}}", body);
+}
+
+pub fn json_str(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A run that stops before its plan is ready (nothing to do, a failed
+// lookup) shouldn't leave the spinner on screen
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.clear_generation();
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, Duration, NaiveDate};
+
+pub const START_MARKER: &str = "<!-- github-grid:stats -->";
+pub const END_MARKER: &str = "<!-- /github-grid:stats -->";
+
+// Shades from no commits to the busiest quarter of active days
+pub const LEVELS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+// Markdown section with the year's grid so far (GitHub's layout: one
+// column per week, Sunday on top) and summary stats
+pub fn render(days: &BTreeMap<NaiveDate, u32>, today: NaiveDate) -> String {
+    let year = today.year();
+    let start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    let in_year: BTreeMap<NaiveDate, u32> = days.range(start..=today).map(|(d, c)| (*d, *c)).collect();
+
+    let rows = grid_rows(&in_year, start, today);
+
+    let total: u32 = in_year.values().sum();
+    let (longest, current) = streaks(&in_year, start, today);
+    let busiest = in_year.iter().max_by_key(|(day, count)| (**count, std::cmp::Reverse(**day)));
+
+    let mut section = format!("{}\n## Activity in {}\n\n```\n", START_MARKER, year);
+    for row in rows {
+        section.push_str(row.trim_end());
+        section.push('\n');
+    }
+    section.push_str(&format!("```\n\nLess {} More\n\n", LEVELS.iter().collect::<String>()));
+    section.push_str("| Commits | Active days | Longest streak | Current streak | Busiest day |\n");
+    section.push_str("|--------:|------------:|---------------:|---------------:|-------------|\n");
+    section.push_str(&format!("| {} | {} | {} days | {} days | {} |\n\n",
+        total,
+        in_year.values().filter(|&&c| c > 0).count(),
+        longest,
+        current,
+        busiest.map_or("-".to_string(), |(day, count)| format!("{} ({} commits)", day.format("%b %-d"), count)),
+    ));
+    section.push_str(&format!("_Updated {}_\n{}", today, END_MARKER));
+    section
+}
+
+// Shaded grid for start..=end in GitHub's layout: one row per weekday,
+// Sunday first, one column per week
+pub fn grid_rows(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate) -> Vec<String> {
+    let level = quartile_levels(days, start, end);
+    week_rows(start, end, |day| LEVELS[level(days.get(&day).copied().unwrap_or(0))])
+}
+
+// Maps a day's count to a shade from 0 to 4. Levels are cut at the
+// quartiles of the active days in start..=end, like GitHub's legend.
+pub fn quartile_levels(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate) -> impl Fn(u32) -> usize {
+    let thresholds = quartile_thresholds(days, start, end);
+    move |count: u32| match count {
+        0 => 0,
+        c if c <= thresholds[0] => 1,
+        c if c <= thresholds[1] => 2,
+        c if c <= thresholds[2] => 3,
+        _ => 4,
+    }
+}
+
+// Highest count shaded at levels 1, 2 and 3; anything above the last is 4
+pub fn quartile_thresholds(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, end: NaiveDate) -> [u32; 3] {
+    let mut active: Vec<u32> = days.range(start..=end).map(|(_, c)| *c).filter(|&c| c > 0).collect();
+    active.sort_unstable();
+    let quartile = |q: usize| active.get((active.len() * q / 4).min(active.len().saturating_sub(1))).copied().unwrap_or(0);
+    [quartile(1), quartile(2), quartile(3)]
+}
+
+// "Less · 0  ░ 1-3  ▒ 4-6  ▓ 7-10  █ 11+ More" for the given thresholds.
+// Levels no count falls into (when quartiles coincide) are shown as "-".
+pub fn legend(thresholds: [u32; 3]) -> String {
+    let bounds = [0, 1, thresholds[0] + 1, thresholds[1] + 1, thresholds[2] + 1];
+    let mut parts = vec![format!("{} 0", LEVELS[0])];
+    for level in 1..4 {
+        let (low, high) = (bounds[level], thresholds[level - 1]);
+        let range = match low.cmp(&high) {
+            std::cmp::Ordering::Less => format!("{}-{}", low, high),
+            std::cmp::Ordering::Equal => low.to_string(),
+            std::cmp::Ordering::Greater => "-".to_string(),
+        };
+        parts.push(format!("{} {}", LEVELS[level], range));
+    }
+    parts.push(format!("{} {}+", LEVELS[4], bounds[4].max(1)));
+    format!("Less {} More", parts.join("  "))
+}
+
+// Grid with one cell per day in start..=end, padded with spaces before
+// `start` so every column is a Sunday-to-Saturday week
+pub fn week_rows(start: NaiveDate, end: NaiveDate, cell: impl Fn(NaiveDate) -> char) -> Vec<String> {
+    let first_sunday = start - Duration::days(start.weekday().num_days_from_sunday() as i64);
+    let mut rows = vec![String::new(); 7];
+    let mut day = first_sunday;
+    while day <= end {
+        rows[day.weekday().num_days_from_sunday() as usize].push(if day < start { ' ' } else { cell(day) });
+        day += Duration::days(1);
+    }
+    rows
+}
+
+// Month names over the columns of `week_rows` for the same range, each at
+// the first week the month starts in
+pub fn month_labels(start: NaiveDate, end: NaiveDate) -> String {
+    let first_sunday = start - Duration::days(start.weekday().num_days_from_sunday() as i64);
+    let mut labels = String::new();
+    let mut week_start = first_sunday;
+    while week_start <= end {
+        let column = ((week_start - first_sunday).num_days() / 7) as usize;
+        let month_begins = (0..7)
+            .map(|offset| week_start + Duration::days(offset))
+            .find(|day| *day >= start && *day <= end && (day.day() == 1 || *day == start));
+        // Only label when there's room for a space after the last name
+        if let Some(day) = month_begins.filter(|_| labels.is_empty() || labels.chars().count() < column) {
+            labels.push_str(&" ".repeat(column - labels.chars().count()));
+            labels.push_str(&day.format("%b").to_string());
+        }
+        week_start += Duration::days(7);
+    }
+    labels
+}
+
+// `week_rows` under a line of month names, with Mon/Wed/Fri labels down the
+// side as on the profile
+pub fn labelled_grid(start: NaiveDate, end: NaiveDate, cell: impl Fn(NaiveDate) -> char) -> Vec<String> {
+    const WEEKDAY_LABELS: [&str; 7] = ["", "Mon", "", "Wed", "", "Fri", ""];
+    let mut lines = vec![format!("    {}", month_labels(start, end)).trim_end().to_string()];
+    for (label, row) in WEEKDAY_LABELS.iter().zip(week_rows(start, end, cell)) {
+        lines.push(format!("{:<4}{}", label, row).trim_end().to_string());
+    }
+    lines
+}
+
+// Longest run of consecutive active days in start..=today, and the run
+// ending today (or yesterday, since today may not be over)
+pub fn streaks(days: &BTreeMap<NaiveDate, u32>, start: NaiveDate, today: NaiveDate) -> (u32, u32) {
+    let active = |day: NaiveDate| days.get(&day).is_some_and(|&c| c > 0);
+    let mut longest = 0;
+    let mut run = 0;
+    let mut day = start;
+    while day <= today {
+        run = if active(day) { run + 1 } else { 0 };
+        longest = longest.max(run);
+        day += Duration::days(1);
+    }
+
+    let mut current = 0;
+    let mut day = if active(today) { today } else { today - Duration::days(1) };
+    while day >= start && active(day) {
+        current += 1;
+        day -= Duration::days(1);
+    }
+    (longest, current)
+}
+
+// `readme` with the stats section replaced, or appended if it has none yet
+pub fn update(readme: &str, section: &str) -> String {
+    let markers = (readme.find(START_MARKER), readme.find(END_MARKER));
+    if let (Some(start), Some(end)) = markers && start < end {
+        return format!("{}{}{}", &readme[..start], section, &readme[end + END_MARKER.len()..]);
+    }
+    let separator = if readme.is_empty() || readme.ends_with("\n\n") {
+        ""
+    } else if readme.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{}{}{}\n", readme, separator, section)
+}
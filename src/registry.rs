@@ -0,0 +1,97 @@
+use std::sync::{LazyLock, RwLock};
+use crate::error::{GitHubGridError, Result};
+use crate::patterns::*;
+
+// Builds a fresh pattern instance for each run
+pub type PatternFactory = fn() -> Box<dyn Pattern>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternCategory {
+    Activity,
+    Legacy,
+    Custom,
+}
+
+#[derive(Clone)]
+pub struct PatternEntry {
+    pub name: String,
+    pub description: String,
+    pub category: PatternCategory,
+    factory: PatternFactory,
+}
+
+impl PatternEntry {
+    pub fn create(&self) -> Box<dyn Pattern> {
+        (self.factory)()
+    }
+}
+
+// Process-wide table of named patterns. Built-ins are registered on first
+// use; downstream crates add their own with `PatternRegistry::register`
+// before handing control to the CLI or calling `create`.
+pub struct PatternRegistry;
+
+static REGISTRY: LazyLock<RwLock<Vec<PatternEntry>>> = LazyLock::new(|| RwLock::new(builtin_patterns()));
+
+impl PatternRegistry {
+    // Registers a pattern under `name`, replacing any existing entry
+    pub fn register(name: &str, description: &str, factory: PatternFactory) {
+        insert(PatternEntry {
+            name: name.to_string(),
+            description: description.to_string(),
+            category: PatternCategory::Custom,
+            factory,
+        });
+    }
+
+    pub fn create(name: &str) -> Result<Box<dyn Pattern>> {
+        let entries = REGISTRY.read().unwrap();
+        match entries.iter().find(|entry| entry.name == name) {
+            Some(entry) => Ok(entry.create()),
+            None => Err(GitHubGridError::Config(format!(
+                "Unknown pattern: {} (available: {})",
+                name,
+                entries.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+
+    pub fn contains(name: &str) -> bool {
+        REGISTRY.read().unwrap().iter().any(|entry| entry.name == name)
+    }
+
+    // All entries in registration order
+    pub fn entries() -> Vec<PatternEntry> {
+        REGISTRY.read().unwrap().clone()
+    }
+}
+
+fn insert(entry: PatternEntry) {
+    let mut entries = REGISTRY.write().unwrap();
+    match entries.iter_mut().find(|existing| existing.name == entry.name) {
+        Some(existing) => *existing = entry,
+        None => entries.push(entry),
+    }
+}
+
+fn builtin_patterns() -> Vec<PatternEntry> {
+    let builtin = |name: &str, description: &str, category, factory| PatternEntry {
+        name: name.to_string(),
+        description: description.to_string(),
+        category,
+        factory,
+    };
+
+    vec![
+        builtin("casual", "Weekend warrior, occasional PRs (~300/year)", PatternCategory::Activity, || Box::new(CasualPattern::new())),
+        builtin("realistic", "Professional developer activity (~1,200/year)", PatternCategory::Activity, || Box::new(RealisticPattern::new())),
+        builtin("active", "Multiple projects, good practices (~2,500/year)", PatternCategory::Activity, || Box::new(ActivePattern::new())),
+        builtin("maintainer", "Managing repos, reviewing PRs (~5,000/year)", PatternCategory::Activity, || Box::new(MaintainerPattern::new())),
+        builtin("hyperactive", "Startup pace, heavy open source (~12,000/year)", PatternCategory::Activity, || Box::new(HyperactivePattern::new())),
+        builtin("extreme", "AI-assisted development (~20,000+/year)", PatternCategory::Activity, || Box::new(ExtremePattern::new())),
+        builtin("four-day", "Four-day week, Fridays off (~2,500/year)", PatternCategory::Activity, || Box::new(FourDayPattern::new())),
+        builtin("steady", "Consistent daily activity", PatternCategory::Legacy, || Box::new(SteadyPattern::new())),
+        builtin("sporadic", "Irregular bursts of activity", PatternCategory::Legacy, || Box::new(SporadicPattern::new())),
+        builtin("contractor", "Mon-Fri focused with occasional weekends", PatternCategory::Legacy, || Box::new(ContractorPattern::new())),
+    ]
+}
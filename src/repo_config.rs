@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use github_grid::error::{GitHubGridError, Result};
+
+// Tracked in the target repo so each grid repo carries its own settings
+pub const REPO_CONFIG_FILE: &str = ".github-grid.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepoConfig {
+    pub pattern: Option<String>,
+    pub branch: Option<String>,
+    pub target_total: Option<u32>,
+    // Per-year overrides of target_total, keyed by year
+    #[serde(default)]
+    pub year_targets: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub identity: Identity,
+    // Country codes of built-in holiday tables to skip
+    #[serde(default)]
+    pub holidays: Vec<String>,
+    // Custom holiday table, relative to the repo
+    pub holidays_file: Option<PathBuf>,
+    pub holiday_eve_share: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl RepoConfig {
+    // The repo's config file, or None if it doesn't have one
+    pub fn load(repo_path: &Path) -> Result<Option<Self>> {
+        let path = repo_path.join(REPO_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let config: Self = toml::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| GitHubGridError::Parse(format!("Invalid {}: {}", path.display(), e)))?;
+        for year in config.year_targets.keys() {
+            year.parse::<i32>()
+                .map_err(|_| GitHubGridError::Parse(format!("Invalid year '{}' in {}", year, path.display())))?;
+        }
+        Ok(Some(config))
+    }
+
+    // year_targets in the CLI's YEAR=TOTAL form
+    pub fn year_target_args(&self) -> Vec<String> {
+        self.year_targets.iter().map(|(year, total)| format!("{}={}", year, total)).collect()
+    }
+}
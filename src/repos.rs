@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local, NaiveDate};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use crate::history::{self, config_dir, RunRecord};
+use github_grid::error::{GitHubGridError, Result};
+
+const REPOS_FILE: &str = "repos.toml";
+
+// A repo the tool manages, remembered so `repos list` can report on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedRepo {
+    pub path: String,
+    pub remote: Option<String>,
+    // Where it was registered from: init, fleet or add
+    pub source: String,
+    pub added: DateTime<Local>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ReposFile {
+    #[serde(default)]
+    repo: Vec<ManagedRepo>,
+}
+
+fn repos_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(REPOS_FILE))
+}
+
+pub fn load() -> Result<Vec<ManagedRepo>> {
+    let path = repos_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file: ReposFile = toml::from_str(&fs::read_to_string(&path)?)
+        .map_err(|e| GitHubGridError::Parse(format!("Invalid repos file {}: {}", path.display(), e)))?;
+    Ok(file.repo)
+}
+
+fn save(repos: Vec<ManagedRepo>) -> Result<()> {
+    let path = repos_path()?;
+    fs::create_dir_all(path.parent().unwrap())?;
+    let content = toml::to_string(&ReposFile { repo: repos })
+        .map_err(|e| GitHubGridError::Parse(format!("Failed to serialize repos: {}", e)))?;
+
+    let tmp = path.with_extension("toml.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+// Starts tracking `path` unless it already is. The remote defaults to the
+// repo's origin URL. Returns false if it was already tracked.
+pub fn register(path: &Path, remote: Option<String>, source: &str) -> Result<bool> {
+    let path = history::canonical_repo(&path.display().to_string());
+    let mut repos = load()?;
+    if repos.iter().any(|r| r.path == path) {
+        return Ok(false);
+    }
+
+    let remote = remote.or_else(|| origin_url(Path::new(&path)));
+    repos.push(ManagedRepo { path, remote, source: source.to_string(), added: Local::now() });
+    save(repos)?;
+    Ok(true)
+}
+
+// Stops tracking `path`; the repo itself is left alone
+pub fn unregister(path: &Path) -> Result<()> {
+    let path = history::canonical_repo(&path.display().to_string());
+    let mut repos = load()?;
+    let before = repos.len();
+    repos.retain(|r| r.path != path);
+    if repos.len() == before {
+        return Err(GitHubGridError::Config(format!("{} is not a managed repo", path)));
+    }
+    save(repos)
+}
+
+fn origin_url(path: &Path) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    let remote = repo.find_remote("origin").ok()?;
+    remote.url().map(str::to_string)
+}
+
+// What the run history says about one repo
+struct RepoSummary<'a> {
+    last_run: Option<&'a RunRecord>,
+    coverage: Option<(NaiveDate, NaiveDate)>,
+    created: usize,
+    pushed: usize,
+}
+
+fn summarize<'a>(path: &str, runs: &'a [RunRecord]) -> RepoSummary<'a> {
+    let runs: Vec<&RunRecord> = runs.iter().filter(|r| r.repo.as_deref() == Some(path)).collect();
+    // Only runs whose commits are still in the repo count towards the totals
+    let kept: Vec<&RunRecord> = runs.iter()
+        .copied()
+        .filter(|r| !r.dry_run && !r.rolled_back && r.created > 0)
+        .collect();
+
+    let start = kept.iter().filter_map(|r| r.start).min();
+    let end = kept.iter().filter_map(|r| r.end).max();
+    RepoSummary {
+        last_run: runs.iter().copied().max_by_key(|r| r.finished),
+        coverage: start.zip(end),
+        created: kept.iter().map(|r| r.created).sum(),
+        pushed: kept.iter().map(|r| r.pushed).sum(),
+    }
+}
+
+pub fn show() -> Result<()> {
+    let repos = load()?;
+    if repos.is_empty() {
+        println!("No managed repos yet (run `init`, a fleet, or `repos add PATH`)");
+        return Ok(());
+    }
+    let runs = history::load()?;
+
+    println!("{:<6}  {:<16}  {:<7}  {:<23}  {:>7}  {:>6}  Repo",
+        "Source", "Last run", "Status", "Coverage", "Created", "Pushed");
+    for repo in &repos {
+        let summary = summarize(&repo.path, &runs);
+        let (last_run, status) = match summary.last_run {
+            Some(run) => (
+                run.finished.format("%Y-%m-%d %H:%M").to_string(),
+                if run.dry_run { "dry-run" } else { run.status.as_str() },
+            ),
+            None => ("never".to_string(), "-"),
+        };
+        let coverage = summary.coverage
+            .map_or_else(|| "-".to_string(), |(start, end)| format!("{}..{}", start, end));
+        let missing = if Path::new(&repo.path).exists() { "" } else { " (missing)" };
+
+        println!("{:<6}  {:<16}  {:<7}  {:<23}  {:>7}  {:>6}  {}{}",
+            repo.source, last_run, status, coverage, summary.created, summary.pushed, repo.path, missing);
+        if let Some(remote) = &repo.remote {
+            println!("{:<6}  ↳ {}", "", remote);
+        }
+    }
+    println!("\nRepos file: {}", repos_path()?.display());
+    Ok(())
+}
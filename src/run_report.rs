@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use github_grid::dates;
+use crate::progress::RunStats;
+use crate::readme_stats::{labelled_grid, legend, quartile_levels, quartile_thresholds, LEVELS};
+
+// GitHub's light theme for the four shades, after the empty cell
+const HTML_SHADES: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    // .html and .htm are HTML, anything else markdown
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("html" | "htm") => Self::Html,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+// A finished run as written to --report, for keeping a record of what was
+// generated when
+pub struct RunReport<'a> {
+    pub command: &'a str,
+    pub args: Vec<String>,
+    pub finished: DateTime<Local>,
+    pub stats: &'a RunStats,
+    pub dry_run: bool,
+    pub error: Option<String>,
+}
+
+impl RunReport<'_> {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.markdown(),
+            ReportFormat::Html => self.html(),
+        }
+    }
+
+    // What was created, or what was planned when nothing was committed
+    // (dry runs, and runs that failed first)
+    fn days(&self) -> &BTreeMap<NaiveDate, u32> {
+        match self.stats.created_days.is_empty() {
+            true => &self.stats.planned_days,
+            false => &self.stats.created_days,
+        }
+    }
+
+    fn range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let days = self.days();
+        let start = self.stats.start.or_else(|| days.keys().next().copied())?;
+        let end = self.stats.end.or_else(|| days.keys().next_back().copied())?;
+        Some((start.min(end), end.max(start)))
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        let stats = self.stats;
+        let status = match &self.error {
+            Some(error) => format!("failed: {}", error),
+            None if self.dry_run => "dry run".to_string(),
+            None => "ok".to_string(),
+        };
+        let commits = match (&stats.first_commit, &stats.last_commit) {
+            (Some(first), Some(last)) => format!("{}..{}", short(first), short(last)),
+            _ => "none".to_string(),
+        };
+        vec![
+            ("Command", self.command.to_string()),
+            ("Status", status),
+            ("Finished", self.finished.format("%Y-%m-%d %H:%M:%S %z").to_string()),
+            ("Repository", stats.repo.clone().unwrap_or_else(|| "-".to_string())),
+            ("Pattern", stats.pattern.clone().unwrap_or_else(|| "-".to_string())),
+            ("Range", self.range().map_or("-".to_string(), |(start, end)| format!("{} to {}", start, end))),
+            ("Seed", stats.seed.map_or("none (patterns are seeded by date)".to_string(), |seed| seed.to_string())),
+            ("Planned", stats.planned.to_string()),
+            ("Created", stats.created.to_string()),
+            ("Pushed", stats.pushed.to_string()),
+            ("Base commit", stats.base_commit.as_deref().map_or("-".to_string(), short)),
+            ("Commits", commits),
+            ("Command line", self.args.join(" ")),
+        ]
+    }
+
+    // (year, month) with its commits and active days
+    fn monthly_totals(&self) -> Vec<((i32, u32), u32, usize)> {
+        let mut months: BTreeMap<(i32, u32), (u32, usize)> = BTreeMap::new();
+        for (day, count) in self.days() {
+            let entry = months.entry((day.year(), day.month())).or_default();
+            entry.0 += count;
+            entry.1 += usize::from(*count > 0);
+        }
+        months.into_iter().map(|(month, (commits, active))| (month, commits, active)).collect()
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = format!("# github-grid {} report\n\n", self.command);
+        out.push_str("| Parameter | Value |\n|-----------|-------|\n");
+        for (name, value) in self.parameters() {
+            out.push_str(&format!("| {} | `{}` |\n", name, value.replace('|', "\\|").replace('`', "'")));
+        }
+
+        out.push_str("\n## Monthly totals\n\n");
+        let months = self.monthly_totals();
+        if months.is_empty() {
+            out.push_str("No commits.\n");
+        } else {
+            out.push_str("| Month | Commits | Active days |\n|-------|--------:|------------:|\n");
+            for ((year, month), commits, active) in months {
+                out.push_str(&format!("| {} | {} | {} |\n", month_name(year, month), commits, active));
+            }
+        }
+
+        if let Some((start, end)) = self.range() {
+            let days = self.days();
+            let level = quartile_levels(days, start, end);
+            out.push_str("\n## Grid\n");
+            for (year, segments) in dates::split_by_year(&[(start, end)]) {
+                let (year_start, year_end) = segments[0];
+                out.push_str(&format!("\n### {}\n\n```\n", year));
+                for line in labelled_grid(year_start, year_end, |day| LEVELS[level(days.get(&day).copied().unwrap_or(0))]) {
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                out.push_str("```\n");
+            }
+            out.push_str(&format!("\n{}\n", legend(quartile_thresholds(days, start, end))));
+        }
+
+        out.push_str("\n## Warnings\n\n");
+        if self.stats.warnings.is_empty() {
+            out.push_str("None.\n");
+        }
+        for warning in &self.stats.warnings {
+            out.push_str(&format!("- {}\n", warning));
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let title = format!("github-grid {} report", self.command);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             body {{ font-family: -apple-system, sans-serif; margin: 2em; color: #1f2328; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             .params td, .params th, .months td, .months th {{ border: 1px solid #d0d7de; padding: 4px 8px; text-align: left; }}\n\
+             .months td:not(:first-child) {{ text-align: right; }}\n\
+             .grid {{ border-spacing: 3px; border-collapse: separate; }}\n\
+             .grid td, .swatch {{ width: 10px; height: 10px; padding: 0; border-radius: 2px; }}\n\
+             .swatch {{ display: inline-block; vertical-align: middle; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            title, title,
+        );
+
+        out.push_str("<table class=\"params\">\n");
+        for (name, value) in self.parameters() {
+            out.push_str(&format!("<tr><th>{}</th><td><code>{}</code></td></tr>\n", name, escape(&value)));
+        }
+        out.push_str("</table>\n");
+
+        out.push_str("<h2>Monthly totals</h2>\n");
+        let months = self.monthly_totals();
+        if months.is_empty() {
+            out.push_str("<p>No commits.</p>\n");
+        } else {
+            out.push_str("<table class=\"months\">\n<tr><th>Month</th><th>Commits</th><th>Active days</th></tr>\n");
+            for ((year, month), commits, active) in months {
+                out.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n", month_name(year, month), commits, active));
+            }
+            out.push_str("</table>\n");
+        }
+
+        if let Some((start, end)) = self.range() {
+            let days = self.days();
+            let level = quartile_levels(days, start, end);
+            out.push_str("<h2>Grid</h2>\n");
+            for (year, segments) in dates::split_by_year(&[(start, end)]) {
+                let (year_start, year_end) = segments[0];
+                out.push_str(&format!("<h3>{}</h3>\n<table class=\"grid\">\n", year));
+                // Same layout as the text grid: a row per weekday, a
+                // column per week, blank cells outside the range
+                let first_sunday = year_start - chrono::Duration::days(year_start.weekday().num_days_from_sunday() as i64);
+                for weekday in 0..7 {
+                    out.push_str("<tr>");
+                    let mut day = first_sunday + chrono::Duration::days(weekday);
+                    while day <= year_end {
+                        if day < year_start {
+                            out.push_str("<td></td>");
+                        } else {
+                            let count = days.get(&day).copied().unwrap_or(0);
+                            out.push_str(&format!(
+                                "<td style=\"background:{}\" title=\"{}: {} commits\"></td>",
+                                HTML_SHADES[level(count)], day, count,
+                            ));
+                        }
+                        day += chrono::Duration::days(7);
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+            let mut key = escape(&legend(quartile_thresholds(days, start, end)));
+            for (symbol, shade) in LEVELS.iter().zip(HTML_SHADES) {
+                key = key.replace(*symbol, &format!("<span class=\"swatch\" style=\"background:{}\"></span>", shade));
+            }
+            out.push_str(&format!("<p>{}</p>\n", key));
+        }
+
+        out.push_str("<h2>Warnings</h2>\n");
+        if self.stats.warnings.is_empty() {
+            out.push_str("<p>None.</p>\n");
+        } else {
+            out.push_str("<ul>\n");
+            for warning in &self.stats.warnings {
+                out.push_str(&format!("<li>{}</li>\n", escape(warning)));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn month_name(year: i32, month: u32) -> String {
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap().format("%b %Y").to_string()
+}
+
+fn short(oid: &str) -> String {
+    oid.chars().take(10).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
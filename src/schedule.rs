@@ -0,0 +1,236 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use github_grid::error::{GitHubGridError, Result};
+
+const UNIT_NAME: &str = "github-grid";
+const LAUNCHD_LABEL: &str = "com.github-grid.catch-up";
+
+// Daily run time in 24h HH:MM
+pub struct ScheduleTime {
+    hour: u32,
+    minute: u32,
+}
+
+impl ScheduleTime {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GitHubGridError::Parse(format!("Invalid time '{}', expected HH:MM", value));
+        let (hour, minute) = value.split_once(':').ok_or_else(invalid)?;
+        let hour: u32 = hour.parse().map_err(|_| invalid())?;
+        let minute: u32 = minute.parse().map_err(|_| invalid())?;
+        if hour > 23 || minute > 59 {
+            return Err(invalid());
+        }
+        Ok(Self { hour, minute })
+    }
+}
+
+// Installs a per-user system timer (systemd on Linux, launchd on macOS)
+// that runs the tool once a day with the given arguments.
+pub fn install(args: &[String], at: &ScheduleTime) -> Result<()> {
+    let exe = env::current_exe()?;
+    let mut command = vec![exe.display().to_string()];
+    command.extend(args.iter().cloned());
+
+    if cfg!(target_os = "macos") {
+        install_launchd(&command, at)
+    } else if cfg!(target_os = "linux") {
+        install_systemd(&command, at)
+    } else {
+        Err(unsupported())
+    }
+}
+
+pub fn remove() -> Result<()> {
+    if cfg!(target_os = "macos") {
+        let plist = launchd_plist_path()?;
+        if plist.exists() {
+            run_quietly("launchctl", &["unload", "-w", &plist.display().to_string()]);
+            fs::remove_file(&plist)?;
+        }
+        println!("🗑️  Removed launchd agent {}", LAUNCHD_LABEL);
+        Ok(())
+    } else if cfg!(target_os = "linux") {
+        run_quietly("systemctl", &["--user", "disable", "--now", &format!("{}.timer", UNIT_NAME)]);
+        let dir = systemd_user_dir()?;
+        for file in [format!("{}.service", UNIT_NAME), format!("{}.timer", UNIT_NAME)] {
+            let path = dir.join(file);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        run_quietly("systemctl", &["--user", "daemon-reload"]);
+        println!("🗑️  Removed systemd timer {}.timer", UNIT_NAME);
+        Ok(())
+    } else {
+        Err(unsupported())
+    }
+}
+
+pub fn status() -> Result<()> {
+    let output = if cfg!(target_os = "macos") {
+        if !launchd_plist_path()?.exists() {
+            println!("No schedule installed");
+            return Ok(());
+        }
+        Command::new("launchctl").args(["list", LAUNCHD_LABEL]).output()?
+    } else if cfg!(target_os = "linux") {
+        if !systemd_user_dir()?.join(format!("{}.timer", UNIT_NAME)).exists() {
+            println!("No schedule installed");
+            return Ok(());
+        }
+        Command::new("systemctl")
+            .args(["--user", "list-timers", "--all", &format!("{}.timer", UNIT_NAME)])
+            .output()?
+    } else {
+        return Err(unsupported());
+    };
+
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    Ok(())
+}
+
+fn install_systemd(command: &[String], at: &ScheduleTime) -> Result<()> {
+    let dir = systemd_user_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let exec_start = command.iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    // User units don't inherit the login shell's PATH, which gh and git live on
+    let path = env::var("PATH").unwrap_or_default();
+
+    let service = format!(
+        "[Unit]\nDescription=GitHub Grid daily catch-up\n\n[Service]\nType=oneshot\nEnvironment=\"PATH={}\"\nExecStart={}\n",
+        path, exec_start
+    );
+    let timer = format!(
+        "[Unit]\nDescription=Run GitHub Grid catch-up daily\n\n[Timer]\nOnCalendar=*-*-* {:02}:{:02}:00\nPersistent=true\nRandomizedDelaySec=30m\n\n[Install]\nWantedBy=timers.target\n",
+        at.hour, at.minute
+    );
+
+    fs::write(dir.join(format!("{}.service", UNIT_NAME)), service)?;
+    fs::write(dir.join(format!("{}.timer", UNIT_NAME)), timer)?;
+
+    run_checked("systemctl", &["--user", "daemon-reload"])?;
+    run_checked("systemctl", &["--user", "enable", "--now", &format!("{}.timer", UNIT_NAME)])?;
+
+    println!("✅ Installed systemd timer {}.timer (daily at {:02}:{:02})", UNIT_NAME, at.hour, at.minute);
+    println!("📁 Units: {}", dir.display());
+    Ok(())
+}
+
+fn install_launchd(command: &[String], at: &ScheduleTime) -> Result<()> {
+    let plist_path = launchd_plist_path()?;
+    fs::create_dir_all(plist_path.parent().unwrap())?;
+
+    let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let log_path = format!("{}/Library/Logs/github-grid.log", home_dir);
+    let program_arguments = command.iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect::<String>();
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{args}    </array>
+    <key>EnvironmentVariables</key>
+    <dict>
+        <key>PATH</key>
+        <string>{path}</string>
+    </dict>
+    <key>StartCalendarInterval</key>
+    <dict>
+        <key>Hour</key>
+        <integer>{hour}</integer>
+        <key>Minute</key>
+        <integer>{minute}</integer>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        args = program_arguments,
+        path = xml_escape(&env::var("PATH").unwrap_or_default()),
+        hour = at.hour,
+        minute = at.minute,
+        log = xml_escape(&log_path),
+    );
+
+    // Reinstalling replaces any previously loaded agent
+    if plist_path.exists() {
+        run_quietly("launchctl", &["unload", "-w", &plist_path.display().to_string()]);
+    }
+    fs::write(&plist_path, plist)?;
+    run_checked("launchctl", &["load", "-w", &plist_path.display().to_string()])?;
+
+    println!("✅ Installed launchd agent {} (daily at {:02}:{:02})", LAUNCHD_LABEL, at.hour, at.minute);
+    println!("📁 Plist: {}", plist_path.display());
+    println!("📝 Log: {}", log_path);
+    Ok(())
+}
+
+fn systemd_user_dir() -> Result<PathBuf> {
+    let config_home = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(home_dir()?).join(".config"),
+    };
+    Ok(config_home.join("systemd/user"))
+}
+
+fn launchd_plist_path() -> Result<PathBuf> {
+    Ok(PathBuf::from(home_dir()?).join(format!("Library/LaunchAgents/{}.plist", LAUNCHD_LABEL)))
+}
+
+fn home_dir() -> Result<String> {
+    env::var("HOME").map_err(|_| GitHubGridError::Config("HOME is not set".to_string()))
+}
+
+fn systemd_quote(arg: &str) -> String {
+    if arg.chars().any(|c| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn run_checked(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program).args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitHubGridError::Config(
+            format!("{} {} failed: {}", program, args.join(" "), stderr.trim())
+        ));
+    }
+    Ok(())
+}
+
+// Best-effort cleanup step whose failure (e.g. unit not loaded) is fine
+fn run_quietly(program: &str, args: &[&str]) {
+    let _ = Command::new(program).args(args).output();
+}
+
+fn unsupported() -> GitHubGridError {
+    GitHubGridError::Config(
+        "Scheduling is only supported on Linux (systemd) and macOS (launchd)".to_string()
+    )
+}
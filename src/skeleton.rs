@@ -0,0 +1,94 @@
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// Cargo library crate with integration tests
+    Rust,
+    /// Python package with pytest tests
+    Python,
+    /// Node.js module with node:test tests
+    Node,
+}
+
+// Files for a small but plausible project named after the repo, as
+// (path, content). The CI workflow only runs on pull requests, so the
+// frequent grid pushes don't trigger it.
+pub fn files(template: Template, repo_name: &str) -> Vec<(String, String)> {
+    let ident = repo_name.replace(['-', '.', ' '], "_").to_lowercase();
+    let title = repo_name.replace(['-', '_'], " ");
+
+    let mut files = vec![
+        ("README.md".to_string(), readme(template, &title)),
+        ("docs/usage.md".to_string(), usage(template, &ident)),
+        (".gitignore".to_string(), gitignore(template).to_string()),
+    ];
+    files.extend(match template {
+        Template::Rust => vec![
+            ("Cargo.toml".to_string(), format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n", ident
+            )),
+            ("src/lib.rs".to_string(),
+                "//! Small numeric helpers.\n\n/// Sum of the values, saturating instead of overflowing.\npub fn total(values: &[u32]) -> u32 {\n    values.iter().fold(0u32, |acc, v| acc.saturating_add(*v))\n}\n\n".to_string()),
+            ("tests/total.rs".to_string(), format!(
+                "use {}::total;\n\n#[test]\nfn sums_values() {{\n    assert_eq!(total(&[1, 2, 3]), 6);\n}}\n\n#[test]\nfn saturates() {{\n    assert_eq!(total(&[u32::MAX, 1]), u32::MAX);\n}}\n", ident
+            )),
+            (".github/workflows/ci.yml".to_string(), ci("rust", "dtolnay/rust-toolchain@stable", "", "cargo test")),
+        ],
+        Template::Python => vec![
+            ("pyproject.toml".to_string(), format!(
+                "[project]\nname = \"{}\"\nversion = \"0.1.0\"\nrequires-python = \">=3.10\"\n\n[project.optional-dependencies]\ntest = [\"pytest\"]\n", ident
+            )),
+            (format!("src/{}/__init__.py", ident),
+                "\"\"\"Small numeric helpers.\"\"\"\n\n\ndef total(values):\n    \"\"\"Sum of the values, ignoring None.\"\"\"\n    return sum(v for v in values if v is not None)\n".to_string()),
+            ("tests/test_total.py".to_string(), format!(
+                "from {} import total\n\n\ndef test_sums_values():\n    assert total([1, 2, 3]) == 6\n\n\ndef test_ignores_none():\n    assert total([1, None, 2]) == 3\n", ident
+            )),
+            (".github/workflows/ci.yml".to_string(), ci("python", "actions/setup-python@v5", "\n        with:\n          python-version: \"3.12\"", "pip install -e '.[test]' && pytest")),
+        ],
+        Template::Node => vec![
+            ("package.json".to_string(), format!(
+                "{{\n  \"name\": \"{}\",\n  \"version\": \"0.1.0\",\n  \"type\": \"module\",\n  \"main\": \"src/index.js\",\n  \"scripts\": {{\n    \"test\": \"node --test\"\n  }}\n}}\n", repo_name.to_lowercase()
+            )),
+            ("src/index.js".to_string(),
+                "// Small numeric helpers.\n\n// Sum of the values, ignoring anything that isn't a number.\nexport function total(values) {\n  return values.filter((v) => typeof v === \"number\").reduce((a, b) => a + b, 0);\n}\n\n".to_string()),
+            ("test/total.test.js".to_string(),
+                "import { test } from \"node:test\";\nimport assert from \"node:assert/strict\";\nimport { total } from \"../src/index.js\";\n\ntest(\"sums values\", () => {\n  assert.equal(total([1, 2, 3]), 6);\n});\n\ntest(\"ignores non-numbers\", () => {\n  assert.equal(total([1, \"x\", 2]), 3);\n});\n".to_string()),
+            (".github/workflows/ci.yml".to_string(), ci("node", "actions/setup-node@v4", "\n        with:\n          node-version: 20", "npm test")),
+        ],
+    });
+    files
+}
+
+fn readme(template: Template, title: &str) -> String {
+    let (install, test) = match template {
+        Template::Rust => ("cargo build", "cargo test"),
+        Template::Python => ("pip install -e '.[test]'", "pytest"),
+        Template::Node => ("npm install", "npm test"),
+    };
+    format!(
+        "# {title}\n\nSmall helpers I keep reaching for, collected in one place.\n\n## Development\n\n```bash\n{install}\n{test}\n```\n\nSee [docs/usage.md](docs/usage.md) for examples.\n"
+    )
+}
+
+fn usage(template: Template, ident: &str) -> String {
+    let example = match template {
+        Template::Rust => format!("```rust\nuse {ident}::total;\n\nassert_eq!(total(&[1, 2, 3]), 6);\n```"),
+        Template::Python => format!("```python\nfrom {ident} import total\n\ntotal([1, 2, 3])  # 6\n```"),
+        Template::Node => "```js\nimport { total } from \"./src/index.js\";\n\ntotal([1, 2, 3]); // 6\n```".to_string(),
+    };
+    format!("# Usage\n\n{example}\n")
+}
+
+fn gitignore(template: Template) -> &'static str {
+    match template {
+        Template::Rust => "/target\n",
+        Template::Python => "__pycache__/\n*.egg-info/\n.venv/\n",
+        Template::Node => "node_modules/\n",
+    }
+}
+
+fn ci(job: &str, setup: &str, setup_with: &str, test: &str) -> String {
+    format!(
+        "name: CI\n\non:\n  pull_request:\n  workflow_dispatch:\n\njobs:\n  {job}:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n      - uses: {setup}{setup_with}\n      - run: {test}\n"
+    )
+}
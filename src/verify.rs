@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::NaiveDate;
+use crate::error::{GitHubGridError, Result};
+use crate::forge::Forge;
+use crate::patterns::CommitInfo;
+
+// A day whose live contribution count is below what was pushed
+#[derive(Debug, Clone, Copy)]
+pub struct Discrepancy {
+    pub date: NaiveDate,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+pub fn expected_by_day(commits: &[CommitInfo]) -> BTreeMap<NaiveDate, u32> {
+    let mut days = BTreeMap::new();
+    for commit in commits {
+        *days.entry(commit.date.date_naive()).or_insert(0) += 1;
+    }
+    days
+}
+
+// The calendar also counts contributions from other repos, so a day only
+// disagrees when it shows fewer than we pushed.
+pub fn compare(expected: &BTreeMap<NaiveDate, u32>, calendar: &BTreeMap<NaiveDate, u32>) -> Vec<Discrepancy> {
+    expected.iter()
+        .filter_map(|(date, &expected)| {
+            let actual = calendar.get(date).copied().unwrap_or(0);
+            (actual < expected).then_some(Discrepancy { date: *date, expected, actual })
+        })
+        .collect()
+}
+
+// How far the live graph may trail the expected count before `check`
+// fails: a number of commits, or a share of what was expected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriftThreshold {
+    Commits(u32),
+    Share(f64),
+}
+
+impl DriftThreshold {
+    // "25" or "10%"
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GitHubGridError::Parse(format!("Invalid threshold '{}', expected commits (25) or a percentage (10%)", value));
+        match value.trim().strip_suffix('%') {
+            Some(percent) => {
+                let percent: f64 = percent.trim().parse().map_err(|_| invalid())?;
+                if !(0.0..=100.0).contains(&percent) {
+                    return Err(invalid());
+                }
+                Ok(Self::Share(percent / 100.0))
+            }
+            None => value.trim().parse().map(Self::Commits).map_err(|_| invalid()),
+        }
+    }
+
+    pub fn allowed(&self, expected: u32) -> u32 {
+        match self {
+            Self::Commits(commits) => *commits,
+            Self::Share(share) => (expected as f64 * share).floor() as u32,
+        }
+    }
+}
+
+// How many of a run's commits made it through each stage, from the plan
+// to the contribution graph. Stages that weren't reached or checked (no
+// push, no verification) are None.
+#[derive(Debug, Clone, Copy)]
+pub struct Reconciliation {
+    pub planned: usize,
+    pub created: usize,
+    pub on_branch: usize,
+    pub pushed: Option<usize>,
+    pub attributed: Option<usize>,
+}
+
+impl Reconciliation {
+    // Each checked stage with its count and how many fell off since the
+    // stage before
+    pub fn stages(&self) -> Vec<(&'static str, usize, usize)> {
+        let stages = [
+            ("Planned", Some(self.planned)),
+            ("Created", Some(self.created)),
+            ("On branch", Some(self.on_branch)),
+            ("Pushed", self.pushed),
+            ("On the graph", self.attributed),
+        ];
+        let mut previous = self.planned;
+        stages.into_iter()
+            .filter_map(|(name, count)| count.map(|count| (name, count)))
+            .map(|(name, count)| {
+                let lost = previous.saturating_sub(count);
+                previous = count;
+                (name, count, lost)
+            })
+            .collect()
+    }
+
+    pub fn has_drop_off(&self) -> bool {
+        self.stages().iter().any(|(_, _, lost)| *lost > 0)
+    }
+}
+
+// Commits from `expected` that the calendar accounts for, counting each day
+// at most up to what was pushed
+pub fn attributed(expected: &BTreeMap<NaiveDate, u32>, discrepancies: &[Discrepancy]) -> usize {
+    let missing: u32 = discrepancies.iter().map(|d| d.expected - d.actual).sum();
+    (expected.values().sum::<u32>() - missing) as usize
+}
+
+// What polling the graph ended with
+#[derive(Debug, Clone, Default)]
+pub struct CalendarCheck {
+    pub discrepancies: Vec<Discrepancy>,
+    // Account emails, read alongside the first poll for explaining misses
+    pub emails: Option<Vec<String>>,
+}
+
+// Polls the live contribution calendar until every expected day shows up
+// or `timeout` elapses, returning whatever still disagrees at the end.
+// `on_attempt` receives the attempt number and remaining discrepancy count.
+pub fn wait_for_calendar(
+    client: &dyn Forge,
+    expected: &BTreeMap<NaiveDate, u32>,
+    timeout: Duration,
+    interval: Duration,
+    mut on_attempt: impl FnMut(u32, usize),
+) -> Result<CalendarCheck> {
+    let (Some(&from), Some(&to)) = (expected.keys().next(), expected.keys().next_back()) else {
+        return Ok(CalendarCheck::default());
+    };
+
+    let deadline = Instant::now() + timeout;
+    let snapshot = client.account_snapshot(from, to)?;
+    let mut calendar = snapshot.calendar;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let discrepancies = compare(expected, &calendar);
+        on_attempt(attempt, discrepancies.len());
+
+        let now = Instant::now();
+        if discrepancies.is_empty() || now >= deadline {
+            return Ok(CalendarCheck { discrepancies, emails: snapshot.emails });
+        }
+        thread::sleep(interval.min(deadline - now));
+        calendar = client.contribution_calendar(from, to)?;
+    }
+}
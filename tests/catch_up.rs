@@ -0,0 +1,38 @@
+use std::process::Command;
+use chrono::{Duration, Local};
+use git2::Repository;
+use github_grid::CommitInfo;
+use github_grid::testing::TestRepo;
+
+#[test]
+fn catch_up_with_met_target_creates_nothing() {
+    let mut repo = TestRepo::new().unwrap();
+    // Yesterday's run already met this year's target of one commit, unless
+    // today is Jan 1 and it landed last year
+    let now = Local::now();
+    let yesterday = now - Duration::days(1);
+    let date = if yesterday.format("%Y").to_string() == now.format("%Y").to_string() {
+        yesterday
+    } else {
+        now.date_naive().and_hms_opt(0, 0, 1).unwrap().and_local_timezone(Local).unwrap()
+    };
+    repo.git_ops()
+        .create_commit(&CommitInfo { date, message: "[AutoGen] Existing work".to_string(), utc_offset: None })
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_github-grid"))
+        .args(["--offline", "--no-fetch", "--no-push", "--yes", "--target-total", "1", "--repo"])
+        .arg(repo.path())
+        .arg("catch-up")
+        .env("XDG_CONFIG_HOME", repo.dir().join("config"))
+        .env("HOME", repo.dir())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+
+    let git = Repository::open(repo.path()).unwrap();
+    let mut history = git.revwalk().unwrap();
+    history.push_head().unwrap();
+    assert_eq!(history.count(), 1, "{}", stdout);
+}